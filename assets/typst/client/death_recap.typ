@@ -0,0 +1,72 @@
+#import "../monokai_pro.typ": *
+#import "../utils.typ": *
+
+#let damage_row(entry, max_damage) = {
+  stack(
+    dir: ttb,
+    spacing: 0.3em,
+    [
+      #text(fill: base7)[#entry.name]
+      #h(1fr)
+      #text(fill: red)[#calc.round(entry.damage)]
+    ],
+    box(width: 100%, height: 0.3em, radius: 0.15em, fill: base3)[
+      #box(
+        width: entry.damage / max_damage * 100%,
+        height: 100%,
+        radius: 0.15em,
+        fill: red,
+      )
+    ],
+  )
+}
+
+#let main(killer, damages, dummy_update) = {
+  box(width: 100%, height: 100%)[
+    #place(bottom + center, dy: -4em)[
+      #box(
+        width: 22em,
+        inset: 1em,
+        radius: 0.5em,
+        fill: base1.transparentize(20%),
+      )[
+        #set text(fill: base7)
+
+        #if killer != none [
+          Destroyed by #text(fill: red)[*#killer.name*]
+          #if "spaceship" in killer [
+            #text(fill: base6)[(#killer.spaceship)]
+          ]
+
+          #if "health" in killer {
+            box(width: 100%, height: 0.5em, radius: 0.25em, fill: base3)[
+              #box(
+                width: calc.clamp(killer.health, 0.0, 1.0) * 100%,
+                height: 100%,
+                radius: 0.25em,
+                fill: green,
+              )
+            ]
+            text(fill: base6, size: 0.8em)[
+              Remaining health: #calc.round(killer.health * 100)%
+            ]
+          }
+        ] else [
+          Destroyed by the environment
+        ]
+
+        #if damages.len() > 0 {
+          line(length: 100%, stroke: base4)
+          text(fill: base6, size: 0.8em)[Damage taken]
+
+          let max_damage = calc.max(..damages.map(d => d.damage))
+          stack(
+            dir: ttb,
+            spacing: 0.6em,
+            ..damages.map(d => damage_row(d, max_damage)),
+          )
+        }
+      ]
+    ]
+  ]
+}
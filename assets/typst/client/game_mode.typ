@@ -18,7 +18,17 @@
         [
           = Sandbox
           #linebreak()
-          Sandbox mode with a mini tutorial contained.
+          Free play in a sandbox.
+        ],
+        dummy_update,
+      ),
+      (
+        <btn:tutorial>,
+        blue,
+        [
+          = Tutorial
+          #linebreak()
+          Learn the basics step by step!
         ],
         dummy_update,
       ),
@@ -52,6 +62,16 @@
         ],
         dummy_update,
       ),
+      (
+        <btn:spectate>,
+        green,
+        [
+          = Spectate
+          #linebreak()
+          Watch an ongoing match!
+        ],
+        dummy_update,
+      ),
     )
 
     #let section_times = calculate_section_time(
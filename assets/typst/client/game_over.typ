@@ -7,6 +7,7 @@
   local_team_index,
   team_names,
   team_scores,
+  server_shutdown,
 ) = {
   set text(fill: base7, size: 24pt)
 
@@ -56,6 +57,10 @@
           ..team_scores.map(it => [#it])
         )
 
+        #if server_shutdown [
+          #text(fill: yellow, size: 18pt)[The server is shutting down for maintenance.]
+        ]
+
         #align(right)[
           #text(fill: purple)[
             #button(lbl: <btn:main-menu>, inters: interactions())[=== Main Menu]
@@ -5,6 +5,10 @@
   curr_player_count,
   max_player_count,
   room_id,
+  vote_maps,
+  voted_map,
+  map_name,
+  loading_progress,
   dummy_update,
 ) = {
   box(width: 100%, height: 100%, inset: 2em)[
@@ -12,6 +16,33 @@
 
     #place(center + top)[
       = Waiting for players (#curr_player_count/#max_player_count)
+
+      #if vote_maps.len() > 0 [
+        Vote for the map
+
+        #for (i, name) in vote_maps.enumerate() [
+          #text(fill: if voted_map == i { green } else { base7 }, size: 0.8em)[
+            #button(
+              lbl: label("btn:vote-" + str(i)),
+              inters: interactions(),
+            )[#name]
+          ]
+        ]
+      ]
+
+      #if map_name != none [
+        #text(size: 0.9em)[Map: #map_name]
+      ]
+
+      #if loading_progress != none {
+        text(fill: base6, size: 0.8em)[
+          #if loading_progress < 1.0 [
+            Loading map... #calc.round(loading_progress * 100)%
+          ] else [
+            Map loaded, waiting for other players...
+          ]
+        ]
+      }
     ]
 
     #place(bottom + right, dy: 1.6em)[
@@ -18,6 +18,11 @@
         move(dx: 15em)[
           #rotate(data.rotation * 1rad)[
             #text(fill: color)[#calc.trunc(data.dist) m]
+            // The objective has been announced but is not active yet.
+            #if data.countdown > 0 [
+              \
+              #text(fill: color)[in #calc.ceil(data.countdown) s]
+            ]
           ]
           #image(bytes(raw_svg), height: 2.5em)
         ],
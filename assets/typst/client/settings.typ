@@ -1,7 +1,40 @@
 #import "../monokai_pro.typ": *
 #import "../utils.typ": *
 
-#let settings(bgm_volume, vfx_volume, dummy_update) = {
+#let network_row(lbl_decrease, lbl_increase, body) = {
+  stack(
+    dir: ltr,
+    spacing: 0.25em,
+    scale(75%)[
+      #settings_button(
+        fill: blue.transparentize(50%),
+        lbl: lbl_decrease,
+        inters: interactions(),
+      )[#text(fill: blue.lighten(20%), size: 1.75em)[= −]]
+    ],
+    box(width: 6em, height: 1.75em)[
+      #align(center + horizon)[#text(fill: blue.lighten(20%), size: 0.8em)[#body]]
+    ],
+    scale(75%)[
+      #settings_button(
+        fill: blue.transparentize(50%),
+        lbl: lbl_increase,
+        inters: interactions(),
+      )[#text(fill: blue.lighten(20%), size: 1.75em)[= +]]
+    ],
+  )
+}
+
+#let settings(
+  bgm_volume,
+  vfx_volume,
+  input_delay_ticks,
+  adaptive_delay,
+  correction_factor,
+  rtt_ms,
+  jitter_ms,
+  dummy_update,
+) = {
   box(width: 100%, height: 100%, inset: (top: 7.25em, right: 2em))[
     #place(top + right)[
       #stack(
@@ -78,7 +111,31 @@
           )[#text(fill: yellow.lighten(20%), size: 1.75em)[= +]]
         ],
       )
-      
+
+      // Network
+      #network_row(
+        <btn:decrease_input_delay>,
+        <btn:increase_input_delay>,
+      )[Delay: #input_delay_ticks ticks]
+      #network_row(
+        <btn:decrease_correction>,
+        <btn:increase_correction>,
+      )[Correction: #calc.round(correction_factor, digits: 2)x]
+
+      #align(right)[
+        #set text(size: 0.7em)
+        #scale(100%)[
+          #button(
+            lbl: <btn:toggle_adaptive_delay>,
+            inters: interactions(),
+            fill: if adaptive_delay { green } else { base6 },
+          )[== #if adaptive_delay [Adaptive Delay: On] else [Adaptive Delay: Off]]
+        ]
+        #text(fill: base7, size: 0.8em)[
+          RTT: #calc.round(rtt_ms) ms | Jitter: #calc.round(jitter_ms) ms
+        ]
+      ]
+
       #align(right)[
         #set text(size: 0.7em, fill: red)
         #scale(100%)[
@@ -0,0 +1,70 @@
+#import "../monokai_pro.typ": *
+#import "../utils.typ": *
+
+#let player_row(player, col) = {
+  let fill = if player.followed {
+    col.darken(60%).transparentize(20%)
+  } else {
+    base1.transparentize(40%)
+  }
+
+  box(width: 14em, inset: 0.5em, radius: 0.3em, fill: fill)[
+    #set text(
+      fill: if player.dead {
+        base5
+      } else {
+        base7
+      },
+    )
+    #stack(
+      dir: ttb,
+      spacing: 0.4em,
+      [
+        #text(fill: col)[#player.name]
+        #h(1fr)
+        #if player.dead [Dead] else [#player.lumina]
+      ],
+      box(width: 100%, height: 0.3em, radius: 0.15em, fill: base3)[
+        #box(
+          width: calc.clamp(player.health, 0.0, 1.0) * 100%,
+          height: 100%,
+          radius: 0.15em,
+          fill: col,
+        )
+      ],
+    )
+  ]
+}
+
+#let team_panel(players, is_team_a, col) = {
+  stack(
+    dir: ttb,
+    spacing: 0.5em,
+    ..players.filter(p => p.is_team_a == is_team_a).map(p => player_row(p, col)),
+  )
+}
+
+#let spectator(players, target, dummy_update) = {
+  box(width: 100%, height: 100%, inset: 2em)[
+    #set text(size: 0.8em)
+
+    #place(left + horizon)[#team_panel(players, true, blue)]
+    #place(right + horizon)[#team_panel(players, false, red)]
+
+    #place(center + top, dy: 3em)[
+      #text(fill: base7, size: 1.2em)[
+        #if target != none [
+          Spectating: *#target*
+        ] else [
+          Free roam
+        ]
+      ]
+    ]
+
+    #place(center + bottom)[
+      #text(fill: base6)[
+        [Q]/[E] cycle players #h(1em) [Space] free roam #h(1em) [WASD] move
+      ]
+    ]
+  ]
+}
@@ -0,0 +1,77 @@
+#import "../monokai_pro.typ": *
+#import "../utils.typ": *
+
+// Must be in the same order as `TutorialStep`.
+#let steps = (
+  ([Move], [Use *WASD* or the left stick to move around.]),
+  ([Boost], [Hold *Shift*, *right click* or the left trigger to boost.]),
+  ([Dash], [Press *Space* or the south button to dash.]),
+  ([Shoot ore], [Aim and *left click* or use the right trigger to shoot an ore.]),
+  ([Collect lumina], [Fly over the lumina dropped by ores to collect them.]),
+  ([Deposit], [Fly to the tesseract and press *E* or the west button to deposit.]),
+  ([Ability], [Press *Q* or the east button to use your ability.]),
+)
+
+#let tutorial(step, completed, dummy_update) = {
+  box(width: 100%, height: 100%, inset: 2em)[
+    #set text(fill: base7)
+
+    #place(left + horizon)[
+      #box(
+        width: 20em,
+        inset: 1em,
+        radius: 0.5em,
+        fill: base1.transparentize(20%),
+      )[
+        = Tutorial
+
+        #stack(
+          dir: ttb,
+          spacing: 0.6em,
+          ..steps
+            .enumerate()
+            .map(it => {
+              let (i, (title, _)) = it
+              let done = i < step or (i == step and completed)
+
+              let (icon, fill) = if done {
+                ("✓", green)
+              } else if i == step {
+                ("▶", yellow)
+              } else {
+                ("○", base5)
+              }
+
+              text(fill: fill)[#icon #title]
+            }),
+        )
+      ]
+    ]
+
+    #place(bottom + center, dy: -2em)[
+      #set text(size: 1.2em)
+      #if step < steps.len() {
+        let (title, instruction) = steps.at(step)
+        box(
+          inset: 1em,
+          radius: 0.5em,
+          fill: base1.transparentize(20%),
+        )[
+          #text(fill: if completed { green } else { yellow })[*#title*]
+          #h(1em)
+          #instruction
+        ]
+      } else {
+        box(
+          inset: 1em,
+          radius: 0.5em,
+          fill: base1.transparentize(20%),
+        )[
+          #text(fill: green)[*Tutorial complete!*]
+          #h(1em)
+          You are ready for a real match, exit to play.
+        ]
+      }
+    ]
+  ]
+}
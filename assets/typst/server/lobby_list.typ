@@ -1,10 +1,14 @@
 #import "../monokai_pro.typ": *
 
-#let lobby_list(lobbies) = [
+#let lobby_list(lobbies, draining) = [
   #box(width: 100%, height: 100%, inset: (x: 4.6%, y: 8%))[
     #set text(fill: base7, size: 24pt)
     = Lobbies
 
+    #if draining != none [
+      #text(fill: red)[Draining, games end in #calc.round(draining)s]
+    ]
+
     #for (i, lobby) in lobbies.enumerate() [
       == Lobby \##i
       Player count: #lobby
@@ -11,6 +11,7 @@ use lumina_common::prelude::*;
 use lumina_shared::prelude::*;
 
 use crate::camera::GameCamera;
+use crate::game::pickup::PickupCollected;
 use crate::player::LocalPlayerId;
 use crate::screens::Screen;
 
@@ -56,6 +57,7 @@ impl Plugin for AudioPlugin {
             .observe(init_audio_receiver)
             .observe(fire_ammo)
             .observe(ammo_hit)
+            .observe(pickup_collected)
             .observe(cleanup_removed_instances);
     }
 }
@@ -144,6 +146,28 @@ fn ammo_hit(
     ));
 }
 
+fn pickup_collected(
+    trigger: Trigger<PickupCollected>,
+    mut commands: Commands,
+    sound_fx: Res<SoundFx>,
+    channel: Res<AudioChannel<SoundFx>>,
+    mut emitter_pool: ResMut<EmitterPool>,
+) {
+    let position = trigger.event().position;
+    let entity = emitter_pool.get_unused_or_spawn(|| commands.spawn_empty().id());
+
+    commands.entity(entity).insert((
+        TransformBundle::from_transform(Transform::from_xyz(position.x, position.y, 0.0)),
+        AudioEmitter {
+            instances: vec![channel
+                .play(sound_fx.collect_item.clone_weak())
+                // Let the spatial audio system decide the volume.
+                .with_volume(0.0)
+                .handle()],
+        },
+    ));
+}
+
 /// Change spaceship audio pitch based on its [`LinearVelocity`].
 fn spaceship_velocity_pitch(
     q_spaceships: Query<(&LinearVelocity, &Spaceship, &Handle<AudioInstance>), With<SourceEntity>>,
@@ -320,6 +344,7 @@ AudioChannelTracks!(
         cannon_shot: "audio/weapon/cannon-shot.ogg",
         gattling_shot: "audio/weapon/gattling-shot.ogg",
         kill: "audio/sfx/kill.ogg",
+        collect_item: "audio/CollectItem.ogg",
         target_down: "audio/streak/target_down.ogg",
         double_down: "audio/streak/double_down.ogg",
         on_fire: "audio/streak/on_fire.ogg",
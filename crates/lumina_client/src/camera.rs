@@ -16,6 +16,7 @@ use bevy_post_process::vignette::VignetteConfig;
 use bevy_radiance_cascades::prelude::*;
 use bevy_radiance_cascades::radiance_cascades::RadianceCascadesTextures;
 use leafwing_input_manager::prelude::*;
+use lightyear::prelude::client::ConnectionManager;
 use lumina_common::prelude::*;
 use lumina_shared::prelude::*;
 use lumina_ui::prelude::*;
@@ -23,7 +24,9 @@ use lumina_vfx::prelude::*;
 use noisy_bevy::simplex_noise_2d_seeded;
 
 use crate::player::aim::IsUsingMouse;
+use crate::player::death::{is_death_cam_active, DeathRecap};
 use crate::player::LocalPlayerId;
+use crate::screens::Screen;
 
 use super::player::LocalPlayerInfo;
 
@@ -42,11 +45,17 @@ impl Plugin for CameraPlugin {
             Update,
             (
                 health_effect,
-                follow_spaceship,
+                follow_spaceship.run_if(not(is_death_cam_active)),
+                death_cam.run_if(is_death_cam_active),
                 camera_zoom,
                 spaceship_velocity_zoom,
                 main_window_zoom.run_if(resource_changed::<MainWindowFunc>),
                 propagate_component::<NoRadiance>,
+                send_camera_view.run_if(
+                    in_state(Screen::InGame)
+                        .or_else(in_state(Screen::Sandbox))
+                        .or_else(in_state(Screen::Tutorial)),
+                ),
             ),
         )
         .add_systems(PreUpdate, restore_camera_shake)
@@ -301,6 +310,38 @@ fn follow_spaceship(
         .lerp(target_position, follow_factor);
 }
 
+/// Pan towards the killer of the local spaceship.
+fn death_cam(
+    mut q_camera: Query<&mut Transform, With<GameCamera>>,
+    q_spaceship_transforms: Query<&GlobalTransform, (With<Spaceship>, With<SourceEntity>)>,
+    player_infos: Res<PlayerInfos>,
+    recap: Res<DeathRecap>,
+    time: Res<Time>,
+) {
+    const FOLLOW_FACTOR: f32 = 4.0;
+
+    let Some(killer_translation) = recap
+        .killer
+        .and_then(|killer| player_infos[PlayerInfoType::Spaceship].get(&killer))
+        .and_then(|e| q_spaceship_transforms.get(*e).ok())
+        .map(|t| t.translation())
+    else {
+        return;
+    };
+
+    let mut camera_transform = q_camera.single_mut();
+
+    // Clamp within 1.0 to prevent overshooting
+    let follow_factor = f32::min(1.0, FOLLOW_FACTOR * time.delta_seconds());
+    let target_position = killer_translation
+        .xy()
+        .extend(camera_transform.translation.z);
+
+    camera_transform.translation = camera_transform
+        .translation
+        .lerp(target_position, follow_factor);
+}
+
 fn spaceship_velocity_zoom(
     q_spaceships: Query<(&LinearVelocity, &Spaceship), With<SourceEntity>>,
     mut camera_zoom: ResMut<CameraZoom>,
@@ -351,6 +392,35 @@ fn camera_zoom(
     projection.scale = camera_zoom.zoom * camera_zoom.zoom_mutliplier;
 }
 
+/// Send the area seen by the [`GameCamera`] to the server for interest management.
+fn send_camera_view(
+    q_camera: Query<(&Transform, &OrthographicProjection), With<GameCamera>>,
+    mut connection_manager: ResMut<ConnectionManager>,
+    time: Res<Time>,
+    mut send_cooldown: Local<f32>,
+) {
+    const SEND_INTERVAL: f32 = 0.25;
+
+    *send_cooldown -= time.delta_seconds();
+    if *send_cooldown > 0.0 {
+        return;
+    }
+
+    let Ok((transform, projection)) = q_camera.get_single() else {
+        return;
+    };
+
+    // The projection area is already scaled by the zoom.
+    let view = CameraView {
+        center: transform.translation.xy() + projection.area.center(),
+        half_extents: projection.area.half_size(),
+    };
+
+    // Sent repeatedly as the channel is unreliable.
+    let _ = connection_manager.send_message::<SeqUnreliableChannel, _>(&view);
+    *send_cooldown = SEND_INTERVAL;
+}
+
 fn restore_camera_shake(
     mut q_cameras: Query<&mut Transform, With<GameCamera>>,
     mut shake: ResMut<CameraShake>,
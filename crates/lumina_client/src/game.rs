@@ -1,18 +1,33 @@
 use bevy::prelude::*;
 use client::*;
 use lightyear::prelude::*;
+use lumina_common::prelude::*;
 use lumina_shared::game::prelude::*;
 use lumina_shared::prelude::*;
+use lumina_shared::tuning::{ActiveTuning, TuningUpdate};
+use lumina_terrain::prelude::*;
 
 use crate::effector::*;
+use crate::screens::Screen;
 
 mod ore_vfx;
+pub mod pickup;
 
 pub(super) struct GamePugin;
 
 impl Plugin for GamePugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(ore_vfx::OreVfxPlugin)
+        app.add_plugins((ore_vfx::OreVfxPlugin, pickup::PickupPlugin))
+            .init_resource::<PendingTileDestruction>()
+            .add_systems(OnEnter(Screen::LocalLobby), clear_pending_tiles)
+            .add_systems(
+                Update,
+                (
+                    destroy_replicated_tiles,
+                    receive_tuning,
+                    replay_neutral_shots,
+                ),
+            )
             .observe(teleport_player)
             .observe(disable_teleporter)
             .observe(enable_teleporter)
@@ -41,6 +56,28 @@ fn teleport_player(
         .remove::<InteractedEffector>();
 }
 
+/// Replay the shots of neutral turrets fired on the server.
+fn replay_neutral_shots(
+    mut commands: Commands,
+    q_turrets: Query<
+        (&NeutralShot, &GlobalTransform, Entity),
+        (Changed<NeutralShot>, With<AmmoStat>, With<SourceEntity>),
+    >,
+) {
+    for (shot, transform, entity) in q_turrets.iter() {
+        // Nothing has been fired yet.
+        if shot.count == 0 {
+            continue;
+        }
+
+        commands.trigger(FireAmmo {
+            weapon_entity: entity,
+            position: transform.translation().xy(),
+            direction: shot.direction,
+        });
+    }
+}
+
 /// Disable teleporter during cooldown.
 fn disable_teleporter(trigger: Trigger<OnAdd, TeleporterCooldown>, mut commands: Commands) {
     let effector_entity = trigger.entity();
@@ -73,3 +110,54 @@ fn deposit_tesseract(
         .entity(effector_entity)
         .remove::<InteractedEffector>();
 }
+
+/// Destroy tiles of the local terrain based on the [`TerrainDestruction`] sent by the server.
+fn destroy_replicated_tiles(
+    q_maps: Query<Entity, (With<ProceduralArena>, With<TerrainStates>)>,
+    mut evr_destruction: EventReader<MessageEvent<TerrainDestruction>>,
+    mut evw_destroy: EventWriter<DestroyTiles>,
+    mut pending: ResMut<PendingTileDestruction>,
+) {
+    for destruction in evr_destruction.read() {
+        pending.extend(
+            destruction
+                .message()
+                .tiles
+                .iter()
+                .map(|&[x, y]| UVec2::new(x as u32, y as u32)),
+        );
+    }
+
+    // Wait for the terrain to be generated (e.g. when spectating a game in progress).
+    if pending.is_empty() || q_maps.is_empty() {
+        return;
+    }
+
+    let tiles = std::mem::take(&mut **pending);
+    // Clients only have one map at a time.
+    for entity in q_maps.iter() {
+        evw_destroy.send(DestroyTiles {
+            entity,
+            tiles: tiles.clone(),
+        });
+    }
+}
+
+fn clear_pending_tiles(mut pending: ResMut<PendingTileDestruction>) {
+    pending.clear();
+}
+
+/// Apply the gameplay tuning pushed by the server.
+fn receive_tuning(
+    mut evr_tuning: EventReader<MessageEvent<TuningUpdate>>,
+    mut active_tuning: ResMut<ActiveTuning>,
+) {
+    for tuning in evr_tuning.read() {
+        info!("Received gameplay tuning from server.");
+        **active_tuning = tuning.message().0.clone();
+    }
+}
+
+/// Destroyed tiles received before the terrain is generated.
+#[derive(Resource, Default, Deref, DerefMut)]
+struct PendingTileDestruction(Vec<UVec2>);
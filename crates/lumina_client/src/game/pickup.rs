@@ -0,0 +1,48 @@
+use bevy::prelude::*;
+use lumina_common::prelude::*;
+use lumina_shared::game::prelude::*;
+
+pub(super) struct PickupPlugin;
+
+impl Plugin for PickupPlugin {
+    fn build(&self, app: &mut App) {
+        app.observe(pickup_collected).observe(pickup_respawned);
+    }
+}
+
+/// Hide collected pickups and trigger [`PickupCollected`].
+fn pickup_collected(
+    trigger: Trigger<OnAdd, PickupCooldown>,
+    mut commands: Commands,
+    q_pickups: Query<(&Pickup, &GlobalTransform), With<SourceEntity>>,
+) {
+    let entity = trigger.entity();
+    let Ok((pickup, transform)) = q_pickups.get(entity) else {
+        return;
+    };
+
+    commands.entity(entity).insert(Visibility::Hidden);
+    commands.trigger(PickupCollected {
+        pickup_type: pickup.pickup_type,
+        position: transform.translation().xy(),
+    });
+}
+
+/// Show pickups again when their cooldown is complete.
+fn pickup_respawned(
+    trigger: Trigger<OnRemove, PickupCooldown>,
+    mut commands: Commands,
+    q_pickups: Query<(), (With<Pickup>, With<SourceEntity>)>,
+) {
+    let entity = trigger.entity();
+    if q_pickups.contains(entity) {
+        commands.entity(entity).insert(Visibility::Inherited);
+    }
+}
+
+/// Triggered when a pickup is collected by any player, used for vfx and audio.
+#[derive(Event)]
+pub struct PickupCollected {
+    pub pickup_type: PickupType,
+    pub position: Vec2,
+}
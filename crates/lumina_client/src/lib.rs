@@ -15,9 +15,11 @@ mod blueprints;
 mod camera;
 mod effector;
 mod game;
+mod network;
 mod player;
 mod screens;
 mod source_entity;
+mod spectator;
 mod typ_animation;
 mod type_registry;
 mod ui;
@@ -52,6 +54,8 @@ impl Plugin for ClientPlugin {
             effector::EffectorPlugin,
             screens::ScreensPlugins,
             game::GamePugin,
+            network::NetworkPlugin,
+            spectator::SpectatorPlugin,
             typ_animation::TypAnimationPlugin::<MainWindowFunc>::default(),
         ))
         .init_state::<Connection>()
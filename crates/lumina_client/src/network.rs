@@ -0,0 +1,131 @@
+//! Runtime tuning of the prediction input delay and correction.
+use bevy::prelude::*;
+use bevy::utils::Duration;
+use client::*;
+use lightyear::prelude::*;
+use lumina_common::settings::{AdaptiveInputDelay, ClientSettings, LuminaSettings};
+
+use super::Connection;
+
+pub(super) struct NetworkPlugin;
+
+impl Plugin for NetworkPlugin {
+    fn build(&self, app: &mut App) {
+        let settings = app.world().get_resource::<LuminaSettings>().unwrap();
+        let tuning = NetworkTuning::from_settings(settings);
+
+        app.insert_resource(tuning)
+            .init_resource::<NetworkStats>()
+            .add_systems(
+                Update,
+                (
+                    (update_network_stats, adapt_input_delay)
+                        .chain()
+                        .run_if(in_state(Connection::Connected)),
+                    apply_network_tuning.run_if(resource_changed::<NetworkTuning>),
+                ),
+            );
+    }
+}
+
+/// Cache the measured round trip time and jitter from the [`ConnectionManager`].
+fn update_network_stats(
+    connection_manager: Res<ConnectionManager>,
+    mut stats: ResMut<NetworkStats>,
+) {
+    stats.rtt = connection_manager.rtt();
+    stats.jitter = connection_manager.jitter();
+}
+
+/// Move [`NetworkTuning::input_delay_ticks`] towards the target computed
+/// from [`NetworkStats`] (at most once every [`AdaptiveInputDelay::adjust_interval`]).
+fn adapt_input_delay(
+    mut tuning: ResMut<NetworkTuning>,
+    stats: Res<NetworkStats>,
+    settings: Res<LuminaSettings>,
+    time: Res<Time>,
+    mut adjust_cooldown: Local<f32>,
+) {
+    *adjust_cooldown -= time.delta_seconds();
+
+    let Some(adaptive) = tuning.adaptive else {
+        return;
+    };
+
+    if *adjust_cooldown > 0.0 {
+        return;
+    }
+
+    let tick_duration = Duration::from_secs_f64(1.0 / settings.fixed_timestep_hz);
+    let target_ticks = adaptive.target_ticks(stats.rtt, stats.jitter, tick_duration);
+
+    // Step 1 tick at a time to avoid sudden jumps in input latency.
+    let input_delay_ticks = match tuning.input_delay_ticks.cmp(&target_ticks) {
+        std::cmp::Ordering::Less => tuning.input_delay_ticks + 1,
+        std::cmp::Ordering::Greater => tuning.input_delay_ticks - 1,
+        std::cmp::Ordering::Equal => return,
+    };
+
+    debug!(
+        "Adapting input delay to {input_delay_ticks} ticks (rtt: {:?}, jitter: {:?})",
+        stats.rtt, stats.jitter
+    );
+    tuning.input_delay_ticks = input_delay_ticks;
+    *adjust_cooldown = adaptive.adjust_interval;
+}
+
+/// Apply [`NetworkTuning`] onto the live [`ClientConfig`] without reconnecting.
+fn apply_network_tuning(tuning: Res<NetworkTuning>, mut config: ResMut<ClientConfig>) {
+    config.prediction.minimum_input_delay_ticks = tuning.input_delay_ticks;
+    config.prediction.correction_ticks_factor = tuning.correction_ticks_factor;
+}
+
+/// Live prediction settings, initialized from the [`LuminaSettings`].
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct NetworkTuning {
+    pub input_delay_ticks: u16,
+    pub correction_ticks_factor: f32,
+    /// Adaptive input delay bounds, [`None`] if the input delay is manually set.
+    pub adaptive: Option<AdaptiveInputDelay>,
+    /// The adaptive input delay from the settings file, used to toggle adaptation back on.
+    default_adaptive: Option<AdaptiveInputDelay>,
+}
+
+impl NetworkTuning {
+    /// Smallest allowed correction ticks factor.
+    pub const MIN_CORRECTION_FACTOR: f32 = 0.0;
+    /// Largest allowed correction ticks factor.
+    pub const MAX_CORRECTION_FACTOR: f32 = 4.0;
+
+    pub fn from_settings(settings: &LuminaSettings) -> Self {
+        Self {
+            input_delay_ticks: settings.client.input_delay_ticks,
+            correction_ticks_factor: settings.client.correction_ticks_factor,
+            adaptive: settings.client.adaptive_input_delay,
+            default_adaptive: settings.client.adaptive_input_delay,
+        }
+    }
+
+    /// Maximum input delay that can be set manually.
+    pub fn max_input_delay_ticks(&self) -> u16 {
+        self.default_adaptive
+            .map(|a| a.max_ticks)
+            .unwrap_or(ClientSettings::MAX_MANUAL_INPUT_DELAY_TICKS)
+    }
+
+    /// Toggle between adaptive and manual input delay.
+    /// Adaptation can only be turned on if it is configured in the settings.
+    pub fn toggle_adaptive(&mut self) {
+        self.adaptive = match self.adaptive {
+            Some(_) => None,
+            None => self.default_adaptive,
+        };
+    }
+}
+
+/// Network conditions measured by the [`ConnectionManager`].
+#[derive(Resource, Default, Debug, Clone, Copy)]
+pub struct NetworkStats {
+    pub rtt: Duration,
+    pub jitter: Duration,
+}
@@ -10,6 +10,7 @@ use super::Connection;
 
 pub mod aim;
 mod ammo;
+pub(super) mod death;
 mod kda;
 mod spaceship;
 mod weapon;
@@ -23,6 +24,7 @@ impl Plugin for PlayerPlugin {
             spaceship::SpaceshipPlugin,
             weapon::WeaponPlugin,
             ammo::AmmoPlugin,
+            death::DeathPlugin,
         ));
 
         app.init_resource::<LocalPlayerId>()
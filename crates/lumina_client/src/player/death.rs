@@ -0,0 +1,299 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use client::*;
+use lightyear::prelude::*;
+use lumina_common::prelude::*;
+use lumina_shared::prelude::*;
+
+use crate::screens::Screen;
+
+use super::{LocalPlayerId, LocalPlayerInfo};
+
+/// Duration of damage history kept in the [`DamageLedger`] (in seconds).
+const LEDGER_DURATION: f32 = 5.0;
+/// Duration that the camera stays on the killer (in seconds).
+const DEATH_CAM_DURATION: f32 = 3.0;
+
+pub(super) struct DeathPlugin;
+
+impl Plugin for DeathPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DamageLedger>()
+            .add_systems(OnEnter(Screen::InGame), reset_death)
+            .add_systems(OnEnter(Screen::LocalLobby), reset_death)
+            .add_systems(
+                Update,
+                (
+                    prune_damage_ledger,
+                    receive_killed_by,
+                    start_death_recap,
+                    tick_death_recap,
+                )
+                    .chain()
+                    .run_if(in_state(Screen::InGame)),
+            )
+            .observe(record_damage);
+    }
+}
+
+/// Record damage taken by the local spaceship.
+fn record_damage(
+    trigger: Trigger<AmmoHit>,
+    local_player_id: Res<LocalPlayerId>,
+    mut ledger: ResMut<DamageLedger>,
+    time: Res<Time>,
+) {
+    let ammo_hit = trigger.event();
+
+    if ammo_hit.hit_player_id == Some(**local_player_id) {
+        ledger.record(
+            time.elapsed_seconds(),
+            ammo_hit.origin_player_id,
+            ammo_hit.damage,
+        );
+    }
+}
+
+fn prune_damage_ledger(mut ledger: ResMut<DamageLedger>, time: Res<Time>) {
+    ledger.prune(time.elapsed_seconds() - LEDGER_DURATION);
+}
+
+/// Use the killer confirmed by the server as the last ammo hit
+/// is not always what destroyed the local spaceship (e.g. mines and hazards).
+fn receive_killed_by(
+    mut evr_killed_by: EventReader<MessageEvent<KilledBy>>,
+    mut ledger: ResMut<DamageLedger>,
+    recap: Option<ResMut<DeathRecap>>,
+) {
+    let Some(killed_by) = evr_killed_by.read().last() else {
+        return;
+    };
+    let killer_id = killed_by.message().killer_id;
+
+    // The message can arrive before or after the death is replicated.
+    match recap {
+        Some(mut recap) => recap.killer = killer_id,
+        None => ledger.killed_by = Some(killer_id),
+    }
+}
+
+/// Snapshot the [`DamageLedger`] into a [`DeathRecap`] when the local spaceship dies.
+fn start_death_recap(
+    mut commands: Commands,
+    q_dead: Query<(), (Added<Dead>, With<Spaceship>, With<SourceEntity>)>,
+    local_player_info: LocalPlayerInfo,
+    mut ledger: ResMut<DamageLedger>,
+) {
+    let Some(entity) = local_player_info.get(PlayerInfoType::Spaceship) else {
+        return;
+    };
+
+    if q_dead.contains(entity) == false {
+        return;
+    }
+
+    commands.insert_resource(DeathRecap {
+        killer: ledger.killer(),
+        damages: ledger.damage_by_source(),
+        timer: Timer::from_seconds(DEATH_CAM_DURATION, TimerMode::Once),
+    });
+    ledger.clear();
+}
+
+/// Remove the [`DeathRecap`] once the local spaceship respawns.
+fn tick_death_recap(
+    mut commands: Commands,
+    q_dead: Query<(), (With<Dead>, With<Spaceship>, With<SourceEntity>)>,
+    local_player_info: LocalPlayerInfo,
+    recap: Option<ResMut<DeathRecap>>,
+    time: Res<Time>,
+) {
+    let Some(mut recap) = recap else {
+        return;
+    };
+
+    let is_dead = local_player_info
+        .get(PlayerInfoType::Spaceship)
+        .is_some_and(|e| q_dead.contains(e));
+
+    if is_dead {
+        recap.timer.tick(time.delta());
+    } else {
+        commands.remove_resource::<DeathRecap>();
+    }
+}
+
+fn reset_death(mut commands: Commands, mut ledger: ResMut<DamageLedger>) {
+    ledger.clear();
+    commands.remove_resource::<DeathRecap>();
+}
+
+/// Run condition for panning the camera towards the killer.
+pub fn is_death_cam_active(recap: Option<Res<DeathRecap>>) -> bool {
+    recap.is_some_and(|recap| recap.killer.is_some() && recap.timer.finished() == false)
+}
+
+/// Recent damage taken by the local spaceship, built from [`AmmoHit`].
+#[derive(Resource, Default, Debug)]
+pub struct DamageLedger {
+    entries: VecDeque<DamageEntry>,
+    /// The killer confirmed by the server, see [`KilledBy`].
+    killed_by: Option<Option<PlayerId>>,
+}
+
+impl DamageLedger {
+    pub fn record(&mut self, time: f32, source: Option<PlayerId>, damage: f32) {
+        self.entries.push_back(DamageEntry {
+            time,
+            source,
+            damage,
+        });
+    }
+
+    /// Remove entries older than `min_time`.
+    pub fn prune(&mut self, min_time: f32) {
+        while self
+            .entries
+            .front()
+            .is_some_and(|entry| entry.time < min_time)
+        {
+            self.entries.pop_front();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.killed_by = None;
+    }
+
+    /// The source of the most recent damage, [`None`] for neutral entities.
+    pub fn last_attacker(&self) -> Option<PlayerId> {
+        self.entries.back().and_then(|entry| entry.source)
+    }
+
+    /// The killer confirmed by the server if received, the last attacker otherwise.
+    pub fn killer(&self) -> Option<PlayerId> {
+        self.killed_by.unwrap_or_else(|| self.last_attacker())
+    }
+
+    /// Total damage per source, sorted from the highest damage.
+    pub fn damage_by_source(&self) -> Vec<(Option<PlayerId>, f32)> {
+        let mut damages = HashMap::<Option<PlayerId>, f32>::default();
+        for entry in self.entries.iter() {
+            *damages.entry(entry.source).or_default() += entry.damage;
+        }
+
+        let mut damages = damages.into_iter().collect::<Vec<_>>();
+        damages.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        damages
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct DamageEntry {
+    /// Elapsed time when the damage is taken (in seconds).
+    time: f32,
+    /// The player that dealt the damage, [`None`] for neutral entities.
+    source: Option<PlayerId>,
+    damage: f32,
+}
+
+/// Information about the latest death of the local spaceship.
+/// Only exists while the local spaceship is dead.
+#[derive(Resource, Debug, Clone)]
+pub struct DeathRecap {
+    /// The player that dealt the final blow, [`None`] if killed by neutral entities.
+    pub killer: Option<PlayerId>,
+    /// Damage taken before death per source, see [`DamageLedger::damage_by_source`].
+    pub damages: Vec<(Option<PlayerId>, f32)>,
+    /// Duration of the death cam.
+    pub timer: Timer,
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::utils::Duration;
+
+    use super::*;
+
+    const A: PlayerId = PlayerId(ClientId::Netcode(1));
+    const B: PlayerId = PlayerId(ClientId::Netcode(2));
+
+    #[test]
+    fn damage_by_source_is_sorted() {
+        let mut ledger = DamageLedger::default();
+        ledger.record(0.0, Some(A), 10.0);
+        ledger.record(0.5, None, 15.0);
+        ledger.record(1.0, Some(B), 30.0);
+        ledger.record(1.5, Some(A), 10.0);
+
+        assert_eq!(
+            ledger.damage_by_source(),
+            vec![(Some(B), 30.0), (Some(A), 20.0), (None, 15.0)]
+        );
+        assert_eq!(ledger.last_attacker(), Some(A));
+    }
+
+    #[test]
+    fn prune_old_entries() {
+        let mut ledger = DamageLedger::default();
+        ledger.record(0.0, Some(A), 10.0);
+        ledger.record(2.0, Some(B), 10.0);
+        ledger.record(4.0, None, 10.0);
+
+        ledger.prune(1.0);
+        assert_eq!(ledger.damage_by_source().len(), 2);
+
+        ledger.prune(5.0);
+        assert!(ledger.damage_by_source().is_empty());
+        assert_eq!(ledger.last_attacker(), None);
+    }
+
+    #[test]
+    fn killer_confirmed_by_server() {
+        let mut ledger = DamageLedger::default();
+        ledger.record(0.0, Some(A), 10.0);
+        assert_eq!(ledger.killer(), Some(A));
+
+        // Killed by a mine or a hazard after being hit by A.
+        ledger.killed_by = Some(None);
+        assert_eq!(ledger.killer(), None);
+
+        ledger.killed_by = Some(Some(B));
+        assert_eq!(ledger.killer(), Some(B));
+
+        ledger.clear();
+        assert_eq!(ledger.killer(), None);
+        assert!(ledger.damage_by_source().is_empty());
+    }
+
+    #[test]
+    fn death_cam_only_follows_players() {
+        let mut recap = DeathRecap {
+            killer: Some(A),
+            damages: Vec::new(),
+            timer: Timer::from_seconds(DEATH_CAM_DURATION, TimerMode::Once),
+        };
+
+        let mut world = World::new();
+        world.insert_resource(recap.clone());
+        assert!(world.run_system_once(is_death_cam_active));
+
+        recap
+            .timer
+            .tick(Duration::from_secs_f32(DEATH_CAM_DURATION));
+        world.insert_resource(recap.clone());
+        assert!(world.run_system_once(is_death_cam_active) == false);
+
+        recap.killer = None;
+        recap.timer.reset();
+        world.insert_resource(recap);
+        assert!(world.run_system_once(is_death_cam_active) == false);
+
+        world.remove_resource::<DeathRecap>();
+        assert!(world.run_system_once(is_death_cam_active) == false);
+    }
+}
@@ -1,25 +1,61 @@
 use bevy::prelude::*;
+use client::*;
+use lightyear::prelude::*;
+use lumina_shared::prelude::*;
 
 pub(super) mod in_game;
 pub(super) mod local_lobby;
 pub(super) mod matchmaking;
 pub(super) mod multiplayer_lobby;
 pub(super) mod sandbox;
+pub(super) mod tutorial;
 
 pub struct ScreensPlugins;
 
 impl Plugin for ScreensPlugins {
     fn build(&self, app: &mut App) {
-        app.init_state::<Screen>().add_plugins((
-            local_lobby::LocalLobbyPlugin,
-            sandbox::SandboxPlugin,
-            matchmaking::MatchmakingPlugin,
-            multiplayer_lobby::MultiplayerLobbyPlugin,
-            in_game::InGamePlugin,
-        ));
+        app.init_state::<Screen>()
+            .init_resource::<ServerShutdownNotice>()
+            .add_plugins((
+                local_lobby::LocalLobbyPlugin,
+                sandbox::SandboxPlugin,
+                tutorial::TutorialPlugin,
+                matchmaking::MatchmakingPlugin,
+                multiplayer_lobby::MultiplayerLobbyPlugin,
+                in_game::InGamePlugin,
+            ))
+            .add_systems(PreUpdate, handle_server_shutdown.after(MainSet::Receive));
     }
 }
 
+/// Leave screens whose lobby is ended by the server when it starts shutting down.
+/// In-progress games continue until the server ends them.
+fn handle_server_shutdown(
+    mut evr_shutdown: EventReader<MessageEvent<ServerShuttingDown>>,
+    mut notice: ResMut<ServerShutdownNotice>,
+    screen: Res<State<Screen>>,
+    mut next_screen_state: ResMut<NextState<Screen>>,
+) {
+    for shutdown in evr_shutdown.read() {
+        warn!(
+            "Server is shutting down, games will end in {}s.",
+            shutdown.message().remaining
+        );
+        **notice = true;
+
+        if matches!(
+            screen.get(),
+            Screen::Matchmaking | Screen::MultiplayerLobby | Screen::Sandbox | Screen::Tutorial
+        ) {
+            next_screen_state.set(Screen::MainMenu);
+        }
+    }
+}
+
+/// True if the server notified that it is shutting down.
+#[derive(Resource, Default, Debug, Deref, DerefMut)]
+pub struct ServerShutdownNotice(pub bool);
+
 #[derive(States, Debug, Hash, PartialEq, Eq, Clone, Default)]
 pub enum Screen {
     // #[default]
@@ -28,12 +64,13 @@ pub enum Screen {
     MainMenu,
     LocalLobby,
     Sandbox,
+    /// Scripted walkthrough on top of the sandbox.
+    Tutorial,
     Matchmaking,
     MultiplayerLobby,
     InGame,
     GameOver,
     // Leaderboard,
-    // Tutorial,
     // Credits,
     // Loading,
 }
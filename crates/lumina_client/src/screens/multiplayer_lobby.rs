@@ -1,8 +1,10 @@
+use bevy::asset::LoadState;
 use bevy::prelude::*;
 use blenvy::*;
 use client::*;
 use lightyear::prelude::*;
 use lumina_common::prelude::*;
+use lumina_shared::game::prelude::*;
 use lumina_shared::prelude::*;
 use lumina_ui::prelude::*;
 
@@ -14,9 +16,13 @@ impl Plugin for MultiplayerLobbyPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(Screen::MultiplayerLobby), spawn_lobby)
             .add_systems(OnExit(Screen::MultiplayerLobby), despawn_lobby)
+            .add_systems(OnEnter(Screen::LocalLobby), remove_map_preload)
+            .add_systems(OnEnter(Screen::MainMenu), remove_map_preload)
             .add_systems(
                 Update,
-                start_game.run_if(in_state(Screen::MultiplayerLobby)),
+                (preload_map, track_map_preload, start_game)
+                    .chain()
+                    .run_if(in_state(Screen::MultiplayerLobby)),
             );
     }
 }
@@ -27,13 +33,85 @@ fn start_game(
     mut evr_start_game: EventReader<MessageEvent<StartGame>>,
     mut next_screen_state: ResMut<NextState<Screen>>,
 ) {
-    for _ in evr_start_game.read() {
+    for start_game in evr_start_game.read() {
+        let start_game = start_game.message();
+        info!("Starting game on {}.", start_game.map_name);
+
         // Spawn map and move in to in game screen.
-        commands.spawn((MapType::AbandonedFactory.info(), SpawnBlueprint, InGameMap));
+        start_game
+            .map
+            .spawn(&mut commands)
+            .insert((InGameMap, WorldIdx::default()));
         next_screen_state.set(Screen::InGame);
     }
 }
 
+/// Start loading the map assets as soon as the server selects the map.
+fn preload_map(
+    mut commands: Commands,
+    mut evr_preload_map: EventReader<MessageEvent<PreloadMap>>,
+    asset_server: Res<AssetServer>,
+) {
+    for preload_map in evr_preload_map.read() {
+        let preload_map = preload_map.message();
+        info!("Preloading {}.", preload_map.map_name);
+
+        // Procedural and terrain maps have no blueprint to load.
+        let handle = match &preload_map.map {
+            GameMap::Blueprint { path } => {
+                Some(asset_server.load(BlueprintPreload::meta_path(path)))
+            }
+            GameMap::Procedural { .. } | GameMap::Terrain { .. } => None,
+        };
+
+        commands.insert_resource(MapPreload {
+            handle,
+            progress: 0.0,
+            reported: false,
+        });
+    }
+}
+
+/// Update the [`MapPreload`] progress and notify the server once it is done.
+fn track_map_preload(
+    preload: Option<ResMut<MapPreload>>,
+    preload_assets: Res<Assets<BlueprintPreload>>,
+    asset_server: Res<AssetServer>,
+    mut connection_manager: ResMut<ConnectionManager>,
+) {
+    let Some(mut preload) = preload else {
+        return;
+    };
+    if preload.reported {
+        return;
+    }
+
+    let progress = match &preload.handle {
+        Some(handle) => match preload_assets.get(handle) {
+            Some(assets) => assets.progress(&asset_server),
+            // Do not hold the game back on a missing meta file.
+            None if matches!(asset_server.load_state(handle), LoadState::Failed(_)) => 1.0,
+            None => 0.0,
+        },
+        None => 1.0,
+    };
+
+    if preload.progress != progress {
+        preload.progress = progress;
+    }
+
+    if progress >= 1.0 {
+        info!("Map preloaded.");
+        let _ = connection_manager.send_message::<OrdReliableChannel, _>(&MapLoaded);
+        preload.reported = true;
+    }
+}
+
+/// Release the preloaded map assets after leaving the lobby or the game.
+fn remove_map_preload(mut commands: Commands) {
+    commands.remove_resource::<MapPreload>();
+}
+
 /// Spawn lobby scene.
 fn spawn_lobby(mut commands: Commands, mut evr_transparency: EventWriter<MainWindowTransparency>) {
     commands.spawn((
@@ -50,6 +128,16 @@ fn despawn_lobby(mut commands: Commands, q_lobby: Query<Entity, With<Multiplayer
     commands.entity(lobby).despawn_recursive();
 }
 
+/// Assets of the map to be played, kept alive until the client leaves the game.
+#[derive(Resource)]
+pub struct MapPreload {
+    handle: Option<Handle<BlueprintPreload>>,
+    /// Fraction of the map assets loaded.
+    pub progress: f32,
+    /// True once [`MapLoaded`] has been sent to the server.
+    reported: bool,
+}
+
 /// Tag for the parent entity of the lobby scene.
 #[derive(Component)]
 pub(super) struct MultiplayerLobby;
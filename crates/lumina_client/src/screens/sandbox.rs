@@ -16,9 +16,11 @@ impl Plugin for SandboxPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             Update,
-            handle_enter_sandbox.run_if(in_state(Screen::Sandbox)),
+            handle_enter_sandbox
+                .run_if(in_state(Screen::Sandbox).or_else(in_state(Screen::Tutorial))),
         )
-        .add_systems(OnExit(Screen::Sandbox), cleanup_sandbox);
+        .add_systems(OnExit(Screen::Sandbox), cleanup_sandbox)
+        .add_systems(OnExit(Screen::Tutorial), cleanup_sandbox);
     }
 }
 
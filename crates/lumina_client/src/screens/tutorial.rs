@@ -0,0 +1,273 @@
+use bevy::prelude::*;
+use bevy::utils::Duration;
+use lumina_common::prelude::*;
+use lumina_shared::prelude::*;
+
+use crate::player::{CachedGameStat, LocalPlayerInfo};
+
+use super::Screen;
+
+/// Delay before moving on to the next step (in seconds).
+const STEP_DELAY: f32 = 1.5;
+
+pub(super) struct TutorialPlugin;
+
+impl Plugin for TutorialPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Tutorial>()
+            .add_systems(OnEnter(Screen::Tutorial), reset_tutorial)
+            .add_systems(
+                Update,
+                (detect_step_completion, advance_step)
+                    .chain()
+                    .run_if(in_state(Screen::Tutorial)),
+            );
+    }
+}
+
+fn reset_tutorial(mut tutorial: ResMut<Tutorial>) {
+    *tutorial = Tutorial::default();
+}
+
+/// Complete the current [`TutorialStep`] based on the local spaceship's state.
+fn detect_step_completion(
+    q_spaceships: Query<(&SpaceshipAction, &CollectedLumina), With<SourceEntity>>,
+    q_ores: Query<(&Health, &MaxHealth), (Changed<Health>, With<OreType>, With<SourceEntity>)>,
+    local_player_info: LocalPlayerInfo,
+    game_stat: Res<CachedGameStat>,
+    mut tutorial: ResMut<Tutorial>,
+) {
+    let Some((action, collected_lumina)) = local_player_info
+        .get(PlayerInfoType::Spaceship)
+        .and_then(|e| q_spaceships.get(e).ok())
+    else {
+        return;
+    };
+
+    tutorial.check_step(&TutorialInput {
+        moving: action.movement_direction.is_some(),
+        boosting: action.is_boosting,
+        dashing: action.is_dash,
+        ore_damaged: q_ores
+            .iter()
+            .any(|(health, max_health)| **health < **max_health),
+        collected_lumina: collected_lumina.0 > 0,
+        game_score: game_stat.game_score.map(|game_score| game_score.score),
+        ability: action.is_ability,
+    });
+}
+
+/// Move on to the next [`TutorialStep`] after [`STEP_DELAY`].
+fn advance_step(mut tutorial: ResMut<Tutorial>, time: Res<Time>) {
+    tutorial.tick(time.delta());
+}
+
+/// Progress of the tutorial.
+#[derive(Resource, Default, Debug)]
+pub struct Tutorial {
+    pub step: TutorialStep,
+    /// Exists when the current step is completed.
+    step_timer: Option<Timer>,
+    /// Game score before the [`TutorialStep::Deposit`] step.
+    initial_score: Option<u8>,
+}
+
+impl Tutorial {
+    /// True if the current step is completed and waiting for the next step.
+    pub fn completed(&self) -> bool {
+        self.step_timer.is_some()
+    }
+
+    /// Complete the current step if the `input` satisfies it.
+    fn check_step(&mut self, input: &TutorialInput) {
+        if self.completed() {
+            return;
+        }
+
+        let completed = match self.step {
+            TutorialStep::Move => input.moving,
+            TutorialStep::Boost => input.boosting,
+            TutorialStep::Dash => input.dashing,
+            TutorialStep::ShootOre => input.ore_damaged,
+            TutorialStep::CollectLumina => input.collected_lumina,
+            // The sandbox only has the local player, any score change is a deposit.
+            TutorialStep::Deposit => input.game_score != self.initial_score,
+            TutorialStep::Ability => input.ability,
+            TutorialStep::Finished => false,
+        };
+
+        if completed {
+            self.step_timer = Some(Timer::from_seconds(STEP_DELAY, TimerMode::Once));
+        } else if self.step != TutorialStep::Deposit {
+            // Track score changes only when the deposit step starts.
+            self.initial_score = input.game_score;
+        }
+    }
+
+    fn tick(&mut self, delta: Duration) {
+        let Some(timer) = self.step_timer.as_mut() else {
+            return;
+        };
+
+        if timer.tick(delta).finished() {
+            self.step = self.step.next();
+            self.step_timer = None;
+        }
+    }
+}
+
+/// State of the local spaceship used to complete a [`TutorialStep`].
+#[derive(Default, Debug, Clone, Copy)]
+struct TutorialInput {
+    moving: bool,
+    boosting: bool,
+    dashing: bool,
+    /// An ore in the sandbox has been damaged.
+    ore_damaged: bool,
+    collected_lumina: bool,
+    game_score: Option<u8>,
+    ability: bool,
+}
+
+/// Steps of the tutorial in order.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TutorialStep {
+    #[default]
+    Move,
+    Boost,
+    Dash,
+    ShootOre,
+    CollectLumina,
+    Deposit,
+    Ability,
+    Finished,
+}
+
+impl TutorialStep {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Move => Self::Boost,
+            Self::Boost => Self::Dash,
+            Self::Dash => Self::ShootOre,
+            Self::ShootOre => Self::CollectLumina,
+            Self::CollectLumina => Self::Deposit,
+            Self::Deposit => Self::Ability,
+            Self::Ability | Self::Finished => Self::Finished,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sandbox starting score, see `GameScore::new(50)` on the server.
+    const SCORE: Option<u8> = Some(50);
+
+    fn input() -> TutorialInput {
+        TutorialInput {
+            game_score: SCORE,
+            ..default()
+        }
+    }
+
+    fn complete(tutorial: &mut Tutorial, input: TutorialInput) {
+        tutorial.check_step(&input);
+        assert!(tutorial.completed(), "{:?} is not completed", tutorial.step);
+
+        // Nothing happens until the delay is over.
+        let step = tutorial.step;
+        tutorial.tick(Duration::from_secs_f32(STEP_DELAY * 0.5));
+        assert_eq!(tutorial.step, step);
+
+        tutorial.tick(Duration::from_secs_f32(STEP_DELAY * 0.5));
+        assert_eq!(tutorial.step, step.next());
+        assert!(tutorial.completed() == false);
+    }
+
+    #[test]
+    fn complete_every_step() {
+        let mut tutorial = Tutorial::default();
+        tutorial.check_step(&input());
+
+        let steps = [
+            TutorialInput {
+                moving: true,
+                ..input()
+            },
+            TutorialInput {
+                boosting: true,
+                ..input()
+            },
+            TutorialInput {
+                dashing: true,
+                ..input()
+            },
+            TutorialInput {
+                ore_damaged: true,
+                ..input()
+            },
+            TutorialInput {
+                collected_lumina: true,
+                ..input()
+            },
+            // Deposit the collected lumina.
+            TutorialInput {
+                game_score: Some(52),
+                ..input()
+            },
+            TutorialInput {
+                ability: true,
+                ..input()
+            },
+        ];
+
+        for input in steps {
+            complete(&mut tutorial, input);
+        }
+        assert_eq!(tutorial.step, TutorialStep::Finished);
+
+        tutorial.check_step(&TutorialInput {
+            moving: true,
+            ability: true,
+            ..input()
+        });
+        assert!(tutorial.completed() == false);
+    }
+
+    #[test]
+    fn deposit_needs_score_change() {
+        let mut tutorial = Tutorial {
+            step: TutorialStep::CollectLumina,
+            ..default()
+        };
+        tutorial.check_step(&input());
+        complete(
+            &mut tutorial,
+            TutorialInput {
+                collected_lumina: true,
+                ..input()
+            },
+        );
+        assert_eq!(tutorial.step, TutorialStep::Deposit);
+
+        // Other actions do not complete the deposit.
+        tutorial.check_step(&TutorialInput {
+            moving: true,
+            collected_lumina: true,
+            ability: true,
+            ..input()
+        });
+        assert!(tutorial.completed() == false);
+
+        // The opposite team scoring also moves the score.
+        complete(
+            &mut tutorial,
+            TutorialInput {
+                game_score: Some(48),
+                ..input()
+            },
+        );
+        assert_eq!(tutorial.step, TutorialStep::Ability);
+    }
+}
@@ -3,6 +3,7 @@ use client::*;
 use leafwing_input_manager::prelude::*;
 use lightyear::prelude::*;
 use lumina_common::prelude::*;
+use lumina_shared::game::prelude::*;
 use lumina_shared::prelude::*;
 
 pub(super) struct SourceEntityPlugin;
@@ -13,6 +14,8 @@ impl Plugin for SourceEntityPlugin {
             .set_source::<Weapon, With<Predicted>>()
             .set_source::<ActionState<PlayerAction>, With<Predicted>>()
             .set_source::<OreType, With<Predicted>>()
-            .set_source::<LuminaType, With<Predicted>>();
+            .set_source::<LuminaType, With<Predicted>>()
+            .set_source::<Neutral, With<Predicted>>()
+            .set_source::<Pickup, With<Predicted>>();
     }
 }
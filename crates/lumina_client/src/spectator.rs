@@ -0,0 +1,224 @@
+//! Watch a game without a spaceship, following any player or roaming freely.
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+use lumina_common::prelude::*;
+use lumina_shared::prelude::*;
+use lumina_ui::prelude::*;
+use velyst::prelude::*;
+use velyst::typst::foundations::{dict, Dict};
+
+use crate::camera::GameCamera;
+use crate::screens::Screen;
+use crate::ui::game_ui::get_name;
+
+pub(super) struct SpectatorPlugin;
+
+impl Plugin for SpectatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(InputManagerPlugin::<SpectatorAction>::default())
+            .init_resource::<Spectator>()
+            .init_resource::<ActionState<SpectatorAction>>()
+            .insert_resource(SpectatorAction::input_map())
+            .register_typst_asset::<SpectatorUi>()
+            .compile_typst_func::<SpectatorUi, SpectatorFunc>()
+            .push_to_main_window::<SpectatorUi, SpectatorFunc, _>(
+                MainWindowSet::Default,
+                in_state(Screen::InGame).and_then(is_spectating),
+            )
+            .init_resource::<SpectatorFunc>()
+            .add_systems(OnEnter(Screen::LocalLobby), reset_spectator)
+            .add_systems(OnEnter(Screen::MainMenu), reset_spectator)
+            .add_systems(
+                Update,
+                (cycle_spectator_target, spectator_camera, update_overview)
+                    .chain()
+                    .run_if(in_state(Screen::InGame).and_then(is_spectating)),
+            );
+    }
+}
+
+pub(super) fn is_spectating(spectator: Res<Spectator>) -> bool {
+    spectator.active
+}
+
+fn reset_spectator(mut spectator: ResMut<Spectator>) {
+    *spectator = Spectator::default();
+}
+
+/// Cycle the followed player or switch to free-roam.
+fn cycle_spectator_target(
+    action: Res<ActionState<SpectatorAction>>,
+    player_infos: Res<PlayerInfos>,
+    mut spectator: ResMut<Spectator>,
+) {
+    let mut players = player_infos[PlayerInfoType::Spaceship]
+        .keys()
+        .copied()
+        .collect::<Vec<_>>();
+    players.sort_by_key(|id| id.to_bits());
+
+    // Stop following players that left.
+    if spectator
+        .target
+        .is_some_and(|target| players.contains(&target) == false)
+    {
+        spectator.target = None;
+    }
+
+    if action.just_pressed(&SpectatorAction::FreeRoam) {
+        spectator.target = None;
+        return;
+    }
+
+    let step = match (
+        action.just_pressed(&SpectatorAction::Next),
+        action.just_pressed(&SpectatorAction::Previous),
+    ) {
+        (true, false) => 1,
+        (false, true) => players.len().saturating_sub(1),
+        _ => return,
+    };
+
+    if players.is_empty() {
+        return;
+    }
+
+    let index = match spectator
+        .target
+        .and_then(|target| players.iter().position(|id| *id == target))
+    {
+        Some(index) => (index + step) % players.len(),
+        // Start from the first player when free-roaming.
+        None => 0,
+    };
+    spectator.target = Some(players[index]);
+}
+
+/// Follow the targeted spaceship, move with the [`SpectatorAction::Move`] axis otherwise.
+fn spectator_camera(
+    mut q_camera: Query<(&mut Transform, &OrthographicProjection), With<GameCamera>>,
+    q_spaceships: Query<&GlobalTransform, (With<Spaceship>, With<SourceEntity>)>,
+    action: Res<ActionState<SpectatorAction>>,
+    player_infos: Res<PlayerInfos>,
+    spectator: Res<Spectator>,
+    time: Res<Time>,
+) {
+    const FOLLOW_FACTOR: f32 = 10.0;
+    const FREE_ROAM_SPEED: f32 = 800.0;
+
+    let Ok((mut camera_transform, projection)) = q_camera.get_single_mut() else {
+        return;
+    };
+
+    let target_translation = spectator
+        .target
+        .and_then(|target| player_infos[PlayerInfoType::Spaceship].get(&target))
+        .and_then(|e| q_spaceships.get(*e).ok())
+        .map(|transform| transform.translation());
+
+    match target_translation {
+        Some(translation) => {
+            // Clamp within 1.0 to prevent overshooting
+            let follow_factor = f32::min(1.0, FOLLOW_FACTOR * time.delta_seconds());
+            let target = translation.xy().extend(camera_transform.translation.z);
+
+            camera_transform.translation = camera_transform.translation.lerp(target, follow_factor);
+        }
+        None => {
+            let direction = action
+                .clamped_axis_pair(&SpectatorAction::Move)
+                .map(|axis| axis.xy())
+                .unwrap_or_default();
+            // Move faster when zoomed out.
+            let velocity = direction * FREE_ROAM_SPEED * projection.scale;
+
+            camera_transform.translation += (velocity * time.delta_seconds()).extend(0.0);
+        }
+    }
+}
+
+/// Update the overview of both teams.
+fn update_overview(
+    q_spaceships: Query<
+        (
+            &PlayerId,
+            &TeamType,
+            &Health,
+            &MaxHealth,
+            Option<&CollectedLumina>,
+            Has<Dead>,
+        ),
+        (With<Spaceship>, With<SourceEntity>),
+    >,
+    spectator: Res<Spectator>,
+    mut func: ResMut<SpectatorFunc>,
+) {
+    let mut players = q_spaceships.iter().collect::<Vec<_>>();
+    players.sort_by_key(|(id, ..)| id.to_bits());
+
+    func.players.clear();
+    for (id, team_type, health, max_health, collected_lumina, dead) in players {
+        func.players.push(dict! {
+            "name" => get_name(id),
+            "is_team_a" => *team_type == TeamType::A,
+            "health" => (**health / **max_health) as f64,
+            "lumina" => collected_lumina.map(|lumina| lumina.0).unwrap_or_default() as i64,
+            "dead" => dead,
+            "followed" => spectator.target == Some(*id),
+        });
+    }
+
+    func.target = spectator.target.as_ref().map(|id| get_name(id).to_string());
+    func.dummy_update = func.dummy_update.wrapping_add(1);
+}
+
+/// Spectating state of the local client.
+#[derive(Resource, Default, Debug)]
+pub(super) struct Spectator {
+    /// True if the local client watches the game without a spaceship.
+    pub active: bool,
+    /// The followed player, free-roam if [`None`].
+    pub target: Option<PlayerId>,
+}
+
+#[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug, Reflect)]
+pub enum SpectatorAction {
+    Move,
+    Next,
+    Previous,
+    FreeRoam,
+}
+
+impl SpectatorAction {
+    /// Define the default bindings to the input
+    pub fn input_map() -> InputMap<Self> {
+        let mut input_map = InputMap::default();
+
+        // Gamepad input bindings
+        input_map.insert(Self::Move, DualAxis::left_stick());
+        input_map.insert(Self::Next, GamepadButtonType::RightTrigger);
+        input_map.insert(Self::Previous, GamepadButtonType::LeftTrigger);
+        input_map.insert(Self::FreeRoam, GamepadButtonType::South);
+
+        // KbM input bindings
+        input_map.insert(Self::Move, VirtualDPad::wasd());
+        input_map.insert(Self::Next, KeyCode::KeyE);
+        input_map.insert(Self::Previous, KeyCode::KeyQ);
+        input_map.insert(Self::FreeRoam, KeyCode::Space);
+
+        input_map
+    }
+}
+
+#[derive(TypstFunc, Resource, Default)]
+#[typst_func(name = "spectator", layer = 0)]
+struct SpectatorFunc {
+    players: Vec<Dict>,
+    /// Name of the followed player.
+    target: Option<String>,
+    dummy_update: u8,
+}
+
+#[derive(TypstPath)]
+#[typst_path = "typst/client/spectator.typ"]
+struct SpectatorUi;
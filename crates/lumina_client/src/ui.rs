@@ -6,6 +6,7 @@ use crate::screens::Screen;
 
 use super::Connection;
 
+pub(super) mod death_recap;
 pub(super) mod game_mode;
 pub(super) mod game_over;
 pub(super) mod game_ui;
@@ -17,6 +18,7 @@ pub(super) mod respawn_cue;
 pub(super) mod sandbox;
 pub(super) mod spaceship_select;
 pub(super) mod splash;
+pub(super) mod tutorial;
 
 pub(super) struct UiPlugin;
 
@@ -34,6 +36,8 @@ impl Plugin for UiPlugin {
             spaceship_select::SpaceshipSelectUiPlugin,
             respawn_cue::RespawnCueUiPlugin,
             kill_cue::KillCueUiPlugin,
+            death_recap::DeathRecapUiPlugin,
+            tutorial::TutorialUiPlugin,
         ));
 
         app.add_systems(OnEnter(Connection::Disconnected), return_to_main_menu)
@@ -0,0 +1,85 @@
+use bevy::prelude::*;
+use lumina_common::prelude::*;
+use lumina_shared::prelude::*;
+use lumina_ui::prelude::*;
+use velyst::prelude::*;
+use velyst::typst::foundations::{dict, Dict, IntoValue};
+
+use crate::player::death::DeathRecap;
+
+use super::game_ui::get_name;
+use super::Screen;
+
+pub(super) struct DeathRecapUiPlugin;
+
+impl Plugin for DeathRecapUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MainFunc>()
+            .register_typst_asset::<DeathRecapUi>()
+            .compile_typst_func::<DeathRecapUi, MainFunc>()
+            .push_to_main_window::<DeathRecapUi, MainFunc, _>(
+                MainWindowSet::Foreground,
+                in_state(Screen::InGame).and_then(resource_exists::<DeathRecap>),
+            )
+            .add_systems(
+                Update,
+                update_death_recap
+                    .run_if(in_state(Screen::InGame).and_then(resource_exists::<DeathRecap>)),
+            );
+    }
+}
+
+fn update_death_recap(
+    q_spaceships: Query<(&Health, &MaxHealth, &SpaceshipType), With<SourceEntity>>,
+    player_infos: Res<PlayerInfos>,
+    recap: Res<DeathRecap>,
+    mut func: ResMut<MainFunc>,
+) {
+    func.killer = recap.killer.map(|killer| {
+        let mut killer_dict = dict! { "name" => get_name(&killer) };
+
+        // The killer might have left the game.
+        if let Some((health, max_health, spaceship_type)) = player_infos[PlayerInfoType::Spaceship]
+            .get(&killer)
+            .and_then(|e| q_spaceships.get(*e).ok())
+        {
+            killer_dict.insert(
+                "health".into(),
+                ((**health / **max_health) as f64).into_value(),
+            );
+            killer_dict.insert(
+                "spaceship".into(),
+                format!("{spaceship_type:?}").into_value(),
+            );
+        }
+
+        killer_dict
+    });
+
+    func.damages = recap
+        .damages
+        .iter()
+        .map(|(source, damage)| {
+            dict! {
+                "name" => source.as_ref().map_or("Neutral", get_name),
+                "damage" => *damage as f64,
+            }
+        })
+        .collect();
+
+    func.dummy_update = func.dummy_update.wrapping_add(1);
+}
+
+#[derive(TypstFunc, Default, Resource)]
+#[typst_func(name = "main", layer = 1)]
+struct MainFunc {
+    /// Name, health and spaceship type of the killer.
+    killer: Option<Dict>,
+    /// Damage taken per source before death.
+    damages: Vec<Dict>,
+    dummy_update: u8,
+}
+
+#[derive(TypstPath)]
+#[typst_path = "typst/client/death_recap.typ"]
+struct DeathRecapUi;
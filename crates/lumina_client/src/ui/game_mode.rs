@@ -9,13 +9,18 @@ use velyst::prelude::*;
 use velyst::typst::foundations;
 
 use crate::effector::{close_effector_popup, InteractedEffector, MatchmakeEffector};
+use crate::spectator::Spectator;
 use crate::typ_animation::AnimateTypAppExt;
 
 use super::lobby::LobbyFunc;
 use super::Screen;
 
-const SANDBOX_BTN: &str = "btn:sandbox";
+const SANDBOX_BTNS: &[(&str, Screen)] = &[
+    ("btn:sandbox", Screen::Sandbox),
+    ("btn:tutorial", Screen::Tutorial),
+];
 const MATCHMAKE_BTNS: &[&str] = &["btn:1v1", "btn:2v2", "btn:3v3"];
+const SPECTATE_BTN: &str = "btn:spectate";
 const CANCEL_BTN: &str = "btn:cancel-matchmake";
 
 pub(super) struct GameModeUiPlugin;
@@ -37,7 +42,7 @@ impl Plugin for GameModeUiPlugin {
             .add_systems(
                 Update,
                 (
-                    (sandbox_btn, matchmacke_btns, cancel_btn)
+                    (sandbox_btn, matchmacke_btns, spectate_btn, cancel_btn)
                         .run_if(|func: Res<MainFunc>| func.closing == false),
                     update_func_closing,
                     close_effector_popup::<MatchmakeEffector, AnimationMarker>,
@@ -80,28 +85,35 @@ fn sandbox_btn(
     mut q_seq_player: Query<&mut SequencePlayer, With<AnimationMarker>>,
     mut evw_transparency: EventWriter<MainWindowTransparency>,
 ) {
-    if interactions.pressed(SANDBOX_BTN) {
-        // Hide menu.
-        q_seq_player.single_mut().time_scale = -1.0;
-
-        // Transition to sandbox screen.
-        commands.add(Coroutine::new(move || {
-            let mut res = co_break();
-            res.add_subroutines((
-                wait(std::time::Duration::from_secs_f32(WINDOW_FADE_DURATION)),
-                move |mut connection_manager: ResMut<ConnectionManager>,
-                      mut next_screen_state: ResMut<NextState<Screen>>| {
-                    next_screen_state.set(Screen::Sandbox);
-                    let _ = connection_manager.send_message::<OrdReliableChannel, _>(&EnterSandbox);
-
-                    co_break()
-                },
-            ));
-            res
-        }));
+    let Some((_, screen)) = SANDBOX_BTNS
+        .iter()
+        .find(|(btn, _)| interactions.pressed(btn))
+        .cloned()
+    else {
+        return;
+    };
 
-        evw_transparency.send(MainWindowTransparency(0.0));
-    }
+    // Hide menu.
+    q_seq_player.single_mut().time_scale = -1.0;
+
+    // Transition to sandbox or tutorial screen, both are hosted in a sandbox lobby.
+    commands.add(Coroutine::new(move || {
+        let screen = screen.clone();
+        let mut res = co_break();
+        res.add_subroutines((
+            wait(std::time::Duration::from_secs_f32(WINDOW_FADE_DURATION)),
+            move |mut connection_manager: ResMut<ConnectionManager>,
+                  mut next_screen_state: ResMut<NextState<Screen>>| {
+                next_screen_state.set(screen.clone());
+                let _ = connection_manager.send_message::<OrdReliableChannel, _>(&EnterSandbox);
+
+                co_break()
+            },
+        ));
+        res
+    }));
+
+    evw_transparency.send(MainWindowTransparency(0.0));
 }
 
 fn matchmacke_btns(
@@ -148,6 +160,42 @@ fn matchmacke_btns(
     evw_transparency.send(MainWindowTransparency(0.0));
 }
 
+fn spectate_btn(
+    mut commands: Commands,
+    interactions: InteractionQuery,
+    mut q_player: Query<&mut SequencePlayer, With<AnimationMarker>>,
+    mut evw_transparency: EventWriter<MainWindowTransparency>,
+) {
+    if interactions.pressed(SPECTATE_BTN) == false {
+        return;
+    }
+
+    // Hide menu.
+    q_player.single_mut().time_scale = -1.0;
+
+    // Transition to matchmakinig screen until a game to watch is found.
+    commands.add(Coroutine::new(move || {
+        let mut res = co_break();
+        res.add_subroutines((
+            wait(std::time::Duration::from_secs_f32(WINDOW_FADE_DURATION)),
+            move |mut connection_manager: ResMut<ConnectionManager>,
+                  mut next_screen_state: ResMut<NextState<Screen>>,
+                  mut spectator: ResMut<Spectator>| {
+                next_screen_state.set(Screen::Matchmaking);
+
+                let _ = connection_manager
+                    .send_message::<OrdReliableChannel, _>(&Spectate { room_id: None });
+                spectator.active = true;
+
+                co_break()
+            },
+        ));
+        res
+    }));
+
+    evw_transparency.send(MainWindowTransparency(0.0));
+}
+
 fn cancel_btn(
     interactions: InteractionQuery,
     mut q_player: Query<&mut SequencePlayer, With<AnimationMarker>>,
@@ -6,6 +6,7 @@ use velyst::prelude::*;
 use velyst::typst_element::prelude::*;
 
 use crate::player::CachedGameStat;
+use crate::screens::ServerShutdownNotice;
 
 use super::Screen;
 
@@ -33,7 +34,13 @@ impl Plugin for GameOverUiPlugin {
     }
 }
 
-fn set_game_over_values(game_stat: Res<CachedGameStat>, mut func: ResMut<GameOverFunc>) {
+fn set_game_over_values(
+    game_stat: Res<CachedGameStat>,
+    shutdown_notice: Res<ServerShutdownNotice>,
+    mut func: ResMut<GameOverFunc>,
+) {
+    func.server_shutdown = **shutdown_notice;
+
     if let CachedGameStat {
         team_type: Some(team_type),
         game_score: Some(game_score),
@@ -59,6 +66,7 @@ pub struct GameOverFunc {
     pub local_team_index: u8,
     pub team_names: Vec<&'static str>,
     pub team_scores: Vec<u8>,
+    pub server_shutdown: bool,
 }
 
 impl InteractableFunc for GameOverFunc {
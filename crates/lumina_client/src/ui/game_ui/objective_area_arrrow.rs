@@ -1,6 +1,9 @@
+use avian2d::prelude::*;
 use bevy::prelude::*;
 use client::*;
 use lightyear::prelude::*;
+use lumina_common::prelude::*;
+use lumina_shared::game::prelude::*;
 use lumina_shared::prelude::*;
 use lumina_ui::prelude::*;
 use velyst::prelude::*;
@@ -21,9 +24,9 @@ impl Plugin for ObjectiveArrowUiPlugin {
                 in_state(Screen::InGame),
             )
             .init_resource::<MainFunc>()
-            .init_resource::<TargetPosition>()
-            .add_systems(OnEnter(Screen::LocalLobby), reset_target_position)
-            .add_systems(Update, update_taget_position)
+            .init_resource::<ObjectiveTarget>()
+            .add_systems(OnEnter(Screen::LocalLobby), reset_objective_target)
+            .add_systems(Update, update_objective_target)
             .add_systems(
                 PostUpdate,
                 update_arrow
@@ -34,36 +37,90 @@ impl Plugin for ObjectiveArrowUiPlugin {
 }
 
 /// Update the [`MainFunc`].
+/// The arrow follows the navigation path towards the objective when there is one,
+/// the path is only searched again when the target or the navigation grids
+/// change, or when the spaceship leaves the path.
 fn update_arrow(
     q_global_transforms: Query<&GlobalTransform>,
+    q_spaceships: Query<(Option<&WorldIdx>, Option<&ColliderAabb>)>,
+    nav: NavQuery,
     q_game_camera: Query<(&GlobalTransform, &OrthographicProjection), With<GameCamera>>,
     mut func: ResMut<MainFunc>,
     local_player_info: LocalPlayerInfo,
-    target_position: Res<TargetPosition>,
+    target: Res<ObjectiveTarget>,
     time: Res<Time>,
     mut transparency: Local<f64>,
+    mut cached: Local<CachedPath>,
 ) {
     const MIN_DIST: f32 = 1200.0;
     const FADE_SPEED: f64 = 4.0;
+    /// Distance from the path before it is searched again.
+    const MAX_PATH_OFFSET: f32 = 200.0;
+    /// Seconds between searches while no path is available.
+    const RETRY_INTERVAL: f64 = 1.0;
 
     let fade_delta = time.delta_seconds_f64() * FADE_SPEED;
-    let TargetPosition(Some(target_position)) = *target_position else {
+    let Some(target_position) = target.position else {
         return;
     };
+    // Seconds until the objective becomes active.
+    let countdown = (target.active_at - time.elapsed_seconds_f64()).max(0.0);
 
     let Ok((camera_transform, projection)) = q_game_camera.get_single() else {
         return;
     };
 
-    if let Some(spaceship_transform) = local_player_info
-        .get(PlayerInfoType::Spaceship)
-        .and_then(|e| q_global_transforms.get(e).ok())
-    {
+    let Some(spaceship_entity) = local_player_info.get(PlayerInfoType::Spaceship) else {
+        return;
+    };
+
+    if let Ok(spaceship_transform) = q_global_transforms.get(spaceship_entity) {
         let spaceship_position = spaceship_transform.translation().xy();
 
-        let diff = target_position - spaceship_position;
-        let dist = diff.length();
-        let direction = diff.normalize_or_zero().as_dvec2();
+        let (world_id, radius) = match q_spaceships.get(spaceship_entity) {
+            Ok((world_id, aabb)) => (
+                world_id.copied().unwrap_or_default(),
+                aabb.map(|aabb| aabb.size().max_element() * 0.5)
+                    .unwrap_or_default(),
+            ),
+            Err(_) => (WorldIdx::default(), 0.0),
+        };
+
+        let elapsed = time.elapsed_seconds_f64();
+        let mut followed = cached
+            .path
+            .as_ref()
+            .and_then(|path| path.follow(spaceship_position, MAX_PATH_OFFSET));
+
+        let stale = cached.target != target_position
+            || cached.world_id != world_id
+            || nav.grids.is_changed()
+            || (cached.path.is_some() && followed.is_none())
+            || (cached.path.is_none() && elapsed - cached.searched_at > RETRY_INTERVAL);
+
+        if stale {
+            let path = nav.find_path(world_id, spaceship_position, target_position, radius);
+            followed = path
+                .as_ref()
+                .map(|path| (path.next_waypoint(), path.length()));
+
+            *cached = CachedPath {
+                path,
+                target: target_position,
+                world_id,
+                searched_at: elapsed,
+            };
+        }
+
+        // Point straight at the objective if there is no path available.
+        let (waypoint, dist) = followed.unwrap_or((
+            target_position,
+            target_position.distance(spaceship_position),
+        ));
+
+        let direction = (waypoint - spaceship_position)
+            .normalize_or_zero()
+            .as_dvec2();
 
         if dist > MIN_DIST {
             *transparency = transparency.lerp(0.0, fade_delta);
@@ -76,6 +133,7 @@ fn update_arrow(
         func.data = dict! {
             "rotation" => direction.y.atan2(direction.x),
             "dist" => dist as f64,
+            "countdown" => countdown,
             "transparency" => *transparency,
             "scale" => projection.scale as f64,
             "camera_diff_x" => camera_diff.x,
@@ -84,19 +142,24 @@ fn update_arrow(
     }
 }
 
-fn update_taget_position(
-    mut evr_position: EventReader<MessageEvent<ObjectivePosition>>,
-    mut target_position: ResMut<TargetPosition>,
+fn update_objective_target(
+    mut evr_announcement: EventReader<MessageEvent<ObjectiveAnnouncement>>,
+    mut target: ResMut<ObjectiveTarget>,
+    time: Res<Time>,
 ) {
-    for event in evr_position.read() {
-        let ObjectivePosition(position) = event.message;
-        **target_position = Some(position);
-        info!("Got objective position: {}", position);
+    for event in evr_announcement.read() {
+        let announcement = event.message();
+        target.position = Some(announcement.position);
+        target.active_at = time.elapsed_seconds_f64() + announcement.countdown as f64;
+        info!(
+            "Next objective at {} in {}s.",
+            announcement.position, announcement.countdown
+        );
     }
 }
 
-fn reset_target_position(mut target_position: ResMut<TargetPosition>) {
-    **target_position = None;
+fn reset_objective_target(mut target: ResMut<ObjectiveTarget>) {
+    *target = ObjectiveTarget::default();
 }
 
 #[derive(TypstFunc, Resource, Default)]
@@ -105,8 +168,22 @@ pub struct MainFunc {
     pub data: Dict,
 }
 
-#[derive(Resource, Deref, DerefMut, Default)]
-struct TargetPosition(Option<Vec2>);
+#[derive(Resource, Default)]
+struct ObjectiveTarget {
+    position: Option<Vec2>,
+    /// Elapsed time (in seconds) at which the objective becomes active.
+    active_at: f64,
+}
+
+/// The last path searched by [`update_arrow`].
+#[derive(Default)]
+struct CachedPath {
+    path: Option<NavPath>,
+    target: Vec2,
+    world_id: WorldIdx,
+    /// Elapsed time (in seconds) of the search.
+    searched_at: f64,
+}
 
 #[derive(TypstPath)]
 #[typst_path = "typst/client/objective_area_arrow.typ"]
@@ -10,7 +10,9 @@ pub(super) struct ScoreBarUiPlugin;
 
 impl Plugin for ScoreBarUiPlugin {
     fn build(&self, app: &mut App) {
-        let run_condition = in_state(Screen::Sandbox).or_else(in_state(Screen::InGame));
+        let run_condition = in_state(Screen::Sandbox)
+            .or_else(in_state(Screen::Tutorial))
+            .or_else(in_state(Screen::InGame));
 
         app.register_typst_asset::<ScoreBarUi>()
             .compile_typst_func::<ScoreBarUi, ScoreBarFunc>()
@@ -6,6 +6,7 @@ use velyst::prelude::*;
 pub(super) struct SettingsUiPlugin;
 
 use crate::audio::AudioVolumeSettings;
+use crate::network::{NetworkStats, NetworkTuning};
 
 impl Plugin for SettingsUiPlugin {
     fn build(&self, app: &mut App) {
@@ -19,7 +20,7 @@ impl Plugin for SettingsUiPlugin {
             .init_resource::<SettingsFunc>()
             .init_resource::<SettingsOverlay>()
             .init_resource::<AudioSettings>()
-            .add_systems(Update, (adjust_audio, close_settings));
+            .add_systems(Update, (adjust_audio, adjust_network, close_settings));
     }
 }
 
@@ -51,11 +52,57 @@ fn adjust_audio(
     func.vfx_volume = audio_settings.vfx_volume;
 }
 
+/// Override the prediction settings live from the settings overlay.
+fn adjust_network(
+    mut func: ResMut<SettingsFunc>,
+    interactions: InteractionQuery,
+    mut tuning: ResMut<NetworkTuning>,
+    stats: Res<NetworkStats>,
+    overlay: Res<SettingsOverlay>,
+) {
+    if overlay.visible == false {
+        return;
+    }
+
+    // Manually setting the input delay overrides the adaptive input delay.
+    if interactions.pressed("btn:decrease_input_delay") {
+        tuning.adaptive = None;
+        tuning.input_delay_ticks = tuning.input_delay_ticks.saturating_sub(1);
+    }
+    if interactions.pressed("btn:increase_input_delay") {
+        tuning.adaptive = None;
+        tuning.input_delay_ticks =
+            (tuning.input_delay_ticks + 1).min(tuning.max_input_delay_ticks());
+    }
+    if interactions.pressed("btn:toggle_adaptive_delay") {
+        tuning.toggle_adaptive();
+    }
+    if interactions.pressed("btn:decrease_correction") {
+        tuning.correction_ticks_factor =
+            (tuning.correction_ticks_factor - 0.25).max(NetworkTuning::MIN_CORRECTION_FACTOR);
+    }
+    if interactions.pressed("btn:increase_correction") {
+        tuning.correction_ticks_factor =
+            (tuning.correction_ticks_factor + 0.25).min(NetworkTuning::MAX_CORRECTION_FACTOR);
+    }
+
+    func.input_delay_ticks = tuning.input_delay_ticks as i64;
+    func.adaptive_delay = tuning.adaptive.is_some();
+    func.correction_factor = tuning.correction_ticks_factor as f64;
+    func.rtt_ms = stats.rtt.as_secs_f64() * 1000.0;
+    func.jitter_ms = stats.jitter.as_secs_f64() * 1000.0;
+}
+
 #[derive(TypstFunc, Resource)]
 #[typst_func(name = "settings", layer = 1)]
 pub struct SettingsFunc {
     bgm_volume: f64,
     vfx_volume: f64,
+    input_delay_ticks: i64,
+    adaptive_delay: bool,
+    correction_factor: f64,
+    rtt_ms: f64,
+    jitter_ms: f64,
     dummy_update: u8,
 }
 
@@ -65,6 +112,11 @@ impl Default for SettingsFunc {
         SettingsFunc {
             bgm_volume: default_audio.bgm_volume,
             vfx_volume: default_audio.vfx_volume,
+            input_delay_ticks: 0,
+            adaptive_delay: false,
+            correction_factor: 0.0,
+            rtt_ms: 0.0,
+            jitter_ms: 0.0,
             dummy_update: 0,
         }
     }
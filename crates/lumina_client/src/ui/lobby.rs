@@ -5,6 +5,8 @@ use lumina_shared::prelude::*;
 use lumina_ui::prelude::*;
 use velyst::prelude::*;
 
+use crate::screens::multiplayer_lobby::MapPreload;
+
 use super::Screen;
 
 pub(super) struct LobbyUiPlugin;
@@ -21,9 +23,18 @@ impl Plugin for LobbyUiPlugin {
             .init_resource::<LobbyFunc>()
             .add_systems(
                 Update,
-                exit_lobby_btn.run_if(in_state(Screen::MultiplayerLobby)),
+                (exit_lobby_btn, map_vote_btns).run_if(in_state(Screen::MultiplayerLobby)),
             )
-            .add_systems(Update, (handle_lobby_data, handle_lobby_update));
+            .add_systems(
+                Update,
+                (
+                    handle_lobby_data,
+                    handle_lobby_update,
+                    handle_map_vote_start,
+                    handle_preload_map,
+                    update_loading_progress,
+                ),
+            );
     }
 }
 
@@ -38,6 +49,49 @@ fn exit_lobby_btn(
     }
 }
 
+/// Vote for one of the maps of the [`MapVoteStart`].
+fn map_vote_btns(
+    interactions: InteractionQuery,
+    mut connection_manager: ResMut<ConnectionManager>,
+    mut lobby_func: ResMut<LobbyFunc>,
+) {
+    let Some(index) =
+        (0..lobby_func.vote_maps.len()).find(|i| interactions.pressed(&format!("btn:vote-{i}")))
+    else {
+        return;
+    };
+
+    if lobby_func.voted_map != Some(index) {
+        let _ = connection_manager.send_message::<OrdReliableChannel, _>(&MapVote {
+            map_name: lobby_func.vote_maps[index].clone(),
+        });
+        lobby_func.voted_map = Some(index);
+    }
+}
+
+/// Show the maps to vote for from [`MapVoteStart`].
+fn handle_map_vote_start(
+    mut evr_map_vote_start: EventReader<MessageEvent<MapVoteStart>>,
+    mut lobby_func: ResMut<LobbyFunc>,
+) {
+    for map_vote_start in evr_map_vote_start.read() {
+        lobby_func.vote_maps = map_vote_start.message().maps.clone();
+        lobby_func.voted_map = None;
+    }
+}
+
+/// The vote is over once the map to preload is selected.
+fn handle_preload_map(
+    mut evr_preload_map: EventReader<MessageEvent<PreloadMap>>,
+    mut lobby_func: ResMut<LobbyFunc>,
+) {
+    for preload_map in evr_preload_map.read() {
+        lobby_func.vote_maps.clear();
+        lobby_func.voted_map = None;
+        lobby_func.map_name = Some(preload_map.message().map_name.clone());
+    }
+}
+
 /// Digest data from [`LobbyUpdate`].
 fn handle_lobby_update(
     mut evr_lobby_update: EventReader<MessageEvent<LobbyUpdate>>,
@@ -58,6 +112,18 @@ fn handle_lobby_data(
 
         // Update ui.
         lobby_func.room_id = Some(data.room_id.0);
+        // A new lobby has not selected its map yet.
+        lobby_func.vote_maps.clear();
+        lobby_func.voted_map = None;
+        lobby_func.map_name = None;
+    }
+}
+
+/// Show the [`MapPreload`] progress.
+fn update_loading_progress(preload: Option<Res<MapPreload>>, mut lobby_func: ResMut<LobbyFunc>) {
+    let loading_progress = preload.map(|preload| preload.progress as f64);
+    if lobby_func.loading_progress != loading_progress {
+        lobby_func.loading_progress = loading_progress;
     }
 }
 
@@ -67,6 +133,14 @@ pub(super) struct LobbyFunc {
     pub curr_player_count: u8,
     pub max_player_count: u8,
     pub room_id: Option<u64>,
+    /// Maps to vote for once the lobby is full.
+    pub vote_maps: Vec<String>,
+    /// Index of the map in [`Self::vote_maps`] that the player voted for.
+    pub voted_map: Option<usize>,
+    /// The map selected by the vote.
+    pub map_name: Option<String>,
+    /// Map loading progress once the map is selected.
+    pub loading_progress: Option<f64>,
     dummy_update: u8,
 }
 
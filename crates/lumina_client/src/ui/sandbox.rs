@@ -11,16 +11,18 @@ pub(super) struct SandboxUiPlugin;
 
 impl Plugin for SandboxUiPlugin {
     fn build(&self, app: &mut App) {
+        let run_condition = in_state(Screen::Sandbox).or_else(in_state(Screen::Tutorial));
+
         app.add_event::<MessageEvent<LobbyData>>()
             .register_typst_asset::<SandboxUi>()
             .compile_typst_func::<SandboxUi, SandboxFunc>()
             .push_to_main_window::<SandboxUi, SandboxFunc, _>(
                 MainWindowSet::Default,
-                in_state(Screen::Sandbox),
+                run_condition.clone(),
             )
             .recompile_on_interaction::<SandboxFunc>(|func| &mut func.dummy_update)
             .init_resource::<SandboxFunc>()
-            .add_systems(Update, exit_sandbox_btn.run_if(in_state(Screen::Sandbox)))
+            .add_systems(Update, exit_sandbox_btn.run_if(run_condition))
             .add_systems(Update, handle_sandbox_data);
     }
 }
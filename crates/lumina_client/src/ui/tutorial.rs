@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+use lumina_ui::prelude::*;
+use velyst::prelude::*;
+
+use crate::screens::tutorial::Tutorial;
+
+use super::Screen;
+
+pub(super) struct TutorialUiPlugin;
+
+impl Plugin for TutorialUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_typst_asset::<TutorialUi>()
+            .compile_typst_func::<TutorialUi, TutorialFunc>()
+            .push_to_main_window::<TutorialUi, TutorialFunc, _>(
+                MainWindowSet::Default,
+                in_state(Screen::Tutorial),
+            )
+            .init_resource::<TutorialFunc>()
+            .add_systems(
+                Update,
+                update_tutorial_func.run_if(in_state(Screen::Tutorial)),
+            );
+    }
+}
+
+fn update_tutorial_func(tutorial: Res<Tutorial>, mut func: ResMut<TutorialFunc>) {
+    func.step = tutorial.step as i64;
+    func.completed = tutorial.completed();
+    func.dummy_update = func.dummy_update.wrapping_add(1);
+}
+
+#[derive(TypstFunc, Resource, Default)]
+#[typst_func(name = "tutorial", layer = 1)]
+struct TutorialFunc {
+    /// Index of the current [`TutorialStep`][crate::screens::tutorial::TutorialStep].
+    step: i64,
+    /// True if the current step is completed.
+    completed: bool,
+    dummy_update: u8,
+}
+
+#[derive(TypstPath)]
+#[typst_path = "typst/client/tutorial.typ"]
+struct TutorialUi;
@@ -132,6 +132,12 @@ impl XorShift32 {
 
         state
     }
+
+    /// Random value in the range of [0.0, 1.0).
+    pub fn next_f32(&mut self) -> f32 {
+        // Only the upper 24 bits fit into the mantissa.
+        (self.next_u32() >> 8) as f32 / (1 << 24) as f32
+    }
 }
 
 pub fn hash_coords_2d(x: u64, y: u64) -> u64 {
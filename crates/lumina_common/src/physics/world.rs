@@ -33,7 +33,7 @@ fn filter_collisions(
 /// Represents the world the [Entity] belongs to.
 /// It is used for collision filtering.
 /// Id is also interchangable with [RoomId].
-#[derive(Component, Deref, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[derive(Component, Deref, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct WorldIdx(pub Option<Entity>);
 
 impl WorldIdx {
@@ -29,7 +29,7 @@ pub fn read_settings<T: DeserializeOwned>(settings_str: &str) -> T {
     ron::de::from_str::<T>(settings_str).expect("Could not deserialize the settings file.")
 }
 
-#[derive(Resource, Deserialize, Serialize, Debug, Clone, Copy)]
+#[derive(Resource, Deserialize, Serialize, Debug, Clone)]
 pub struct LuminaSettings {
     pub fixed_timestep_hz: f64,
     /// In milliseconds.
@@ -45,7 +45,7 @@ impl LuminaSettings {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ServerSettings {
     /// If true, disable any rendering-related plugins
     pub headless: bool,
@@ -53,6 +53,163 @@ pub struct ServerSettings {
     pub inspector: bool,
     /// Possibly add a conditioner to simulate network conditions
     pub conditioner: Option<Conditioner>,
+    /// Interest management and bandwidth settings.
+    #[serde(default)]
+    pub interest: InterestSettings,
+    /// Server-side lag compensation for ammo hits.
+    #[serde(default)]
+    pub lag_compensation: LagCompensationSettings,
+    /// Validation of messages sent by clients.
+    #[serde(default)]
+    pub validation: ValidationSettings,
+    /// Graceful shutdown settings.
+    #[serde(default)]
+    pub shutdown: ShutdownSettings,
+    /// Countdown and map loading before a game starts.
+    #[serde(default)]
+    pub lobby: LobbySettings,
+    /// Always play this map (by name in `maps.registry.ron`)
+    /// instead of voting between the maps of the rotation.
+    #[serde(default)]
+    pub pinned_map: Option<String>,
+    /// Seed used by every new lobby, a random seed is used if [`None`].
+    /// Set this to reproduce a match from its seed.
+    #[serde(default)]
+    pub lobby_seed: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct InterestSettings {
+    /// If false, every replicated entity is sent to the whole room.
+    pub enabled: bool,
+    /// Upper bound of the half extents of the area where distance culled
+    /// entities (ores, lumina) stay relevant to a client. Also used around
+    /// the client's spaceship until the client sends its camera view.
+    pub relevance_half_extents: Vec2,
+    /// Margin added around the client's camera view so that entities
+    /// become relevant before they enter the screen.
+    pub view_margin: f32,
+    /// Interval between relevance updates in milliseconds.
+    pub update_interval: u64,
+    /// Maximum bytes per second sent to each client, `None` for no cap.
+    pub send_bandwidth_cap: Option<u32>,
+}
+
+impl InterestSettings {
+    pub fn update_interval(&self) -> Duration {
+        Duration::from_millis(self.update_interval)
+    }
+}
+
+impl Default for InterestSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            relevance_half_extents: Vec2::new(1600.0, 1000.0),
+            view_margin: 300.0,
+            update_interval: 200,
+            send_bandwidth_cap: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct LagCompensationSettings {
+    /// If false, ammo hits are resolved against the current positions.
+    pub enabled: bool,
+    /// Maximum duration the server is allowed to rewind in milliseconds.
+    /// Clients with a higher latency will have their hits resolved
+    /// against the oldest available state instead.
+    pub max_rewind: u64,
+    /// Interpolation delay of the clients in milliseconds.
+    /// Other spaceships are rendered this far in the past on the client.
+    pub interpolation_delay: u64,
+}
+
+impl LagCompensationSettings {
+    pub fn max_rewind(&self) -> Duration {
+        Duration::from_millis(self.max_rewind)
+    }
+
+    pub fn interpolation_delay(&self) -> Duration {
+        Duration::from_millis(self.interpolation_delay)
+    }
+}
+
+impl Default for LagCompensationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_rewind: 250,
+            interpolation_delay: 100,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct ValidationSettings {
+    /// Maximum distance between a spaceship and a teleporter start to teleport.
+    pub teleport_radius: f32,
+    /// Maximum distance between a spaceship and a tesseract to deposit lumina.
+    pub deposit_radius: f32,
+    /// Number of messages a client is allowed to send per second.
+    pub message_rate: f32,
+    /// Number of messages a client is allowed to send in a single burst.
+    pub message_burst: f32,
+    /// Violation score at which a client gets kicked.
+    pub kick_threshold: f32,
+    /// Violation score forgiven per second.
+    pub violation_decay: f32,
+}
+
+impl Default for ValidationSettings {
+    fn default() -> Self {
+        Self {
+            teleport_radius: 300.0,
+            deposit_radius: 400.0,
+            message_rate: 10.0,
+            message_burst: 20.0,
+            kick_threshold: 10.0,
+            violation_decay: 0.2,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct ShutdownSettings {
+    /// Maximum time (in seconds) to wait for in-progress games to
+    /// finish before they are ended forcefully.
+    pub drain_timeout: f32,
+}
+
+impl Default for ShutdownSettings {
+    fn default() -> Self {
+        Self {
+            drain_timeout: 300.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct LobbySettings {
+    /// Time (in seconds) the players have to vote for the map once the lobby is full,
+    /// the vote ends early once every player voted.
+    pub vote_duration: f32,
+    /// Minimum time (in seconds) between the map being selected and the game start.
+    pub countdown: f32,
+    /// Maximum time (in seconds) to wait for every client to load the map,
+    /// the game starts without the slow clients afterwards.
+    pub map_load_timeout: f32,
+}
+
+impl Default for LobbySettings {
+    fn default() -> Self {
+        Self {
+            vote_duration: 10.0,
+            countdown: 5.0,
+            map_load_timeout: 20.0,
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Copy)]
@@ -65,8 +222,44 @@ pub struct ClientSettings {
     pub client_port: u16,
     /// Possibly add a conditioner to simulate network conditions
     pub conditioner: Option<Conditioner>,
+    /// Initial input delay (in ticks) used for prediction.
     pub input_delay_ticks: u16,
+    /// Initial correction ticks factor used for prediction.
     pub correction_ticks_factor: f32,
+    /// Adapt [`Self::input_delay_ticks`] based on the measured network conditions.
+    /// Set to `None` to keep the input delay fixed.
+    #[serde(default)]
+    pub adaptive_input_delay: Option<AdaptiveInputDelay>,
+}
+
+impl ClientSettings {
+    /// Upper bound of a manually set input delay (in ticks)
+    /// when [`Self::adaptive_input_delay`] is not configured.
+    pub const MAX_MANUAL_INPUT_DELAY_TICKS: u16 = 16;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct AdaptiveInputDelay {
+    /// Lower bound of the input delay (in ticks).
+    pub min_ticks: u16,
+    /// Upper bound of the input delay (in ticks).
+    pub max_ticks: u16,
+    /// Multiplier applied on the jitter to absorb latency spikes.
+    pub jitter_factor: f32,
+    /// Minimum time (in seconds) between two input delay adjustments.
+    pub adjust_interval: f32,
+}
+
+impl AdaptiveInputDelay {
+    /// Compute the target input delay (in ticks) from the round trip time
+    /// and jitter, clamped within [`Self::min_ticks`] and [`Self::max_ticks`].
+    pub fn target_ticks(&self, rtt: Duration, jitter: Duration, tick_duration: Duration) -> u16 {
+        // Inputs only need to cover the one way trip to the server.
+        let latency = rtt.as_secs_f32() * 0.5 + jitter.as_secs_f32() * self.jitter_factor;
+        let ticks = (latency / tick_duration.as_secs_f32()).ceil() as u16;
+
+        ticks.clamp(self.min_ticks, self.max_ticks)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
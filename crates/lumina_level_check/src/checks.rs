@@ -0,0 +1,369 @@
+//! The conventions a level must follow for the game logic to find what it needs.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use bevy::asset::ron;
+use lumina_shared::game::prelude::*;
+use lumina_shared::player::objective::LuminaSpawnArea;
+use lumina_shared::prelude::*;
+use serde::Deserialize;
+use strum::{EnumCount, IntoEnumIterator};
+
+use crate::level::LevelNode;
+
+/// What a level needs to contain.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LevelRequirements {
+    /// Minimum number of [`SpawnPoint`]s per [`TeamType`].
+    pub spawn_points: [usize; TeamType::COUNT],
+    /// Exact number of [`ObjectiveArea`]s, [`None`] if it does not matter.
+    pub objective_areas: Option<usize>,
+}
+
+/// Run every check on a level.
+pub fn check_level(level: &LevelNode, requirements: &LevelRequirements) -> Vec<Issue> {
+    let mut issues = Vec::new();
+
+    check_spawn_points(level, requirements, &mut issues);
+    check_objective_areas(level, requirements, &mut issues);
+    check_teleporters(level, &mut issues);
+    check_neutrals(level, &mut issues);
+
+    issues
+}
+
+fn check_spawn_points(
+    level: &LevelNode,
+    requirements: &LevelRequirements,
+    issues: &mut Vec<Issue>,
+) {
+    let parents = level.find_all::<SpawnPointParent>();
+    if parents.is_empty() {
+        if requirements.spawn_points.iter().any(|&count| count > 0) {
+            issues.push(Issue::error("No SpawnPointParent found."));
+        }
+        return;
+    }
+
+    if parents.len() > 1 {
+        issues.push(Issue::error(format!(
+            "Found {} SpawnPointParents, only 1 is used per world.",
+            parents.len()
+        )));
+    }
+
+    // Spawn points are only registered when they are direct children of the parent.
+    let mut counts = [0; TeamType::COUNT];
+    level.walk(&mut |node, ancestors| {
+        let Some(value) = node.get::<SpawnPoint>() else {
+            return;
+        };
+
+        match ron::de::from_str::<SpawnPointData>(value) {
+            Ok(SpawnPointData(team_type)) => {
+                if ancestors
+                    .last()
+                    .is_some_and(|p| p.has::<SpawnPointParent>())
+                {
+                    counts[team_type as usize] += 1;
+                } else {
+                    issues.push(Issue::error(format!(
+                        "SpawnPoint {:?} is not a direct child of a SpawnPointParent.",
+                        node.name
+                    )));
+                }
+            }
+            Err(err) => issues.push(Issue::error(format!(
+                "SpawnPoint {:?} has an invalid team {value:?}: {err}",
+                node.name
+            ))),
+        }
+    });
+
+    for team_type in TeamType::iter() {
+        let count = counts[team_type as usize];
+        let required = requirements.spawn_points[team_type as usize];
+        if count < required {
+            issues.push(Issue::error(format!(
+                "Team {} has {count} spawn points, expected at least {required}.",
+                team_type.as_ref()
+            )));
+        }
+    }
+}
+
+fn check_objective_areas(
+    level: &LevelNode,
+    requirements: &LevelRequirements,
+    issues: &mut Vec<Issue>,
+) {
+    let areas = level.find_all::<ObjectiveArea>();
+    if let Some(required) = requirements.objective_areas {
+        if areas.len() != required {
+            issues.push(Issue::error(format!(
+                "Found {} ObjectiveAreas, expected {required}.",
+                areas.len()
+            )));
+        }
+    }
+
+    for area in areas.iter() {
+        let ores = area.find_all::<OreType>();
+        if ores.is_empty() {
+            issues.push(Issue::error(format!(
+                "ObjectiveArea {:?} has no ores.",
+                area.name
+            )));
+        }
+
+        for ore in ores.iter() {
+            let spawn_areas = ore.find_all::<LuminaSpawnArea>().len();
+            if spawn_areas != 1 {
+                issues.push(Issue::error(format!(
+                    "Ore {:?} in {:?} has {spawn_areas} LuminaSpawnAreas, expected 1.",
+                    ore.name, area.name
+                )));
+            }
+        }
+    }
+
+    level.walk(&mut |node, ancestors| {
+        if node.has::<OreType>() && ancestors.iter().any(|a| a.has::<ObjectiveArea>()) == false {
+            issues.push(Issue::warning(format!(
+                "Ore {:?} is not inside an ObjectiveArea and will never reset.",
+                node.name
+            )));
+        }
+
+        if node.has::<LuminaSpawnArea>() && ancestors.iter().any(|a| a.has::<OreType>()) == false {
+            issues.push(Issue::error(format!(
+                "LuminaSpawnArea {:?} is not inside an ore.",
+                node.name
+            )));
+        }
+    });
+}
+
+fn check_teleporters(level: &LevelNode, issues: &mut Vec<Issue>) {
+    let mut ids = HashMap::<u32, &str>::new();
+
+    for teleporter in level.find_all::<Teleporter>() {
+        let value = teleporter.get::<Teleporter>().unwrap_or_default();
+        let Teleporter(id) = match ron::de::from_str::<Teleporter>(value) {
+            Ok(id) => id,
+            Err(err) => {
+                issues.push(Issue::error(format!(
+                    "Teleporter {:?} has an invalid id {value:?}: {err}",
+                    teleporter.name
+                )));
+                continue;
+            }
+        };
+
+        if let Some(other) = ids.insert(id, &teleporter.name) {
+            issues.push(Issue::error(format!(
+                "Teleporter {:?} and {other:?} share the same id {id}.",
+                teleporter.name
+            )));
+        }
+
+        let starts = teleporter.find_all::<TeleporterStart>();
+        let ends = teleporter.find_all::<TeleporterEnd>().len();
+        if starts.is_empty() {
+            issues.push(Issue::error(format!(
+                "Teleporter {id} ({:?}) has no TeleporterStart.",
+                teleporter.name
+            )));
+        }
+
+        // Every start leads to the ends and the other two-way starts.
+        let mut two_way_starts = 0;
+        for start in starts.iter() {
+            let value = start.get::<TeleporterStart>().unwrap_or_default();
+            match ron::de::from_str::<TeleporterStartData>(value) {
+                Ok(data) if data.two_way => two_way_starts += 1,
+                Ok(_) => {}
+                Err(err) => issues.push(Issue::error(format!(
+                    "TeleporterStart {:?} is invalid {value:?}: {err}",
+                    start.name
+                ))),
+            }
+        }
+        if ends == 0 && two_way_starts < 2 {
+            issues.push(Issue::error(format!(
+                "Teleporter {id} ({:?}) has no exit, add a TeleporterEnd or two-way starts.",
+                teleporter.name
+            )));
+        }
+    }
+
+    // The id is propagated from the Teleporter down to the start and end points.
+    level.walk(&mut |node, ancestors| {
+        let is_point = node.has::<TeleporterStart>() || node.has::<TeleporterEnd>();
+        let has_id = node.has::<Teleporter>() || ancestors.iter().any(|a| a.has::<Teleporter>());
+        if is_point && has_id == false {
+            issues.push(Issue::error(format!(
+                "Teleporter point {:?} is not inside a Teleporter.",
+                node.name
+            )));
+        }
+    });
+}
+
+fn check_neutrals(level: &LevelNode, issues: &mut Vec<Issue>) {
+    level.walk(&mut |node, _| {
+        let has_behaviour =
+            node.has::<NeutralTurret>() || node.has::<NeutralMine>() || node.has::<NeutralDrone>();
+        if has_behaviour && node.has::<Neutral>() == false {
+            issues.push(Issue::error(format!(
+                "Neutral behaviour on {:?} without a Neutral.",
+                node.name
+            )));
+        }
+
+        if node.has::<Neutral>() && node.has::<MaxHealth>() == false {
+            issues.push(Issue::error(format!(
+                "Neutral {:?} has no MaxHealth and can not be destroyed.",
+                node.name
+            )));
+        }
+
+        if node.has::<NeutralTurret>() && node.has::<AmmoStat>() == false {
+            issues.push(Issue::error(format!(
+                "NeutralTurret {:?} has no AmmoStat to fire with.",
+                node.name
+            )));
+        }
+    });
+}
+
+/// [`SpawnPoint`] does not expose its team, so deserialize it separately.
+#[derive(Deserialize)]
+struct SpawnPointData(TeamType);
+
+/// The part of [`TeleporterStart`] that decides where it leads.
+#[derive(Deserialize)]
+struct TeleporterStartData {
+    #[serde(default)]
+    two_way: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct Issue {
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Issue {
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            message: message.into(),
+        }
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.severity {
+            Severity::Warning => write!(f, "warning: {}", self.message),
+            Severity::Error => write!(f, "error: {}", self.message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level::short_name;
+
+    fn node<T>(name: &str, value: &str, children: Vec<LevelNode>) -> LevelNode {
+        LevelNode {
+            name: name.to_string(),
+            components: [(short_name::<T>().to_string(), value.to_string())].into(),
+            children,
+        }
+    }
+
+    fn errors(issues: &[Issue]) -> usize {
+        issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Error)
+            .count()
+    }
+
+    #[test]
+    fn spawn_points_must_be_direct_children() {
+        let level = LevelNode {
+            children: vec![node::<SpawnPointParent>(
+                "Parent",
+                "()",
+                vec![
+                    node::<SpawnPoint>("A", "(A)", vec![]),
+                    node::<SpawnPoint>("B", "(B)", vec![]),
+                    LevelNode {
+                        children: vec![node::<SpawnPoint>("Nested", "(B)", vec![])],
+                        ..Default::default()
+                    },
+                ],
+            )],
+            ..Default::default()
+        };
+        let requirements = LevelRequirements {
+            spawn_points: [1, 2],
+            objective_areas: None,
+        };
+
+        let issues = check_level(&level, &requirements);
+        // The nested spawn point is reported and does not count towards team B.
+        assert_eq!(errors(&issues), 2, "{issues:?}");
+    }
+
+    #[test]
+    fn teleporters_need_a_start_and_an_exit() {
+        let level = LevelNode {
+            children: vec![
+                node::<Teleporter>(
+                    "Teleporter 0",
+                    "(0)",
+                    vec![
+                        node::<TeleporterStart>("Start", "()", vec![]),
+                        node::<TeleporterEnd>("End", "()", vec![]),
+                    ],
+                ),
+                node::<Teleporter>(
+                    "Teleporter 1",
+                    "(0)",
+                    vec![node::<TeleporterStart>("Start", "()", vec![])],
+                ),
+                node::<Teleporter>(
+                    "Teleporter 2",
+                    "(2)",
+                    vec![
+                        node::<TeleporterStart>("A", "(two_way: true)", vec![]),
+                        node::<TeleporterStart>("B", "(two_way: true)", vec![]),
+                    ],
+                ),
+            ],
+            ..Default::default()
+        };
+
+        let issues = check_level(&level, &LevelRequirements::default());
+        // Duplicated id and missing exit, two-way starts lead to each other.
+        assert_eq!(errors(&issues), 2, "{issues:?}");
+    }
+}
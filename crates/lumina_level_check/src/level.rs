@@ -0,0 +1,296 @@
+//! Read the node hierarchy and Blenvy components of an exported level without running Bevy.
+//!
+//! Nested blueprints (`BlueprintInfo`) are expanded in place, the same way Blenvy
+//! spawns them: the components of the blueprint scene are added to the instance node
+//! and the scene nodes become its children.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use bevy::asset::ron;
+use serde::Deserialize;
+use thiserror::Error;
+
+/// Component name (without module path) to its ron value.
+pub type Components = HashMap<String, String>;
+
+/// A node in the level hierarchy.
+#[derive(Debug, Clone, Default)]
+pub struct LevelNode {
+    pub name: String,
+    pub components: Components,
+    pub children: Vec<LevelNode>,
+}
+
+impl LevelNode {
+    pub fn has<T>(&self) -> bool {
+        self.components.contains_key(short_name::<T>())
+    }
+
+    pub fn get<T>(&self) -> Option<&str> {
+        self.components.get(short_name::<T>()).map(String::as_str)
+    }
+
+    /// Visit this node and all of its descendants, depth first.
+    pub fn walk<'a>(&'a self, f: &mut impl FnMut(&'a LevelNode, &[&'a LevelNode])) {
+        fn walk_impl<'a>(
+            node: &'a LevelNode,
+            ancestors: &mut Vec<&'a LevelNode>,
+            f: &mut impl FnMut(&'a LevelNode, &[&'a LevelNode]),
+        ) {
+            f(node, ancestors);
+            ancestors.push(node);
+            for child in node.children.iter() {
+                walk_impl(child, ancestors, f);
+            }
+            ancestors.pop();
+        }
+
+        walk_impl(self, &mut Vec::new(), f);
+    }
+
+    /// This node and all of its descendants that have the component `T`.
+    pub fn find_all<T>(&self) -> Vec<&LevelNode> {
+        let mut nodes = Vec::new();
+        self.walk(&mut |node, _| {
+            if node.has::<T>() {
+                nodes.push(node);
+            }
+        });
+
+        nodes
+    }
+}
+
+/// Type name without the module path, e.g. `ObjectiveArea`.
+pub fn short_name<T>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// Loads levels from the asset folder, caching the blueprints along the way.
+pub struct LevelLoader {
+    assets_dir: PathBuf,
+    blueprints: HashMap<String, LevelNode>,
+}
+
+impl LevelLoader {
+    pub fn new(assets_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            assets_dir: assets_dir.into(),
+            blueprints: HashMap::new(),
+        }
+    }
+
+    /// Load a level (or blueprint) from its asset path, e.g. `levels/maps/Sandbox.glb`.
+    pub fn load(&mut self, path: &str) -> Result<LevelNode, LevelError> {
+        self.load_recursive(path, &mut Vec::new())
+    }
+
+    fn load_recursive(
+        &mut self,
+        path: &str,
+        stack: &mut Vec<String>,
+    ) -> Result<LevelNode, LevelError> {
+        if let Some(blueprint) = self.blueprints.get(path) {
+            return Ok(blueprint.clone());
+        }
+
+        if stack.iter().any(|p| p == path) {
+            return Err(LevelError::Recursive(path.to_string()));
+        }
+
+        let gltf = read_gltf(&self.assets_dir.join(path)).map_err(|err| LevelError::Gltf {
+            path: path.to_string(),
+            err,
+        })?;
+        let scene = gltf
+            .scenes
+            .get(gltf.scene)
+            .ok_or_else(|| LevelError::NoScene(path.to_string()))?;
+
+        stack.push(path.to_string());
+        let mut root = LevelNode {
+            name: path.to_string(),
+            components: components(&scene.extras, path)?,
+            children: Vec::with_capacity(scene.nodes.len()),
+        };
+        for &node in scene.nodes.iter() {
+            root.children
+                .push(self.load_node(&gltf, node, path, stack)?);
+        }
+        stack.pop();
+
+        self.blueprints.insert(path.to_string(), root.clone());
+        Ok(root)
+    }
+
+    fn load_node(
+        &mut self,
+        gltf: &GltfJson,
+        index: usize,
+        path: &str,
+        stack: &mut Vec<String>,
+    ) -> Result<LevelNode, LevelError> {
+        let gltf_node = gltf.nodes.get(index).ok_or_else(|| LevelError::Gltf {
+            path: path.to_string(),
+            err: GltfError::NodeIndex(index),
+        })?;
+
+        let mut node = LevelNode {
+            name: gltf_node
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("Node {index}")),
+            components: components(&gltf_node.extras, path)?,
+            children: Vec::with_capacity(gltf_node.children.len()),
+        };
+
+        if let Some(info) = node.components.get("BlueprintInfo") {
+            let info = ron::de::from_str::<BlueprintInfoData>(info).map_err(|err| {
+                LevelError::Component {
+                    path: path.to_string(),
+                    component: "BlueprintInfo".to_string(),
+                    err,
+                }
+            })?;
+
+            let blueprint = self.load_recursive(&info.path, stack)?;
+            // Components on the instance override the ones from the blueprint.
+            for (name, value) in blueprint.components {
+                node.components.entry(name).or_insert(value);
+            }
+            node.children.extend(blueprint.children);
+        }
+
+        for &child in gltf_node.children.iter() {
+            node.children
+                .push(self.load_node(gltf, child, path, stack)?);
+        }
+
+        Ok(node)
+    }
+}
+
+/// Collect the components exported by Blenvy.
+///
+/// Components registered in the type registry are stored as a json map in `bevy_components`
+/// (with their full type path), while Blenvy's own components are stored as separate keys.
+fn components(extras: &Extras, path: &str) -> Result<Components, LevelError> {
+    let mut components = Components::new();
+
+    for (key, value) in extras.iter() {
+        let Some(value) = value.as_str() else {
+            continue;
+        };
+
+        if key == "bevy_components" {
+            let registered =
+                serde_json::from_str::<HashMap<String, String>>(value).map_err(|err| {
+                    LevelError::Gltf {
+                        path: path.to_string(),
+                        err: GltfError::Json(err),
+                    }
+                })?;
+
+            for (type_path, value) in registered {
+                let name = type_path.rsplit("::").next().unwrap_or(&type_path);
+                components.insert(name.to_string(), value);
+            }
+        } else {
+            components.insert(key.clone(), value.to_string());
+        }
+    }
+
+    Ok(components)
+}
+
+/// Read the json part of a `.glb` or `.gltf` file.
+fn read_gltf(path: &Path) -> Result<GltfJson, GltfError> {
+    const MAGIC: &[u8] = b"glTF";
+    const JSON_CHUNK: u32 = 0x4E4F534A;
+
+    let bytes = std::fs::read(path)?;
+    if bytes.starts_with(MAGIC) == false {
+        return Ok(serde_json::from_slice(&bytes)?);
+    }
+
+    // 12 bytes header followed by the json chunk (length, type, data).
+    let read_u32 = |offset: usize| {
+        bytes
+            .get(offset..offset + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .ok_or(GltfError::Binary)
+    };
+    let chunk_len = read_u32(12)? as usize;
+    if read_u32(16)? != JSON_CHUNK {
+        return Err(GltfError::Binary);
+    }
+
+    let json = bytes.get(20..20 + chunk_len).ok_or(GltfError::Binary)?;
+    Ok(serde_json::from_slice(json)?)
+}
+
+type Extras = serde_json::Map<String, serde_json::Value>;
+
+#[derive(Deserialize)]
+struct GltfJson {
+    #[serde(default)]
+    scene: usize,
+    #[serde(default)]
+    scenes: Vec<GltfScene>,
+    #[serde(default)]
+    nodes: Vec<GltfNode>,
+}
+
+#[derive(Deserialize)]
+struct GltfScene {
+    #[serde(default)]
+    nodes: Vec<usize>,
+    #[serde(default)]
+    extras: Extras,
+}
+
+#[derive(Deserialize)]
+struct GltfNode {
+    name: Option<String>,
+    #[serde(default)]
+    children: Vec<usize>,
+    #[serde(default)]
+    extras: Extras,
+}
+
+#[derive(Deserialize)]
+struct BlueprintInfoData {
+    path: String,
+}
+
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum LevelError {
+    #[error("Could not read {path}: {err}")]
+    Gltf { path: String, err: GltfError },
+    #[error("{0} has no scene.")]
+    NoScene(String),
+    #[error("{0} contains itself as a blueprint.")]
+    Recursive(String),
+    #[error("Could not parse {component} in {path}: {err}")]
+    Component {
+        path: String,
+        component: String,
+        err: ron::de::SpannedError,
+    },
+}
+
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum GltfError {
+    #[error("Could not load file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not deserialize json: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid glb header.")]
+    Binary,
+    #[error("Node {0} does not exist.")]
+    NodeIndex(usize),
+}
@@ -0,0 +1,163 @@
+//! Validate the levels exported from Blender without starting the game.
+//!
+//! Every [`MapType`] and every blueprint map in `maps.registry.ron` is checked for the
+//! components the game logic relies on (spawn points, objective areas, teleporters...).
+//! The process exits with a failure code if any error is found so that it can be used in CI.
+//!
+//! ```text
+//! cargo run -p lumina_level_check -- [assets folder]
+//! ```
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use bevy::asset::ron;
+use lumina_shared::game::map_registry::MapRegistryAsset;
+use lumina_shared::game::prelude::*;
+use lumina_shared::player::objective::DEFAULT_OBJECTIVE_AREA_COUNT;
+use lumina_shared::prelude::*;
+use strum::{EnumCount, IntoEnumIterator};
+
+use checks::{Issue, LevelRequirements, Severity};
+use level::LevelLoader;
+
+mod checks;
+mod level;
+
+const REGISTRY_PATH: &str = "maps.registry.ron";
+
+fn main() -> ExitCode {
+    let assets_dir = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("assets"));
+
+    let mut reports = Vec::new();
+
+    let registry = match std::fs::read(assets_dir.join(REGISTRY_PATH)) {
+        Ok(bytes) => match ron::de::from_bytes::<MapRegistryAsset>(&bytes) {
+            Ok(registry) => {
+                if registry.find(&registry.default).is_none() {
+                    reports.push((
+                        REGISTRY_PATH.to_string(),
+                        vec![Issue::error(format!(
+                            "Default map {:?} is not in the registry.",
+                            registry.default
+                        ))],
+                    ));
+                }
+                Some(registry)
+            }
+            Err(err) => {
+                reports.push((
+                    REGISTRY_PATH.to_string(),
+                    vec![Issue::error(err.to_string())],
+                ));
+                None
+            }
+        },
+        Err(err) => {
+            reports.push((
+                REGISTRY_PATH.to_string(),
+                vec![Issue::error(err.to_string())],
+            ));
+            None
+        }
+    };
+
+    let mut loader = LevelLoader::new(&assets_dir);
+    for (path, requirements) in levels(registry.as_ref(), &mut reports) {
+        let issues = match loader.load(&path) {
+            Ok(level) => checks::check_level(&level, &requirements),
+            Err(err) => vec![Issue::error(err.to_string())],
+        };
+        reports.push((path, issues));
+    }
+
+    let mut error_count = 0;
+    for (path, issues) in reports.iter() {
+        if issues.is_empty() {
+            println!("{path}: ok");
+            continue;
+        }
+
+        println!("{path}:");
+        for issue in issues.iter() {
+            println!("  {issue}");
+        }
+        error_count += issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Error)
+            .count();
+    }
+
+    match error_count {
+        0 => ExitCode::SUCCESS,
+        _ => {
+            eprintln!("Found {error_count} error(s) in the levels.");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// The levels to check along with what they need to contain.
+fn levels(
+    registry: Option<&MapRegistryAsset>,
+    reports: &mut Vec<(String, Vec<Issue>)>,
+) -> Vec<(String, LevelRequirements)> {
+    // The multiplayer lobby must be able to hold the biggest match.
+    let max_team_size = registry
+        .iter()
+        .flat_map(|registry| registry.maps.iter())
+        .flat_map(|map| map.team_sizes.iter())
+        .copied()
+        .max()
+        .unwrap_or(1) as usize;
+
+    let mut levels = MapType::iter()
+        .map(|map_type| {
+            let requirements = match map_type {
+                MapType::Local | MapType::Sandbox => LevelRequirements {
+                    spawn_points: [1, 0],
+                    objective_areas: None,
+                },
+                MapType::Multiplayer => LevelRequirements {
+                    spawn_points: [max_team_size; TeamType::COUNT],
+                    objective_areas: None,
+                },
+                MapType::AbandonedFactory => LevelRequirements {
+                    spawn_points: [max_team_size; TeamType::COUNT],
+                    objective_areas: Some(DEFAULT_OBJECTIVE_AREA_COUNT),
+                },
+            };
+
+            (format!("{}.glb", map_type.as_ref()), requirements)
+        })
+        .collect::<Vec<_>>();
+
+    for map in registry.iter().flat_map(|registry| registry.maps.iter()) {
+        let MapSource::Blueprint(path) = &map.source else {
+            continue;
+        };
+
+        let Some(&team_size) = map.team_sizes.iter().max() else {
+            reports.push((
+                REGISTRY_PATH.to_string(),
+                vec![Issue::error(format!("{:?} has no team sizes.", map.name))],
+            ));
+            continue;
+        };
+
+        // The registry knows better than the defaults above.
+        let requirements = LevelRequirements {
+            spawn_points: [team_size as usize; TeamType::COUNT],
+            objective_areas: Some(map.objective_count),
+        };
+        match levels.iter_mut().find(|(p, _)| p == path) {
+            Some((_, r)) => *r = requirements,
+            None => levels.push((path.clone(), requirements)),
+        }
+    }
+
+    levels
+}
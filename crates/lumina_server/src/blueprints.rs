@@ -5,6 +5,8 @@ use lumina_common::prelude::*;
 use lumina_shared::prelude::*;
 use server::*;
 
+use crate::interest::ReplicationPriority;
+
 pub(super) struct BlueprintsPlugin;
 
 impl Plugin for BlueprintsPlugin {
@@ -37,6 +39,7 @@ fn replicate_from_server(
             &WorldIdx,
             Has<NoRecursive>,
             Option<&PlayerId>,
+            (Has<OreType>, Has<LuminaType>),
             Entity,
         ),
         (Without<SyncTarget>, Without<BlueprintSpawning>),
@@ -45,12 +48,19 @@ fn replicate_from_server(
     q_sync_filter: Query<(), With<HierarchySync>>,
     mut room_manager: ResMut<RoomManager>,
 ) {
-    for (replicate, world_id, no_recursive, player_id, entity) in q_entities.iter_mut() {
+    for (replicate, world_id, no_recursive, player_id, (is_ore, is_lumina), entity) in
+        q_entities.iter_mut()
+    {
         // Will be controlled by player id if it exists.
         let target = player_id
             .map(|&id| NetworkTarget::Single(id.0))
             .unwrap_or_default();
 
+        let priority = match is_ore || is_lumina {
+            true => ReplicationPriority::Objective,
+            false => ReplicationPriority::Map,
+        };
+
         commands.entity(entity).insert(Replicate {
             sync: SyncTarget {
                 prediction: replicate.prediction_target(),
@@ -64,6 +74,7 @@ fn replicate_from_server(
             hierarchy: ReplicateHierarchy {
                 recursive: no_recursive,
             },
+            group: priority.apply(ReplicationGroup::default()),
             ..default()
         });
 
@@ -7,35 +7,47 @@ use lumina_shared::game::RESPAWN_DURATION;
 use lumina_shared::prelude::*;
 use server::*;
 
-use crate::lobby::{ClientExitLobby, Lobby, LobbyInGame};
+use crate::lobby::{ClientExitLobby, Lobby, LobbyInGame, Spectators};
 use crate::player::ResetSpaceship;
 
-mod teleporter;
+mod hazard;
+mod neutral;
+mod pickup;
+mod switch;
+pub mod teleporter;
+pub mod terrain;
 
 pub(super) struct GamePlugin;
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(teleporter::TeleporterPlugin)
-            .add_systems(
-                Update,
-                (
-                    handle_player_death,
-                    init_game,
-                    propagate_game_score,
-                    track_game_score,
-                    track_game_timer,
-                    track_respawn_delay,
-                ),
-            )
-            .observe(end_game);
+        app.add_plugins((
+            hazard::HazardPlugin,
+            neutral::NeutralPlugin,
+            pickup::PickupPlugin,
+            switch::SwitchPlugin,
+            teleporter::TeleporterPlugin,
+            terrain::TerrainPlugin,
+        ))
+        .add_systems(
+            Update,
+            (
+                handle_player_death,
+                init_game,
+                propagate_game_score,
+                track_game_score,
+                track_game_timer,
+                track_respawn_delay,
+            ),
+        )
+        .observe(end_game);
     }
 }
 
 fn end_game(
     trigger: Trigger<EndGame>,
     mut commands: Commands,
-    q_lobbies: Query<&Lobby>,
+    q_lobbies: Query<(&Lobby, Option<&Spectators>)>,
     mut connection_manager: ResMut<ConnectionManager>,
     room_manager: Res<RoomManager>,
     mut evw_client_exit: EventWriter<ClientExitLobby>,
@@ -43,11 +55,14 @@ fn end_game(
     let entity = trigger.entity();
 
     // Entity must be a lobby entity for logic beneath to work.
-    let Ok(lobby) = q_lobbies.get(entity) else {
+    let Ok((lobby, spectators)) = q_lobbies.get(entity) else {
         return;
     };
 
-    for id in lobby.iter() {
+    for id in lobby
+        .iter()
+        .chain(spectators.into_iter().flat_map(|s| s.iter()))
+    {
         evw_client_exit.send(ClientExitLobby(*id));
     }
 
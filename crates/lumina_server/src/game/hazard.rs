@@ -0,0 +1,35 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use lumina_common::prelude::*;
+use lumina_shared::game::prelude::*;
+use lumina_shared::prelude::*;
+
+use crate::player::kda::LastDamage;
+
+pub(super) struct HazardPlugin;
+
+impl Plugin for HazardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, hazard_damage);
+    }
+}
+
+/// Damage the spaceships inside active [`AnimationHazard`]s.
+fn hazard_damage(
+    mut commands: Commands,
+    q_hazards: Query<(&AnimationHazard, &CollidingEntities), With<HazardActive>>,
+    mut q_spaceships: AliveQuery<&mut Health, (With<Spaceship>, With<SourceEntity>)>,
+    time: Res<Time>,
+) {
+    for (hazard, colliding_entities) in q_hazards.iter() {
+        for &entity in colliding_entities.iter() {
+            let Ok(mut health) = q_spaceships.get_mut(entity) else {
+                continue;
+            };
+
+            **health -= hazard.damage_per_second * time.delta_seconds();
+            // Nobody gets the kill.
+            commands.entity(entity).try_insert(LastDamage::default());
+        }
+    }
+}
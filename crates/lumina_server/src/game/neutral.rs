@@ -0,0 +1,326 @@
+use std::f32::consts::TAU;
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use lumina_common::prelude::*;
+use lumina_shared::game::prelude::*;
+use lumina_shared::prelude::*;
+
+use crate::lobby::{LobbyRng, RngStream};
+use crate::player::kda::LastDamage;
+use crate::player::objective::SpawnLumina;
+
+pub(super) struct NeutralPlugin;
+
+impl Plugin for NeutralPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, setup_neutrals).add_systems(
+            FixedUpdate,
+            (
+                neutral_turrets,
+                neutral_drones,
+                (mine_detonation, neutral_destruction).chain(),
+                respawn_neutrals,
+            ),
+        );
+    }
+}
+
+/// Spaceships that neutrals can target.
+type TargetQuery<'w, 's> =
+    AliveQuery<'w, 's, (&'static Position, &'static WorldIdx), With<Spaceship>>;
+
+/// Initialize the server only states of the neutral behaviours.
+fn setup_neutrals(
+    mut commands: Commands,
+    q_turrets: Query<(&NeutralTurret, Entity), Added<NeutralTurret>>,
+    q_drones: Query<Entity, Added<NeutralDrone>>,
+) {
+    for (turret, entity) in q_turrets.iter() {
+        commands.entity(entity).insert((
+            TurretReload(Timer::from_seconds(turret.fire_interval, TimerMode::Once)),
+            NeutralShot::default(),
+        ));
+    }
+
+    for entity in q_drones.iter() {
+        commands.entity(entity).insert(DroneState::default());
+    }
+}
+
+/// Fire at the closest spaceship in range and in sight.
+fn neutral_turrets(
+    mut commands: Commands,
+    mut q_turrets: Query<
+        (
+            &NeutralTurret,
+            &mut TurretReload,
+            &mut NeutralShot,
+            &GlobalTransform,
+            &WorldIdx,
+            Entity,
+        ),
+        (With<AmmoStat>, Without<NeutralDestroyed>),
+    >,
+    q_targets: TargetQuery,
+    nav: NavQuery,
+    time: Res<Time>,
+) {
+    for (turret, mut reload, mut shot, transform, world_id, entity) in q_turrets.iter_mut() {
+        reload.tick(time.delta());
+        if reload.finished() == false {
+            continue;
+        }
+
+        let position = transform.translation().xy();
+        let Some(target) = closest_target(&q_targets, world_id, position, turret.range) else {
+            continue;
+        };
+
+        // Do not shoot through walls.
+        if nav
+            .grids
+            .get(world_id)
+            .is_some_and(|grid| grid.line_of_sight(position, target, 0.0) == false)
+        {
+            continue;
+        }
+
+        let direction = (target - position).normalize_or_zero();
+        commands.trigger(FireAmmo {
+            weapon_entity: entity,
+            position,
+            direction,
+        });
+
+        shot.count = shot.count.wrapping_add(1);
+        shot.direction = direction;
+        reload.reset();
+    }
+}
+
+/// Chase the closest spaceship in range, roam around the spawn position otherwise.
+fn neutral_drones(
+    mut q_drones: Query<(
+        &NeutralDrone,
+        &mut DroneState,
+        &mut LinearVelocity,
+        &Position,
+        &WorldIdx,
+        Has<NeutralDestroyed>,
+    )>,
+    q_targets: TargetQuery,
+    mut q_rngs: Query<&mut LobbyRng>,
+    nav: NavQuery,
+    time: Res<Time>,
+) {
+    /// Time between each path finding.
+    const REPATH_INTERVAL: f32 = 0.5;
+
+    for (drone, mut state, mut linear_velocity, position, world_id, destroyed) in
+        q_drones.iter_mut()
+    {
+        if destroyed {
+            linear_velocity.0 = Vec2::ZERO;
+            continue;
+        }
+
+        let home = *state.home.get_or_insert(position.0);
+
+        let goal = match closest_target(&q_targets, world_id, position.0, drone.chase_range) {
+            Some(target) => target,
+            None => {
+                let roaming = state
+                    .waypoint
+                    .is_some_and(|waypoint| waypoint.distance(position.0) > drone.radius);
+
+                if roaming == false {
+                    let Some(mut rng) = world_id.and_then(|e| q_rngs.get_mut(e).ok()) else {
+                        continue;
+                    };
+
+                    let rng = rng.stream(RngStream::Neutrals);
+                    let dir = Vec2::from_angle(rng.next_f32() * TAU);
+                    let distance = rng.next_f32() * drone.patrol_radius;
+                    state.waypoint = Some(home + dir * distance);
+                    state.path = None;
+                }
+
+                state.waypoint.unwrap_or(home)
+            }
+        };
+
+        state.repath += time.delta_seconds();
+        if state.path.is_none() || state.repath >= REPATH_INTERVAL {
+            state.path = nav.find_path(*world_id, position.0, goal, drone.radius);
+            state.repath = 0.0;
+        }
+
+        // Head straight to the goal if there is no navigation grid.
+        let next = state
+            .path
+            .as_ref()
+            .map(NavPath::next_waypoint)
+            .unwrap_or(goal);
+        linear_velocity.0 = (next - position.0).normalize_or_zero() * drone.speed;
+    }
+}
+
+/// Explode mines that are destroyed or have a spaceship nearby.
+fn mine_detonation(
+    mut commands: Commands,
+    mut q_mines: Query<
+        (
+            &NeutralMine,
+            &Neutral,
+            &mut Health,
+            &GlobalTransform,
+            &WorldIdx,
+            Entity,
+        ),
+        (Without<NeutralDestroyed>, Without<Spaceship>),
+    >,
+    mut q_spaceships: AliveQuery<(&Position, &WorldIdx, &mut Health, Entity), With<Spaceship>>,
+) {
+    for (mine, neutral, mut health, transform, world_id, entity) in q_mines.iter_mut() {
+        let position = transform.translation().xy();
+
+        let triggered = q_spaceships
+            .iter()
+            .any(|(spaceship_position, spaceship_world, ..)| {
+                spaceship_world == world_id
+                    && spaceship_position.distance(position) <= mine.trigger_radius
+            });
+        let shot_down = **health <= 0.0;
+        if triggered == false && shot_down == false {
+            continue;
+        }
+
+        for (spaceship_position, spaceship_world, mut spaceship_health, spaceship_entity) in
+            q_spaceships.iter_mut()
+        {
+            if spaceship_world == world_id
+                && spaceship_position.distance(position) <= mine.blast_radius
+            {
+                **spaceship_health -= mine.damage;
+                // Nobody gets the kill.
+                commands
+                    .entity(spaceship_entity)
+                    .try_insert(LastDamage::default());
+            }
+        }
+
+        // Mines shot down are rewarded in neutral_destruction.
+        if shot_down == false {
+            **health = 0.0;
+            destroy_neutral(&mut commands, entity, neutral);
+        }
+    }
+}
+
+/// Drop lumina around neutrals with 0.0 health or less.
+fn neutral_destruction(
+    mut commands: Commands,
+    q_neutrals: Query<
+        (&Neutral, &Health, &GlobalTransform, &WorldIdx, Entity),
+        (Changed<Health>, Without<NeutralDestroyed>),
+    >,
+    mut q_rngs: Query<&mut LobbyRng>,
+) {
+    for (neutral, health, transform, &world_id, entity) in q_neutrals.iter() {
+        if **health > 0.0 {
+            continue;
+        }
+
+        destroy_neutral(&mut commands, entity, neutral);
+
+        let Some(mut rng) = world_id.and_then(|e| q_rngs.get_mut(e).ok()) else {
+            warn!("No lobby rng found for neutral {entity}.");
+            continue;
+        };
+
+        let translation = transform.translation().xy();
+        let radius = 2.0 + (neutral.lumina_reward as f32 * 0.5);
+        let drops = rng.stream(RngStream::Drops);
+        for _ in 0..neutral.lumina_reward {
+            let dir = Vec2::from_angle(drops.next_f32() * TAU);
+            let distance = drops.next_f32() * radius;
+
+            commands.trigger(SpawnLumina {
+                position: Position(translation + (dir * distance)),
+                world_id,
+            });
+        }
+    }
+}
+
+/// Restore destroyed neutrals after their respawn duration.
+fn respawn_neutrals(
+    mut commands: Commands,
+    mut q_neutrals: Query<(&mut NeutralRespawn, &mut Health, &MaxHealth, Entity)>,
+    time: Res<Time>,
+) {
+    for (mut respawn, mut health, max_health, entity) in q_neutrals.iter_mut() {
+        respawn.tick(time.delta());
+        if respawn.finished() {
+            **health = **max_health;
+            commands
+                .entity(entity)
+                .remove::<(NeutralDestroyed, NeutralRespawn)>();
+        }
+    }
+}
+
+fn destroy_neutral(commands: &mut Commands, entity: Entity, neutral: &Neutral) {
+    let mut cmd = commands.entity(entity);
+    cmd.insert(NeutralDestroyed);
+
+    if neutral.respawn_duration > 0.0 {
+        cmd.insert(NeutralRespawn(Timer::from_seconds(
+            neutral.respawn_duration,
+            TimerMode::Once,
+        )));
+    }
+}
+
+/// Position of the closest target within `range`.
+fn closest_target(
+    q_targets: &TargetQuery,
+    world_id: &WorldIdx,
+    position: Vec2,
+    range: f32,
+) -> Option<Vec2> {
+    q_targets
+        .iter()
+        .filter(|(_, target_world)| *target_world == world_id)
+        .map(|(target_position, _)| target_position.0)
+        .filter(|target| target.distance(position) <= range)
+        .min_by(|a, b| {
+            a.distance_squared(position)
+                .total_cmp(&b.distance_squared(position))
+        })
+}
+
+/// Marker component when the neutral is destroyed.
+/// Must be removed when it's being restored.
+#[derive(Component)]
+pub struct NeutralDestroyed;
+
+/// Restore the neutral after the timer ends.
+#[derive(Component, Deref, DerefMut)]
+struct NeutralRespawn(Timer);
+
+/// Time until the next [`NeutralTurret`] shot.
+#[derive(Component, Deref, DerefMut)]
+struct TurretReload(Timer);
+
+#[derive(Component, Default)]
+struct DroneState {
+    /// Spawn position, set on the first update.
+    home: Option<Vec2>,
+    /// Roaming target when there is nothing to chase.
+    waypoint: Option<Vec2>,
+    path: Option<NavPath>,
+    /// Time since the last path finding.
+    repath: f32,
+}
@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use lumina_common::prelude::*;
+use lumina_shared::game::pickup::PickupRespawn;
+use lumina_shared::game::prelude::*;
+use lumina_shared::prelude::*;
+
+pub(super) struct PickupPlugin;
+
+impl Plugin for PickupPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, pickup_collection);
+    }
+}
+
+/// Apply available [`Pickup`]s to the spaceships flying through them
+/// and start their [`PickupCooldown`].
+fn pickup_collection(
+    mut commands: Commands,
+    q_pickups: Query<(&Pickup, &CollidingEntities, Entity), Without<PickupCooldown>>,
+    mut q_spaceships: AliveQuery<
+        (&PlayerId, &Spaceship, &mut Health, &MaxHealth, &mut Energy),
+        With<SourceEntity>,
+    >,
+    mut q_weapons: Query<(&Weapon, &mut WeaponMagazine)>,
+    player_infos: Res<PlayerInfos>,
+) {
+    for (pickup, colliding_entities, entity) in q_pickups.iter() {
+        for &spaceship_entity in colliding_entities.iter() {
+            let Ok((player_id, spaceship, mut health, max_health, mut energy)) =
+                q_spaceships.get_mut(spaceship_entity)
+            else {
+                continue;
+            };
+
+            // Leave the pickup to players who need it.
+            let collected = match pickup.pickup_type {
+                PickupType::Health => {
+                    let collect = **health < **max_health;
+                    if collect {
+                        **health = (**health + pickup.amount).min(**max_health);
+                    }
+                    collect
+                }
+                PickupType::Energy => {
+                    let max_energy = spaceship.energy.max_energy;
+                    let collect = energy.energy < max_energy;
+                    if collect {
+                        energy.energy = (energy.energy + pickup.amount).min(max_energy);
+                    }
+                    collect
+                }
+                PickupType::Ammo => player_infos[PlayerInfoType::Weapon]
+                    .get(player_id)
+                    .and_then(|e| q_weapons.get_mut(*e).ok())
+                    .is_some_and(|(weapon, mut magazine)| {
+                        let collect = magazine.0 < weapon.magazine_size();
+                        if collect {
+                            magazine.0 =
+                                (magazine.0 + pickup.amount as u32).min(weapon.magazine_size());
+                        }
+                        collect
+                    }),
+            };
+
+            if collected {
+                info!(
+                    "Player {:?} collected {:?} pickup {entity}",
+                    player_id, pickup.pickup_type
+                );
+                commands
+                    .entity(entity)
+                    .start_auto_timer::<PickupRespawn>(Duration::from_secs_f32(
+                        pickup.respawn_duration,
+                    ));
+
+                // Only allow one player to collect the pickup.
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use lumina_common::prelude::*;
+use lumina_shared::game::prelude::*;
+use lumina_shared::prelude::*;
+
+pub(super) struct SwitchPlugin;
+
+impl Plugin for SwitchPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, animator_switches);
+    }
+}
+
+/// Control the [`Animator`] of an [`AnimatorSwitch`] once a spaceship enters it,
+/// the switch is released after every spaceship left.
+fn animator_switches(
+    mut commands: Commands,
+    q_switches: Query<
+        (
+            &AnimatorSwitch,
+            &CollidingEntities,
+            &WorldIdx,
+            Has<SwitchPressed>,
+            Entity,
+        ),
+        Changed<CollidingEntities>,
+    >,
+    q_spaceships: AliveQuery<(), (With<Spaceship>, With<SourceEntity>)>,
+    mut animators: AnimatorQuery,
+) {
+    for (switch, colliding_entities, &world_id, pressed, entity) in q_switches.iter() {
+        let occupied = colliding_entities
+            .iter()
+            .any(|&entity| q_spaceships.contains(entity));
+
+        if occupied == pressed {
+            continue;
+        }
+
+        if pressed {
+            commands.entity(entity).remove::<SwitchPressed>();
+            continue;
+        }
+
+        commands.entity(entity).insert(SwitchPressed);
+        let name = &switch.animator;
+        let found = match switch.action {
+            SwitchAction::Play => animators.play(world_id, name),
+            SwitchAction::Reverse => animators.reverse(world_id, name),
+            SwitchAction::Stop => animators.stop(world_id, name),
+            SwitchAction::Restart => {
+                animators.seek(world_id, name, 0.0) && animators.play(world_id, name)
+            }
+        };
+
+        if found == false {
+            warn!("Switch {entity} controls unknown animator {name:?}.");
+        }
+    }
+}
+
+/// A spaceship is inside the [`AnimatorSwitch`].
+#[derive(Component)]
+struct SwitchPressed;
@@ -1,6 +1,9 @@
+use std::time::Duration;
+
 use avian2d::prelude::*;
 use bevy::prelude::*;
 use bevy::utils::HashMap;
+use leafwing_input_manager::prelude::*;
 use lightyear::prelude::*;
 use lumina_common::prelude::*;
 use lumina_shared::game::prelude::*;
@@ -8,88 +11,154 @@ use lumina_shared::prelude::*;
 use server::*;
 
 use crate::lobby::LobbyRemoval;
-use crate::LobbyInfos;
+use crate::validation::Validated;
 
 pub(super) struct TeleporterPlugin;
 
 impl Plugin for TeleporterPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<TeleporterInfos>().add_systems(
-            Update,
-            (
-                (setup_teleporter_info, cleanup_teleporter_info).chain(),
-                teleport_player,
-            ),
-        );
+        app.init_resource::<TeleporterInfos>()
+            .add_plugins(AutoTimerPlugin::<PlayerTeleport>::default())
+            .add_systems(
+                Update,
+                (
+                    (setup_teleporter_info, cleanup_teleporter_info).chain(),
+                    teleport_player,
+                ),
+            );
     }
 }
 
 fn teleport_player(
-    // mut commands: Commands,
+    mut commands: Commands,
+    q_starts: Query<(
+        &TeleporterStart,
+        &Teleporter,
+        &GlobalTransform,
+        &WorldIdx,
+        Has<TeleporterEffect>,
+        Entity,
+    )>,
     q_global_transforms: Query<&GlobalTransform>,
-    mut q_positions: Query<&mut Position>,
-    mut evr_teleport: EventReader<MessageEvent<Teleport>>,
+    mut q_spaceships: Query<(&mut Position, &mut LinearVelocity, &WorldIdx), With<Spaceship>>,
+    q_actions: Query<&ActionState<PlayerAction>>,
+    mut evr_teleport: EventReader<Validated<Teleport>>,
     infos: Res<TeleporterInfos>,
-    lobby_infos: Res<LobbyInfos>,
     player_infos: Res<PlayerInfos>,
 ) {
     for teleport in evr_teleport.read() {
-        let client_id = teleport.context();
+        let player_id = PlayerId(*teleport.context());
+        let teleporter = teleport.message().teleporter;
+
+        let Some(&spaceship_entity) = player_infos[PlayerInfoType::Spaceship].get(&player_id)
+        else {
+            continue;
+        };
+        let Ok((mut position, mut linear_velocity, world_id)) =
+            q_spaceships.get_mut(spaceship_entity)
+        else {
+            continue;
+        };
 
-        let Some(spaceship_entity) =
-            player_infos[PlayerInfoType::Spaceship].get(&PlayerId(*client_id))
+        // The start the player is using, a teleporter can have more than one.
+        let Some((start, _, start_transform, _, in_effect, start_entity)) = q_starts
+            .iter()
+            .filter(|(_, start_teleporter, _, start_world, ..)| {
+                **start_teleporter == teleporter && *start_world == world_id
+            })
+            .min_by(|(_, _, a, ..), (_, _, b, ..)| {
+                let distance_a = a.translation().xy().distance_squared(position.0);
+                let distance_b = b.translation().xy().distance_squared(position.0);
+                distance_a.total_cmp(&distance_b)
+            })
         else {
             continue;
         };
 
-        let Some(&teleporter_entity) = lobby_infos
-            .get(client_id)
-            .map(|e| e.room_id())
-            .and_then(|room_id| infos.get(&room_id))
-            .and_then(|info| info.get(&teleport.message().teleporter))
+        let Some(exits) = infos
+            .get(&world_id.room_id())
+            .and_then(|info| info.get(&teleporter))
         else {
             continue;
         };
 
-        if let Ok(global_transform) = q_global_transforms.get(teleporter_entity) {
-            if let Ok(mut position) = q_positions.get_mut(*spaceship_entity) {
-                // Teleport the spaceship.
-                let translation = global_transform.translation().xy();
-                *position = Position::new(translation);
+        // Aim decides the exit, fallback to the travel direction.
+        let direction = player_infos[PlayerInfoType::Action]
+            .get(&player_id)
+            .and_then(|e| q_actions.get(*e).ok())
+            .filter(|action| action.pressed(&PlayerAction::Aim))
+            .and_then(|action| action.axis_pair(&PlayerAction::Aim))
+            .map(|axis| axis.xy().normalize_or_zero())
+            .filter(|direction| *direction != Vec2::ZERO)
+            .unwrap_or_else(|| linear_velocity.normalize_or_zero());
 
-                // Start the cooldown effect.
-                // commands.start_cooldown_effect::<Teleporter, TeleporterStart>(teleporter_entity);
-            }
+        let start_translation = start_transform.translation().xy();
+        let Some(exit_transform) = exits
+            .iter()
+            .filter(|e| **e != start_entity)
+            .filter_map(|e| q_global_transforms.get(*e).ok())
+            .map(|transform| {
+                let alignment = (transform.translation().xy() - start_translation)
+                    .normalize_or_zero()
+                    .dot(direction);
+                (transform, alignment)
+            })
+            // Keep the first exit when the alignments are equal.
+            .reduce(|best, exit| if exit.1 > best.1 { exit } else { best })
+            .map(|(transform, _)| transform)
+        else {
+            continue;
+        };
+
+        // Teleport the spaceship.
+        *position = Position::new(exit_transform.translation().xy());
+
+        // Keep the momentum, rotated from the start to the exit orientation.
+        let rotation = exit_transform.compute_transform().rotation
+            * start_transform.compute_transform().rotation.inverse();
+        linear_velocity.0 = (rotation * linear_velocity.extend(0.0)).xy();
+
+        // Start the cooldown effect, others can still use the
+        // teleporter while the effect is active.
+        if in_effect == false {
+            commands.start_cooldown_effect::<Teleporter, TeleporterStart>(start_entity);
+        }
+
+        if start.player_cooldown > 0.0 {
+            commands
+                .entity(spaceship_entity)
+                .start_auto_timer::<PlayerTeleport>(Duration::from_secs_f32(start.player_cooldown));
         }
     }
 }
 
-/// Add teleporter info.
+/// Add teleporter exits info.
 fn setup_teleporter_info(
     mut commands: Commands,
     q_teleporters: Query<
-        (&Teleporter, &WorldIdx, Entity),
+        (&Teleporter, &WorldIdx, Option<&TeleporterStart>, Entity),
         (
-            With<WorldIdx>,
-            With<TeleporterEnd>,
-            Without<TeleporterEndInitialized>,
+            Or<(With<TeleporterStart>, With<TeleporterEnd>)>,
+            Without<TeleporterExitInitialized>,
         ),
     >,
     mut infos: ResMut<TeleporterInfos>,
 ) {
-    for (teleporter, world_id, entity) in q_teleporters.iter() {
-        // Find teleporter info using room id.
-        if let Some(info) = infos.get_mut(&world_id.room_id()) {
-            info.insert(*teleporter, entity);
-        } else {
-            // Create a new one if not exists.
-            infos.insert(
-                world_id.room_id(),
-                TeleporterInfo([(*teleporter, entity)].into()),
-            );
+    for (teleporter, world_id, start, entity) in q_teleporters.iter() {
+        commands.entity(entity).insert(TeleporterExitInitialized);
+
+        // One-way starts are not exits.
+        if start.is_some_and(|start| start.two_way == false) {
+            continue;
         }
 
-        commands.entity(entity).insert(TeleporterEndInitialized);
+        // Find teleporter info using room id or create a new one if not exists.
+        infos
+            .entry(world_id.room_id())
+            .or_default()
+            .entry(*teleporter)
+            .or_default()
+            .push(entity);
     }
 }
 
@@ -107,11 +176,19 @@ fn cleanup_teleporter_info(
 #[derive(Resource, Deref, DerefMut, Default)]
 pub struct TeleporterInfos(HashMap<RoomId, TeleporterInfo>);
 
-/// Maps [`Teleporter`] ID to [`TeleporterEnd`].
+/// Maps [`Teleporter`] ID to its exits, the [`TeleporterEnd`]s
+/// and two-way [`TeleporterStart`]s.
 #[derive(Deref, DerefMut, Default)]
-pub struct TeleporterInfo(HashMap<Teleporter, Entity>);
+pub struct TeleporterInfo(HashMap<Teleporter, Vec<Entity>>);
 
-/// Marker for [`Teleporter`]s that are initialized into
+/// Marker for [`Teleporter`] points that are initialized into
 /// the [`TeleporterInfos`].
 #[derive(Component)]
-pub struct TeleporterEndInitialized;
+pub struct TeleporterExitInitialized;
+
+/// Per player cooldown after teleporting, see [`TeleporterStart::player_cooldown`].
+pub type PlayerTeleportCooldown = AutoTimer<PlayerTeleport>;
+
+/// Marker for the [`PlayerTeleportCooldown`] timer.
+#[derive(Debug, Clone, Copy)]
+pub struct PlayerTeleport;
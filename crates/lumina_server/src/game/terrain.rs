@@ -0,0 +1,53 @@
+use bevy::prelude::*;
+use lightyear::prelude::*;
+use lumina_common::prelude::*;
+use lumina_shared::prelude::*;
+use lumina_terrain::prelude::*;
+use server::*;
+
+pub(super) struct TerrainPlugin;
+
+impl Plugin for TerrainPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (init_destroyed_tiles, replicate_tile_destruction));
+    }
+}
+
+fn init_destroyed_tiles(mut commands: Commands, q_maps: Query<Entity, Added<TerrainStates>>) {
+    for entity in q_maps.iter() {
+        commands.entity(entity).insert(DestroyedTiles::default());
+    }
+}
+
+/// Send destroyed tiles to all clients in the lobby of the map
+/// instead of replicating every tile entity.
+fn replicate_tile_destruction(
+    mut q_maps: Query<(&WorldIdx, &mut DestroyedTiles)>,
+    mut evr_destroyed: EventReader<TilesDestroyed>,
+    mut connection_manager: ResMut<ConnectionManager>,
+    room_manager: Res<RoomManager>,
+) {
+    for destroyed in evr_destroyed.read() {
+        let Ok((world_id, mut destroyed_tiles)) = q_maps.get_mut(destroyed.entity) else {
+            continue;
+        };
+
+        let tiles = destroyed
+            .tiles
+            .iter()
+            .map(|tile| [tile.x as u16, tile.y as u16])
+            .collect::<Vec<_>>();
+        destroyed_tiles.extend_from_slice(&tiles);
+
+        let _ = connection_manager.send_message_to_room::<OrdReliableChannel, _>(
+            &TerrainDestruction { tiles },
+            world_id.room_id(),
+            &room_manager,
+        );
+    }
+}
+
+/// Every tile destroyed since the terrain was spawned,
+/// sent to spectators that join a game in progress.
+#[derive(Component, Default, Deref, DerefMut, Debug)]
+pub struct DestroyedTiles(Vec<[u16; 2]>);
@@ -0,0 +1,256 @@
+//! Interest management: distance based relevance and replication priorities.
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use lightyear::prelude::*;
+use lumina_common::prelude::*;
+use lumina_common::settings::InterestSettings;
+use lumina_shared::game::prelude::*;
+use lumina_shared::prelude::*;
+use server::*;
+
+use crate::lobby::{ClientExitLobby, Lobby, Spectators};
+use crate::validation::Validated;
+
+pub(super) struct InterestPlugin;
+
+impl Plugin for InterestPlugin {
+    fn build(&self, app: &mut App) {
+        let settings = app.world().get_resource::<LuminaSettings>().unwrap();
+        let interest = settings.server.interest;
+
+        if interest.enabled == false {
+            return;
+        }
+
+        app.insert_resource(RelevanceTimer(Timer::new(
+            interest.update_interval(),
+            TimerMode::Repeating,
+        )))
+        // Player ammos are not replicated, they are simulated by every client
+        // from the replicated inputs which must stay relevant to keep weapons in sync.
+        // Turret ammos are replicated through `NeutralShot` and are culled with their turret.
+        .init_resource::<CameraViews>()
+        .add_systems(
+            Update,
+            (
+                mark_distance_culled::<OreType>,
+                mark_distance_culled::<LuminaType>,
+                mark_distance_culled::<Neutral>,
+                update_camera_views,
+            ),
+        )
+        .add_systems(PostUpdate, update_distance_relevance);
+    }
+}
+
+/// Mark replicated entities with `T` to be culled by distance.
+fn mark_distance_culled<T: Component>(
+    mut commands: Commands,
+    q_entities: Query<Entity, (With<T>, With<SyncTarget>, Without<DistanceCulled>)>,
+) {
+    for entity in q_entities.iter() {
+        commands.entity(entity).insert(DistanceCulled::default());
+    }
+}
+
+/// Store the latest [`CameraView`] of every client in a lobby.
+fn update_camera_views(
+    mut evr_camera_view: EventReader<Validated<CameraView>>,
+    mut evr_client_exit_lobby: EventReader<ClientExitLobby>,
+    mut camera_views: ResMut<CameraViews>,
+) {
+    for camera_view in evr_camera_view.read() {
+        camera_views.insert(*camera_view.context(), *camera_view.message());
+    }
+
+    for exit_client in evr_client_exit_lobby.read() {
+        camera_views.remove(&exit_client.id());
+    }
+}
+
+/// Gain or lose relevance of [`DistanceCulled`] entities for every client
+/// in the same lobby based on the area that the client can see.
+/// [`Spectators`] have no spaceship, they keep the relevance of every entity
+/// until their camera view is known.
+#[allow(clippy::too_many_arguments)]
+fn update_distance_relevance(
+    mut q_culled: Query<(&GlobalTransform, &WorldIdx, &mut DistanceCulled, Entity)>,
+    q_lobbies: Query<(&Lobby, Option<&Spectators>)>,
+    q_positions: Query<&Position, (With<Spaceship>, With<SourceEntity>)>,
+    mut relevance_manager: ResMut<RelevanceManager>,
+    mut timer: ResMut<RelevanceTimer>,
+    camera_views: Res<CameraViews>,
+    player_infos: Res<PlayerInfos>,
+    settings: Res<LuminaSettings>,
+    time: Res<Time>,
+) {
+    if timer.tick(time.delta()).just_finished() == false {
+        return;
+    }
+
+    let interest = settings.server.interest;
+
+    // Relevance area of every player and spectator, computed once per update.
+    let relevance_areas = q_lobbies
+        .iter()
+        .flat_map(|(lobby, spectators)| {
+            lobby
+                .iter()
+                .chain(spectators.into_iter().flat_map(|s| s.iter()))
+        })
+        .filter_map(|client_id| {
+            let spaceship_position = player_infos[PlayerInfoType::Spaceship]
+                .get(&PlayerId(*client_id))
+                .and_then(|e| q_positions.get(*e).ok())
+                .map(|position| position.0);
+
+            relevance_area(&interest, camera_views.get(client_id), spaceship_position)
+                .map(|area| (*client_id, area))
+        })
+        .collect::<HashMap<_, _>>();
+
+    for (transform, world_id, mut culled, entity) in q_culled.iter_mut() {
+        let Some((lobby, spectators)) = world_id.0.and_then(|e| q_lobbies.get(e).ok()) else {
+            continue;
+        };
+        let spectators = spectators.map(|s| s.as_slice()).unwrap_or_default();
+
+        let translation = transform.translation().xy();
+
+        // Forget clients that are no longer in the lobby.
+        culled.retain(|client_id, _| lobby.contains(client_id) || spectators.contains(client_id));
+
+        for &client_id in lobby.iter().chain(spectators) {
+            let relevant = match relevance_areas.get(&client_id) {
+                Some(area) => area.contains(translation),
+                // Spectators watch the whole map until their camera view arrives.
+                None if spectators.contains(&client_id) => true,
+                None => continue,
+            };
+
+            // Only notify the relevance manager on state change.
+            if culled.insert(client_id, relevant) == Some(relevant) {
+                continue;
+            }
+
+            match relevant {
+                true => relevance_manager.gain_relevance(client_id, entity),
+                false => relevance_manager.lose_relevance(client_id, entity),
+            };
+        }
+    }
+}
+
+/// Area in which entities are relevant to a client.
+///
+/// Covers the client's [`CameraView`] with [`InterestSettings::view_margin`],
+/// bounded by [`InterestSettings::relevance_half_extents`]. Falls back to the
+/// maximum area around the spaceship until the client sends its camera view.
+fn relevance_area(
+    interest: &InterestSettings,
+    camera_view: Option<&CameraView>,
+    spaceship_position: Option<Vec2>,
+) -> Option<Rect> {
+    match camera_view {
+        Some(view) => Some(Rect::from_center_half_size(
+            view.center,
+            (view.half_extents + interest.view_margin).min(interest.relevance_half_extents),
+        )),
+        None => spaceship_position
+            .map(|position| Rect::from_center_half_size(position, interest.relevance_half_extents)),
+    }
+}
+
+/// Replicated entity that is only relevant to clients that can see it,
+/// see [`relevance_area`].
+///
+/// Stores the last known relevance of each client.
+#[derive(Component, Default, Deref, DerefMut)]
+pub struct DistanceCulled(HashMap<ClientId, bool>);
+
+#[derive(Resource, Deref, DerefMut)]
+struct RelevanceTimer(Timer);
+
+/// Latest [`CameraView`] sent by each client.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct CameraViews(HashMap<ClientId, CameraView>);
+
+/// Priority of replicated entities. When the bandwidth budget is exceeded,
+/// groups with higher priority are sent first while the rest accumulate
+/// priority until they get sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplicationPriority {
+    /// Player inputs.
+    Input,
+    /// Spaceships and weapons.
+    Player,
+    /// Map entities (animated platforms, teleporters, etc.).
+    Map,
+    /// Ores and lumina.
+    Objective,
+}
+
+impl ReplicationPriority {
+    pub fn value(self) -> f32 {
+        match self {
+            ReplicationPriority::Input => 10.0,
+            ReplicationPriority::Player => 8.0,
+            ReplicationPriority::Map => 2.0,
+            ReplicationPriority::Objective => 1.0,
+        }
+    }
+
+    /// Apply priority to a [`ReplicationGroup`].
+    pub fn apply(self, group: ReplicationGroup) -> ReplicationGroup {
+        group.set_priority(self.value())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interest() -> InterestSettings {
+        InterestSettings {
+            relevance_half_extents: Vec2::new(1600.0, 1000.0),
+            view_margin: 100.0,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn area_follows_camera_view() {
+        let view = CameraView {
+            center: Vec2::new(500.0, 0.0),
+            half_extents: Vec2::new(640.0, 360.0),
+        };
+        let area = relevance_area(&interest(), Some(&view), Some(Vec2::ZERO)).unwrap();
+
+        assert_eq!(area.center(), view.center);
+        assert_eq!(area.half_size(), Vec2::new(740.0, 460.0));
+        assert!(area.contains(Vec2::new(1200.0, 0.0)));
+        assert!(area.contains(Vec2::new(0.0, 500.0)) == false);
+    }
+
+    #[test]
+    fn zoomed_out_view_is_bounded() {
+        let view = CameraView {
+            center: Vec2::ZERO,
+            half_extents: Vec2::new(10000.0, 10000.0),
+        };
+        let area = relevance_area(&interest(), Some(&view), None).unwrap();
+
+        assert_eq!(area.half_size(), interest().relevance_half_extents);
+    }
+
+    #[test]
+    fn spaceship_fallback_without_camera_view() {
+        let position = Vec2::new(100.0, 200.0);
+        let area = relevance_area(&interest(), None, Some(position)).unwrap();
+
+        assert_eq!(area.center(), position);
+        assert_eq!(area.half_size(), interest().relevance_half_extents);
+        assert!(relevance_area(&interest(), None, None).is_none());
+    }
+}
@@ -0,0 +1,334 @@
+//! Server-side lag compensation for ammo hits.
+//!
+//! Clients see other spaceships in the past (latency + interpolation delay).
+//! To make hits register where the shooter saw them, the server keeps a short
+//! history of spaceship states and tests ammo against the state perceived by
+//! the client that fired it.
+use std::collections::VecDeque;
+
+use avian2d::collision::contact_query;
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use bevy::utils::{Duration, HashSet};
+use lightyear::prelude::*;
+use lumina_common::prelude::*;
+use lumina_common::settings::LagCompensationSettings;
+use lumina_shared::prelude::*;
+use server::*;
+
+pub(super) struct LagCompensationPlugin;
+
+impl Plugin for LagCompensationPlugin {
+    fn build(&self, app: &mut App) {
+        let settings = app.world().get_resource::<LuminaSettings>().unwrap();
+        let lag_compensation = settings.server.lag_compensation;
+
+        if lag_compensation.enabled == false {
+            return;
+        }
+
+        let tick_duration = Duration::from_secs_f64(1.0 / settings.fixed_timestep_hz);
+        let max_rewind_ticks = duration_to_ticks(lag_compensation.max_rewind(), tick_duration);
+
+        app.insert_resource(RewindConfig {
+            max_rewind_ticks,
+            tick_duration,
+        })
+        .add_systems(Update, init_spaceship_history)
+        .add_systems(
+            FixedPostUpdate,
+            (record_spaceship_history, rewind_ammo_collision)
+                .chain()
+                .after(PhysicsSet::Sync),
+        )
+        .observe(clear_history_on_respawn);
+    }
+}
+
+fn init_spaceship_history(
+    mut commands: Commands,
+    q_spaceships: Query<Entity, (With<Spaceship>, With<SourceEntity>, Without<StateHistory>)>,
+    config: Res<RewindConfig>,
+) {
+    for entity in q_spaceships.iter() {
+        commands
+            .entity(entity)
+            .insert(StateHistory::new(config.max_rewind_ticks));
+    }
+}
+
+/// Record the state of every spaceship after the physics step.
+fn record_spaceship_history(mut q_spaceships: Query<(&Position, &Rotation, &mut StateHistory)>) {
+    for (position, rotation, mut history) in q_spaceships.iter_mut() {
+        history.record(*position, *rotation);
+    }
+}
+
+/// Replace the spaceship collisions of every active ammo with collisions
+/// against the spaceship states perceived by the client that fired the ammo.
+///
+/// Collisions with other entities (walls, ores, etc.) are left untouched
+/// and are all resolved by `ammo_collision` in the next fixed update.
+fn rewind_ammo_collision(
+    mut q_ammos: Query<
+        (
+            &Position,
+            &Rotation,
+            &Collider,
+            &PlayerId,
+            &WorldIdx,
+            &Visibility,
+            &mut CollidingEntities,
+        ),
+        (With<AmmoLifetime>, With<RigidBody>, With<SourceEntity>),
+    >,
+    q_spaceships: AliveQuery<(&Collider, &PlayerId, &WorldIdx, &StateHistory, Entity)>,
+    q_histories: Query<(), With<StateHistory>>,
+    connection_manager: Res<ConnectionManager>,
+    settings: Res<LuminaSettings>,
+    config: Res<RewindConfig>,
+) {
+    let interpolation_delay = settings.server.lag_compensation.interpolation_delay();
+
+    for (position, rotation, collider, player_id, world_id, viz, mut colliding) in
+        q_ammos.iter_mut()
+    {
+        // Skip already hidden ammos.
+        if viz == Visibility::Hidden {
+            continue;
+        }
+
+        // The shooter saw other spaceships a full round trip plus
+        // the interpolation delay in the past.
+        let rtt = connection_manager
+            .connection(player_id.0)
+            .map(|connection| connection.rtt())
+            .unwrap_or_default();
+        let rewind_ticks = config.rewind_ticks(rtt + interpolation_delay);
+
+        let mut rewound_hits = Vec::new();
+        for (spaceship_collider, spaceship_id, spaceship_world, history, entity) in
+            q_spaceships.iter()
+        {
+            if spaceship_id == player_id || spaceship_world != world_id {
+                continue;
+            }
+
+            let Some((spaceship_position, spaceship_rotation)) = history.rewind(rewind_ticks)
+            else {
+                continue;
+            };
+
+            if contact_query::intersection_test(
+                collider,
+                *position,
+                *rotation,
+                spaceship_collider,
+                spaceship_position,
+                spaceship_rotation,
+            )
+            .unwrap_or_default()
+            {
+                rewound_hits.push(entity);
+            }
+        }
+
+        // Only trigger change detection when the collisions actually differ.
+        if let Some(collisions) =
+            replace_spaceship_hits(&colliding, &rewound_hits, |e| q_histories.contains(e))
+        {
+            **colliding = collisions;
+        }
+    }
+}
+
+/// Replace the spaceship hits of `colliding` with `rewound_hits`,
+/// [`None`] if the spaceship hits are already the same.
+fn replace_spaceship_hits(
+    colliding: &HashSet<Entity>,
+    rewound_hits: &[Entity],
+    is_spaceship: impl Fn(Entity) -> bool,
+) -> Option<HashSet<Entity>> {
+    let live_hits = colliding.iter().filter(|e| is_spaceship(**e)).count();
+    if live_hits == rewound_hits.len() && rewound_hits.iter().all(|e| colliding.contains(e)) {
+        return None;
+    }
+
+    let mut collisions = colliding
+        .iter()
+        .copied()
+        .filter(|e| is_spaceship(*e) == false)
+        .collect::<HashSet<_>>();
+    collisions.extend(rewound_hits);
+
+    Some(collisions)
+}
+
+/// Spaceships are moved to a spawn point on respawn,
+/// older states should not be hittable anymore.
+fn clear_history_on_respawn(
+    trigger: Trigger<OnRemove, Dead>,
+    mut q_histories: Query<&mut StateHistory>,
+) {
+    if let Ok(mut history) = q_histories.get_mut(trigger.entity()) {
+        history.clear();
+    }
+}
+
+fn duration_to_ticks(duration: Duration, tick_duration: Duration) -> usize {
+    (duration.as_secs_f32() / tick_duration.as_secs_f32()).round() as usize
+}
+
+#[derive(Resource, Debug, Clone, Copy)]
+struct RewindConfig {
+    /// Maximum number of ticks that can be rewound.
+    max_rewind_ticks: usize,
+    tick_duration: Duration,
+}
+
+impl RewindConfig {
+    /// Number of ticks to rewind for a delay, clamped to [`Self::max_rewind_ticks`].
+    fn rewind_ticks(&self, delay: Duration) -> usize {
+        duration_to_ticks(delay, self.tick_duration).min(self.max_rewind_ticks)
+    }
+}
+
+/// Ring buffer of the [`Position`] and [`Rotation`] of an entity,
+/// one entry per fixed tick, newest first.
+#[derive(Component, Debug, Clone)]
+pub struct StateHistory {
+    states: VecDeque<(Position, Rotation)>,
+    capacity: usize,
+}
+
+impl StateHistory {
+    pub fn new(max_rewind_ticks: usize) -> Self {
+        // Include the current tick.
+        let capacity = max_rewind_ticks + 1;
+
+        Self {
+            states: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn record(&mut self, position: Position, rotation: Rotation) {
+        if self.states.len() == self.capacity {
+            self.states.pop_back();
+        }
+
+        self.states.push_front((position, rotation));
+    }
+
+    /// Get the state `ticks` ago, or the oldest state if
+    /// the history does not go back that far.
+    pub fn rewind(&self, ticks: usize) -> Option<(Position, Rotation)> {
+        self.states
+            .get(ticks)
+            .or_else(|| self.states.back())
+            .copied()
+    }
+
+    pub fn clear(&mut self) {
+        self.states.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(x: f32) -> (Position, Rotation) {
+        (Position::from_xy(x, 0.0), Rotation::default())
+    }
+
+    fn history(max_rewind_ticks: usize, xs: &[f32]) -> StateHistory {
+        let mut history = StateHistory::new(max_rewind_ticks);
+        for &x in xs {
+            let (position, rotation) = state(x);
+            history.record(position, rotation);
+        }
+        history
+    }
+
+    fn rewound_x(history: &StateHistory, ticks: usize) -> Option<f32> {
+        history.rewind(ticks).map(|(position, _)| position.x)
+    }
+
+    #[test]
+    fn rewind_newest_first() {
+        let history = history(4, &[0.0, 1.0, 2.0]);
+
+        assert_eq!(rewound_x(&history, 0), Some(2.0));
+        assert_eq!(rewound_x(&history, 1), Some(1.0));
+        assert_eq!(rewound_x(&history, 2), Some(0.0));
+    }
+
+    #[test]
+    fn rewind_wraps_around_capacity() {
+        // Capacity of 3 (2 rewind ticks + the current tick).
+        let history = history(2, &[0.0, 1.0, 2.0, 3.0, 4.0]);
+
+        assert_eq!(rewound_x(&history, 0), Some(4.0));
+        assert_eq!(rewound_x(&history, 2), Some(2.0));
+        // Older states are dropped, fall back to the oldest one.
+        assert_eq!(rewound_x(&history, 3), Some(2.0));
+        assert_eq!(rewound_x(&history, 100), Some(2.0));
+    }
+
+    #[test]
+    fn rewind_empty_or_cleared() {
+        let mut history = history(2, &[0.0, 1.0]);
+        history.clear();
+
+        assert_eq!(rewound_x(&history, 0), None);
+        assert_eq!(rewound_x(&StateHistory::new(2), 1), None);
+    }
+
+    #[test]
+    fn rewind_ticks_clamped() {
+        let config = RewindConfig {
+            max_rewind_ticks: 10,
+            tick_duration: Duration::from_millis(16),
+        };
+
+        assert_eq!(config.rewind_ticks(Duration::ZERO), 0);
+        assert_eq!(config.rewind_ticks(Duration::from_millis(80)), 5);
+        assert_eq!(config.rewind_ticks(Duration::from_secs(2)), 10);
+    }
+
+    #[test]
+    fn replace_live_hits_with_rewound_hits() {
+        let wall = Entity::from_raw(0);
+        let live_spaceship = Entity::from_raw(1);
+        let rewound_spaceship = Entity::from_raw(2);
+        let is_spaceship = |e: Entity| e == live_spaceship || e == rewound_spaceship;
+
+        let colliding = HashSet::from_iter([wall, live_spaceship]);
+        let collisions =
+            replace_spaceship_hits(&colliding, &[rewound_spaceship], is_spaceship).unwrap();
+
+        assert_eq!(collisions, HashSet::from_iter([wall, rewound_spaceship]));
+
+        // Spaceship hits are removed when nothing is hit in the past.
+        let collisions = replace_spaceship_hits(&colliding, &[], is_spaceship).unwrap();
+        assert_eq!(collisions, HashSet::from_iter([wall]));
+    }
+
+    #[test]
+    fn keep_identical_hits() {
+        let wall = Entity::from_raw(0);
+        let spaceship = Entity::from_raw(1);
+        let is_spaceship = |e: Entity| e == spaceship;
+
+        let colliding = HashSet::from_iter([wall, spaceship]);
+        assert_eq!(
+            replace_spaceship_hits(&colliding, &[spaceship], is_spaceship),
+            None
+        );
+        assert_eq!(
+            replace_spaceship_hits(&HashSet::from_iter([wall]), &[], is_spaceship),
+            None
+        );
+    }
+}
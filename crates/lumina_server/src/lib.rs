@@ -11,10 +11,15 @@ use server::*;
 
 mod blueprints;
 mod game;
+mod interest;
+mod lag_compensation;
 mod lobby;
 mod player;
+mod shutdown;
 mod source_entity;
+mod tuning;
 mod ui;
+mod validation;
 
 pub struct ServerPlugin;
 
@@ -37,6 +42,11 @@ impl Plugin for ServerPlugin {
             lobby::LobbyPlugin,
             player::PlayerPlugin,
             game::GamePlugin,
+            interest::InterestPlugin,
+            lag_compensation::LagCompensationPlugin,
+            validation::ValidationPlugin,
+            shutdown::ShutdownPlugin,
+            tuning::TuningPlugin,
         ))
         .init_resource::<LobbyInfos>()
         .add_systems(Startup, start_server);
@@ -84,10 +94,23 @@ fn server_config(settings: &LuminaSettings) -> ServerConfig {
             send_interval: settings.server_replication_interval(),
             ..default()
         },
+        packet: packet_config(settings),
         ..default()
     }
 }
 
+/// Create the lightyear [`PacketConfig`] with the per client bandwidth budget.
+fn packet_config(settings: &LuminaSettings) -> PacketConfig {
+    match settings.server.interest.send_bandwidth_cap {
+        Some(send_bandwidth_cap) => PacketConfig {
+            send_bandwidth_cap,
+            bandwidth_cap_enabled: true,
+            ..default()
+        },
+        None => PacketConfig::default(),
+    }
+}
+
 #[derive(Resource, Default, Debug, Deref, DerefMut)]
 pub struct LobbyInfos(HashMap<ClientId, Entity>);
 
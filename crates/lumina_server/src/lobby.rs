@@ -4,12 +4,15 @@ use lumina_common::prelude::*;
 use lumina_shared::prelude::*;
 use server::*;
 use smallvec::SmallVec;
+use strum::EnumCount;
 
 mod in_game;
 mod matchmaking;
 mod sandbox;
+mod spectator;
 
 use crate::player::{objective::ObjectiveAreaManager, ResetSpaceship};
+use crate::validation::Validated;
 
 use super::LobbyInfos;
 
@@ -21,6 +24,7 @@ impl Plugin for LobbyPlugin {
             sandbox::SandboxPlugin,
             matchmaking::MatchmakingPlugin,
             in_game::InGamePlugin,
+            spectator::SpectatorPlugin,
         ))
         .add_event::<ClientExitLobby>()
         .add_event::<LobbyRemoval>()
@@ -40,14 +44,23 @@ impl Plugin for LobbyPlugin {
 
 fn cleanup_empty_lobbies(
     mut commands: Commands,
-    q_lobbies: Query<(Entity, &Lobby), (Changed<Lobby>, Without<LobbyInGame>, Without<LobbyFull>)>,
+    q_lobbies: Query<
+        (Entity, &Lobby, Option<&Spectators>),
+        (Changed<Lobby>, Without<LobbyInGame>, Without<LobbyFull>),
+    >,
     mut evw_lobby_removal: EventWriter<LobbyRemoval>,
+    mut evw_client_exit_lobby: EventWriter<ClientExitLobby>,
 ) {
-    for (entity, lobby) in q_lobbies.iter() {
+    for (entity, lobby, spectators) in q_lobbies.iter() {
         if lobby.is_empty() {
             info!("Removing empty lobby: {entity:?}");
             commands.entity(entity).despawn_recursive();
             evw_lobby_removal.send(LobbyRemoval(entity.room_id()));
+
+            // Nothing left to watch.
+            for id in spectators.into_iter().flat_map(|s| s.iter()) {
+                evw_client_exit_lobby.send(ClientExitLobby(*id));
+            }
         }
     }
 }
@@ -84,14 +97,14 @@ fn handle_disconnections(
 }
 
 fn handle_exit_lobby(
-    mut evr_exit_lobby: EventReader<MessageEvent<ExitLobby>>,
+    mut evr_exit_lobby: EventReader<Validated<ExitLobby>>,
     mut evw_client_exit_lobby: EventWriter<ClientExitLobby>,
 ) {
     if evr_exit_lobby.is_empty() == false {
         evw_client_exit_lobby.send_batch(
             evr_exit_lobby
                 .read()
-                .map(|exit| ClientExitLobby(exit.context)),
+                .map(|exit| ClientExitLobby(*exit.context())),
         );
     }
 }
@@ -99,7 +112,7 @@ fn handle_exit_lobby(
 fn execute_exit_lobby(
     mut commands: Commands,
     mut evr_client_exit_lobby: EventReader<ClientExitLobby>,
-    mut q_lobbies: Query<&mut Lobby>,
+    mut q_lobbies: Query<(&mut Lobby, Option<&mut Spectators>)>,
     mut room_manager: ResMut<RoomManager>,
     mut player_infos: ResMut<PlayerInfos>,
     mut lobby_infos: ResMut<LobbyInfos>,
@@ -110,10 +123,13 @@ fn execute_exit_lobby(
         if let Some(lobby_entity) = lobby_infos.remove(&client_id) {
             let room_id = lobby_entity.room_id();
             // Remove client from the lobby.
-            if let Ok(mut lobby) = q_lobbies.get_mut(lobby_entity) {
-                lobby.remove_client(&client_id);
-                // Now that someone left, the lobby is no longer full
-                commands.entity(lobby_entity).remove::<LobbyFull>();
+            if let Ok((mut lobby, spectators)) = q_lobbies.get_mut(lobby_entity) {
+                if lobby.remove_client(&client_id) {
+                    // Now that someone left, the lobby is no longer full
+                    commands.entity(lobby_entity).remove::<LobbyFull>();
+                } else if let Some(mut spectators) = spectators {
+                    spectators.remove_client(&client_id);
+                }
             }
 
             // Remove client from the room.
@@ -163,20 +179,26 @@ pub(super) struct LobbyBundle {
     pub lobby: Lobby,
     pub size: LobbySize,
     pub seed: LobbySeed,
+    pub rng: LobbyRng,
     pub world_id: WorldIdx,
     pub spatial: SpatialBundle,
     pub objective_manager: ObjectiveAreaManager,
+    pub spectators: Spectators,
 }
 
 impl LobbyBundle {
     pub fn new(initial_client: ClientId, size: u8, seed: u32, world_entity: Entity) -> Self {
+        let mut rng = LobbyRng::new(seed);
+
         Self {
             size: LobbySize(size),
             lobby: Lobby(SmallVec::from_slice(&[initial_client])),
             seed: LobbySeed(seed),
+            objective_manager: ObjectiveAreaManager::new(&mut rng),
+            rng,
             world_id: WorldIdx::from_entity(world_entity),
             spatial: SpatialBundle::default(),
-            objective_manager: ObjectiveAreaManager::default(),
+            spectators: Spectators::default(),
         }
     }
 }
@@ -186,6 +208,24 @@ impl LobbyBundle {
 pub struct Lobby(SmallVec<[ClientId; 6]>);
 
 impl Lobby {
+    /// Returns false if the client is not inside the lobby.
+    pub fn remove_client(&mut self, client_id: &ClientId) -> bool {
+        match self.iter().position(|id| id == client_id) {
+            Some(index) => {
+                self.swap_remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// A vec of clients watching the lobby without a spaceship.
+/// Spectators do not count towards the [`LobbySize`].
+#[derive(Component, Default, Debug, Deref, DerefMut)]
+pub struct Spectators(SmallVec<[ClientId; 4]>);
+
+impl Spectators {
     pub fn remove_client(&mut self, client_id: &ClientId) {
         if let Some(index) = self.iter().position(|id| id == client_id) {
             self.swap_remove(index);
@@ -196,6 +236,50 @@ impl Lobby {
 #[derive(Component, Debug, Deref, DerefMut)]
 pub struct LobbySeed(pub u32);
 
+/// Seeded random streams of a lobby, derived from the [`LobbySeed`].
+///
+/// Every kind of gameplay randomness draws from its own [`RngStream`] so that
+/// adding a draw to one stream does not shift the values of the others,
+/// making a match reproducible from its seed.
+#[derive(Component)]
+pub struct LobbyRng {
+    streams: [XorShift32; RngStream::COUNT],
+}
+
+impl LobbyRng {
+    pub fn new(seed: u32) -> Self {
+        Self {
+            streams: std::array::from_fn(|i| {
+                // Golden ratio increment to spread the sub-seeds apart.
+                XorShift32::new(seed ^ (i as u32 + 1).wrapping_mul(0x9E3779B9))
+            }),
+        }
+    }
+
+    pub fn stream(&mut self, stream: RngStream) -> &mut XorShift32 {
+        &mut self.streams[stream as usize]
+    }
+
+    /// Create an independent generator seeded from a stream,
+    /// for components that own their randomness.
+    pub fn fork(&mut self, stream: RngStream) -> XorShift32 {
+        XorShift32::new(self.stream(stream).next_u32())
+    }
+}
+
+#[derive(EnumCount, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RngStream {
+    /// Lumina value of destroyed ores.
+    Ores,
+    /// Selection of the active objective area
+    /// (forked into the [`ObjectiveAreaManager`]).
+    Objectives,
+    /// Positions of dropped lumina.
+    Drops,
+    /// Roaming waypoints of neutral drones.
+    Neutrals,
+}
+
 #[derive(Component, Debug, Deref, DerefMut)]
 pub struct LobbySize(pub u8);
 
@@ -225,3 +309,33 @@ pub struct LobbyRemoval(pub RoomId);
 /// Trigger [`ResetSpaceship`] for all spaceship in the lobby.
 #[derive(Event, Debug)]
 pub struct ResetSpaceshipsInLobby;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draws(rng: &mut LobbyRng) -> Vec<u32> {
+        [RngStream::Ores, RngStream::Drops, RngStream::Ores]
+            .into_iter()
+            .map(|stream| rng.stream(stream).next_u32())
+            .chain(std::iter::once(rng.fork(RngStream::Objectives).next_u32()))
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_same_sequence() {
+        assert_eq!(draws(&mut LobbyRng::new(7)), draws(&mut LobbyRng::new(7)));
+        assert_ne!(draws(&mut LobbyRng::new(7)), draws(&mut LobbyRng::new(8)));
+    }
+
+    #[test]
+    fn streams_are_independent() {
+        let mut rng = LobbyRng::new(7);
+        let ores = rng.stream(RngStream::Ores).next_u32();
+
+        // Drawing from another stream first does not shift the ores stream.
+        let mut other = LobbyRng::new(7);
+        other.stream(RngStream::Drops).next_u32();
+        assert_eq!(other.stream(RngStream::Ores).next_u32(), ores);
+    }
+}
@@ -1,13 +1,16 @@
 use bevy::prelude::*;
-use blenvy::*;
+use bevy::utils::HashMap;
 use lightyear::prelude::*;
 use lumina_common::prelude::*;
+use lumina_shared::game::prelude::*;
 use lumina_shared::prelude::*;
 use server::*;
 
-use crate::player::objective::{ObjectiveAreaManager, ResetObjectiveArea, OBJECTIVE_AREA_COUNT};
+use crate::player::objective::{ObjectiveAreaManager, ObjectiveAreasReady, ResetObjectiveArea};
+use crate::validation::Validated;
+use crate::LobbyInfos;
 
-use super::{LobbyFull, LobbyInGame, ResetSpaceshipsInLobby};
+use super::{Lobby, LobbyFull, LobbyInGame, LobbySeed, LobbySize, ResetSpaceshipsInLobby};
 
 pub(super) struct InGamePlugin;
 
@@ -15,110 +18,401 @@ impl Plugin for InGamePlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             Update,
-            (start_countdown, start_game, manage_objective_areas),
+            (
+                start_map_vote,
+                handle_map_votes,
+                end_map_vote,
+                handle_map_loaded,
+                start_game,
+                manage_objective_areas,
+            ),
         );
     }
 }
 
-fn start_countdown(mut commands: Commands, q_lobbies: Query<Entity, Added<LobbyFull>>) {
-    for entity in q_lobbies.iter() {
-        // Initialize the countdown timer for 5 seconds if it's not already set
-        commands
-            .entity(entity)
-            .insert(CountdownTimer(Timer::from_seconds(5.0, TimerMode::Once)));
+/// Start a vote between the next maps of the rotation once the lobby is full.
+fn start_map_vote(
+    mut commands: Commands,
+    q_full_lobbies: Query<Entity, Added<LobbyFull>>,
+    q_lobbies: Query<(&LobbySeed, &LobbySize), (With<LobbyFull>, Without<LobbyInGame>)>,
+    mut connection_manager: ResMut<ConnectionManager>,
+    room_manager: Res<RoomManager>,
+    settings: Res<LuminaSettings>,
+    registry: MapRegistry,
+    mut rotation: Local<usize>,
+    mut queue: Local<Vec<Entity>>,
+) {
+    // Use a queuing system so that no lobby is missed if the registry is not loaded yet.
+    for entity in q_full_lobbies.iter() {
+        if queue.contains(&entity) == false {
+            queue.push(entity);
+        }
+    }
+
+    let Some(registry) = registry.get() else {
+        if queue.is_empty() == false && q_full_lobbies.is_empty() == false {
+            info!("Map registry is not loaded yet, waiting to select a map.");
+        }
+        return;
+    };
+
+    for entity in queue.drain(..) {
+        // The lobby might have been emptied while waiting for the registry.
+        let Ok((seed, size)) = q_lobbies.get(entity) else {
+            continue;
+        };
+
+        let candidates = map_candidates(registry, &settings, size, &mut rotation)
+            .into_iter()
+            .map(|info| SelectedMap {
+                map_name: info.name.clone(),
+                map: info.game_map(**seed),
+            })
+            .collect::<Vec<_>>();
+
+        if candidates.is_empty() {
+            error!("Map registry is empty, lobby {entity} can't start.");
+            continue;
+        }
+
+        // Nothing to vote for with a single map.
+        let duration = match candidates.len() {
+            1 => 0.0,
+            _ => settings.server.lobby.vote_duration,
+        };
+
+        if duration > 0.0 {
+            let _ = connection_manager.send_message_to_room::<OrdReliableChannel, _>(
+                &MapVoteStart {
+                    maps: candidates.iter().map(|c| c.map_name.clone()).collect(),
+                    duration,
+                },
+                entity.room_id(),
+                &room_manager,
+            );
+        }
+
+        commands.entity(entity).insert(MapVoting {
+            candidates,
+            votes: HashMap::default(),
+            timer: Timer::from_seconds(duration, TimerMode::Once),
+        });
+    }
+}
+
+/// Record the votes of the lobby players, voting again replaces the previous vote.
+fn handle_map_votes(
+    mut evr_map_vote: EventReader<Validated<MapVote>>,
+    mut q_votings: Query<(&mut MapVoting, &Lobby)>,
+    lobby_infos: Res<LobbyInfos>,
+) {
+    for map_vote in evr_map_vote.read() {
+        let client_id = *map_vote.context();
+
+        // Spectators do not vote.
+        let Some(mut voting) = lobby_infos
+            .get(&client_id)
+            .and_then(|e| q_votings.get_mut(*e).ok())
+            .filter(|(_, lobby)| lobby.contains(&client_id))
+            .map(|(voting, _)| voting)
+        else {
+            continue;
+        };
+
+        let map_name = &map_vote.message().map_name;
+        match voting
+            .candidates
+            .iter()
+            .position(|candidate| candidate.map_name == *map_name)
+        {
+            Some(index) => {
+                voting.votes.insert(client_id, index);
+            }
+            None => warn!("Client {client_id} voted for {map_name:?} which is not a candidate."),
+        }
     }
 }
 
-/// Manages the countdown and starts the game for each lobby individually
+/// Select the most voted map once every player voted or the vote times out,
+/// and let the clients preload it during the countdown.
+fn end_map_vote(
+    mut commands: Commands,
+    mut q_votings: Query<(&mut MapVoting, &Lobby, Entity), (With<LobbyFull>, Without<LobbyInGame>)>,
+    mut connection_manager: ResMut<ConnectionManager>,
+    room_manager: Res<RoomManager>,
+    settings: Res<LuminaSettings>,
+    time: Res<Time>,
+) {
+    let lobby_settings = settings.server.lobby;
+
+    for (mut voting, lobby, entity) in q_votings.iter_mut() {
+        voting.timer.tick(time.delta());
+
+        let all_voted = lobby.iter().all(|id| voting.votes.contains_key(id));
+        if voting.timer.finished() == false && all_voted == false {
+            continue;
+        }
+
+        let SelectedMap { map_name, map } = voting.winner().clone();
+        info!("Lobby {entity} will play {map_name}.");
+
+        let _ = connection_manager.send_message_to_room::<OrdReliableChannel, _>(
+            &PreloadMap {
+                map_name: map_name.clone(),
+                map: map.clone(),
+            },
+            entity.room_id(),
+            &room_manager,
+        );
+
+        commands.entity(entity).remove::<MapVoting>().insert((
+            CountdownTimer(Timer::from_seconds(
+                lobby_settings.countdown,
+                TimerMode::Once,
+            )),
+            MapLoadTimeout(Timer::from_seconds(
+                lobby_settings.map_load_timeout,
+                TimerMode::Once,
+            )),
+            SelectedMap { map_name, map },
+            LoadedClients::default(),
+        ));
+    }
+}
+
+/// Track the clients that finished preloading the map.
+fn handle_map_loaded(
+    mut evr_map_loaded: EventReader<Validated<MapLoaded>>,
+    mut q_lobbies: Query<&mut LoadedClients>,
+    lobby_infos: Res<LobbyInfos>,
+) {
+    for map_loaded in evr_map_loaded.read() {
+        let client_id = *map_loaded.context();
+
+        let Some(mut loaded_clients) = lobby_infos
+            .get(&client_id)
+            .and_then(|e| q_lobbies.get_mut(*e).ok())
+        else {
+            continue;
+        };
+
+        if loaded_clients.contains(&client_id) == false {
+            loaded_clients.push(client_id);
+        }
+    }
+}
+
+/// Start the game for each lobby individually once the countdown ends
+/// and every client loaded the map (or the load timeout is reached).
 fn start_game(
     mut commands: Commands,
-    mut q_lobbies: Query<(&mut CountdownTimer, Entity), With<LobbyFull>>,
+    mut q_lobbies: Query<
+        (
+            &mut CountdownTimer,
+            &mut MapLoadTimeout,
+            &SelectedMap,
+            &LoadedClients,
+            &Lobby,
+            Entity,
+        ),
+        With<LobbyFull>,
+    >,
     q_spaceships: Query<Entity, (With<Spaceship>, With<SourceEntity>, With<SpawnPointEntity>)>,
     mut connection_manager: ResMut<ConnectionManager>,
     room_manager: Res<RoomManager>,
     time: Res<Time>,
 ) {
-    for (mut countdown_timer, entity) in q_lobbies.iter_mut() {
-        // When the countdown reaches zero, start the game.
-        if countdown_timer.tick(time.delta()).just_finished() {
-            // Spawn map and send messages to notify clients.
-            commands
-                .spawn((MapType::AbandonedFactory.info(), SpawnBlueprint))
-                .set_parent(entity);
+    for (mut countdown_timer, mut load_timeout, selected_map, loaded_clients, lobby, entity) in
+        q_lobbies.iter_mut()
+    {
+        countdown_timer.tick(time.delta());
+        load_timeout.tick(time.delta());
 
-            let _ = connection_manager.send_message_to_room::<OrdReliableChannel, _>(
-                &StartGame,
-                entity.room_id(),
-                &room_manager,
+        if countdown_timer.finished() == false {
+            continue;
+        }
+
+        let all_loaded = lobby.iter().all(|id| loaded_clients.contains(id));
+        if all_loaded == false {
+            if load_timeout.finished() == false {
+                continue;
+            }
+
+            warn!(
+                "Lobby {entity} is starting before clients {:?} loaded the map.",
+                lobby
+                    .iter()
+                    .filter(|id| loaded_clients.contains(id) == false)
+                    .collect::<Vec<_>>()
             );
+        }
+
+        let SelectedMap { map_name, map } = selected_map.clone();
+        info!("Lobby {entity} is playing {map_name}.");
+
+        // Spawn map and send messages to notify clients.
+        map.spawn(&mut commands).set_parent(entity);
+
+        let _ = connection_manager.send_message_to_room::<OrdReliableChannel, _>(
+            &StartGame { map_name, map },
+            entity.room_id(),
+            &room_manager,
+        );
 
-            // Trigger spaceship reset.
-            commands.trigger_targets(ResetSpaceshipsInLobby, entity);
+        // Trigger spaceship reset.
+        commands.trigger_targets(ResetSpaceshipsInLobby, entity);
 
+        commands
+            .entity(entity)
+            .insert(LobbyInGame)
+            // Remove the countdown states after the game starts,
+            // the selected map is kept for late spectators.
+            .remove::<(CountdownTimer, MapLoadTimeout, LoadedClients)>();
+
+        for spaceship_entity in q_spaceships.iter() {
+            println!("\n\nremove spaceship spawn point for {spaceship_entity}");
             commands
-                .entity(entity)
-                .insert(LobbyInGame)
-                // Remove the countdown timer after the game starts.
-                .remove::<CountdownTimer>();
-
-            for spaceship_entity in q_spaceships.iter() {
-                println!("\n\nremove spaceship spawn point for {spaceship_entity}");
-                commands
-                    .entity(spaceship_entity)
-                    .remove::<SpawnPointEntity>();
-            }
+                .entity(spaceship_entity)
+                .remove::<SpawnPointEntity>();
+        }
+
+        info!("Game started for lobby {entity}!");
+    }
+}
 
-            info!("Game started for lobby {entity}!");
+/// The pinned map if any, otherwise the next maps of the rotation that support
+/// the lobby size (or the registry default if none of them does).
+fn map_candidates<'a>(
+    registry: &'a MapRegistryAsset,
+    settings: &LuminaSettings,
+    size: &LobbySize,
+    rotation: &mut usize,
+) -> Vec<&'a MapInfo> {
+    if let Some(name) = &settings.server.pinned_map {
+        match registry.find(name) {
+            Some(info) => return vec![info],
+            None => warn!("Pinned map {name:?} is not in the registry."),
         }
     }
+
+    let team_size = **size / 2;
+    let eligible = registry.supporting(team_size).collect::<Vec<_>>();
+    if eligible.is_empty() {
+        error!(
+            "No map in the registry supports lobby size {}, falling back to {:?}.",
+            **size, registry.default
+        );
+        return registry.default_map().into_iter().collect();
+    }
+
+    let candidates = (0..eligible.len().min(MAP_VOTE_CANDIDATES))
+        .map(|i| eligible[(*rotation + i) % eligible.len()])
+        .collect();
+    *rotation += 1;
+    candidates
 }
 
+/// Announce the next objective area ahead of time, while the players are
+/// still clearing the last ores of the active one.
 fn manage_objective_areas(
     mut commands: Commands,
-    // Manage sandbox managers only.
-    mut q_manager: Query<(&mut ObjectiveAreaManager, Entity), With<LobbyInGame>>,
+    // Manage lobby managers only.
+    mut q_manager: Query<
+        (&mut ObjectiveAreaManager, Entity),
+        (With<LobbyInGame>, With<ObjectiveAreasReady>),
+    >,
     // Do no reset already resetting areas.
     q_areas: Query<
         (&ObjectiveArea, Has<ActiveObjectiveArea>, &GlobalTransform),
         Without<ResetObjectiveArea>,
     >,
+    q_all_areas: Query<(&ObjectiveArea, &GlobalTransform)>,
     mut connection: ResMut<ConnectionManager>,
     room_manager: Res<RoomManager>,
 ) {
-    for (mut manager, lobby_entity) in q_manager
-        .iter_mut()
-        // TODO: Use a better marker to indicate that the objective area manager is ready.
-        .filter(|(m, _)| m.areas.len() == OBJECTIVE_AREA_COUNT)
-    {
-        let area_entity = manager.areas[manager.selected_index];
-        if let Ok((area, is_active, transform)) = q_areas.get(area_entity) {
-            let depleted = area.ores.unused().is_empty();
-            if depleted == false {
-                continue;
-            }
+    for (mut manager, lobby_entity) in q_manager.iter_mut() {
+        let selected_index = manager.selected_index;
 
-            if is_active {
-                // Active area has been depleted, choose a new active area!
-                commands.entity(area_entity).remove::<ActiveObjectiveArea>();
-                let mut new_index = rand::random_range(0..manager.areas.len());
-                if new_index == manager.selected_index {
-                    new_index = (new_index + 1) % manager.areas.len();
+        // Previous areas stay active until they are depleted.
+        for (index, &area_entity) in manager.areas.iter().enumerate() {
+            if let Ok((area, true, _)) = q_areas.get(area_entity) {
+                if index != selected_index && area.ores.unused().is_empty() {
+                    commands.entity(area_entity).remove::<ActiveObjectiveArea>();
                 }
-
-                manager.selected_index = new_index;
-            } else {
-                // Reset in 5 seconds.
-                commands.entity(area_entity).insert((
-                    ResetObjectiveArea(Timer::from_seconds(5.0, TimerMode::Once)),
-                    ActiveObjectiveArea,
-                ));
-                let _ = connection.send_message_to_room::<OrdReliableChannel, _>(
-                    &ObjectivePosition(transform.translation().xy()),
-                    lobby_entity.room_id(),
-                    &room_manager,
-                );
             }
         }
+
+        let Some(&area_entity) = manager.areas.get(selected_index) else {
+            continue;
+        };
+        let Ok((area, is_active, transform)) = q_areas.get(area_entity) else {
+            continue;
+        };
+
+        if is_active == false {
+            // Announce the area ahead of time, its ores respawn after the countdown.
+            commands.entity(area_entity).insert((
+                ResetObjectiveArea(Timer::from_seconds(OBJECTIVE_COUNTDOWN, TimerMode::Once)),
+                ActiveObjectiveArea,
+            ));
+            let _ = connection.send_message_to_room::<OrdReliableChannel, _>(
+                &ObjectiveAnnouncement {
+                    position: transform.translation().xy(),
+                    countdown: OBJECTIVE_COUNTDOWN,
+                },
+                lobby_entity.room_id(),
+                &room_manager,
+            );
+            continue;
+        }
+
+        let remaining = area.ores.unused().len();
+        if remaining > ANNOUNCE_REMAINING_ORES {
+            continue;
+        }
+
+        // The next area is announced on the next frame.
+        let next_index = manager.pick_next(&q_all_areas);
+        if next_index == Some(selected_index) && remaining == 0 {
+            // The only area left to pick, announce it again once depleted.
+            commands.entity(area_entity).remove::<ActiveObjectiveArea>();
+        }
+    }
+}
+
+/// Maximum number of maps to vote between.
+const MAP_VOTE_CANDIDATES: usize = 3;
+
+/// Seconds between an objective area being announced and its ores respawning.
+const OBJECTIVE_COUNTDOWN: f32 = 5.0;
+
+/// The next objective area is announced once the active one has this many ores left.
+const ANNOUNCE_REMAINING_ORES: usize = 1;
+
+/// Maps that the lobby players vote between before the countdown.
+#[derive(Component)]
+struct MapVoting {
+    candidates: Vec<SelectedMap>,
+    /// Index of the candidate voted by each player.
+    votes: HashMap<ClientId, usize>,
+    timer: Timer,
+}
+
+impl MapVoting {
+    /// The candidate with the most votes, ties go to the earliest one in the rotation.
+    fn winner(&self) -> &SelectedMap {
+        let mut counts = vec![0; self.candidates.len()];
+        for &index in self.votes.values() {
+            counts[index] += 1;
+        }
+
+        let index = counts
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|(_, count)| **count)
+            .map_or(0, |(index, _)| index);
+        &self.candidates[index]
     }
 }
 
@@ -126,5 +420,58 @@ fn manage_objective_areas(
 #[derive(Component, Deref, DerefMut)]
 pub struct CountdownTimer(Timer);
 
+/// Time left for the clients to load the map before the game starts without them.
+#[derive(Component, Deref, DerefMut)]
+struct MapLoadTimeout(Timer);
+
+/// The map that the lobby plays, selected by the [`MapVoting`].
+#[derive(Component, Clone)]
+pub(super) struct SelectedMap {
+    pub map_name: String,
+    pub map: GameMap,
+}
+
+/// Clients that finished preloading the [`SelectedMap`].
+#[derive(Component, Default, Deref, DerefMut)]
+struct LoadedClients(Vec<ClientId>);
+
 #[derive(Component)]
 pub struct ActiveObjectiveArea;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn voting(candidate_count: u32, votes: &[(u64, usize)]) -> MapVoting {
+        MapVoting {
+            candidates: (0..candidate_count)
+                .map(|seed| SelectedMap {
+                    map_name: format!("Map {seed}"),
+                    map: GameMap::Procedural {
+                        seed,
+                        objective_count: 0,
+                    },
+                })
+                .collect(),
+            votes: votes
+                .iter()
+                .map(|&(id, index)| (ClientId::Netcode(id), index))
+                .collect(),
+            timer: Timer::default(),
+        }
+    }
+
+    #[test]
+    fn most_voted_map_wins() {
+        let voting = voting(3, &[(0, 2), (1, 1), (2, 2)]);
+        assert_eq!(voting.winner().map_name, "Map 2");
+    }
+
+    #[test]
+    fn ties_go_to_the_rotation_order() {
+        assert_eq!(voting(3, &[]).winner().map_name, "Map 0");
+
+        let voting = voting(3, &[(0, 2), (1, 1)]);
+        assert_eq!(voting.winner().map_name, "Map 1");
+    }
+}
@@ -6,6 +6,8 @@ use lumina_shared::prelude::*;
 use server::*;
 
 use crate::player::SpawnClientPlayer;
+use crate::shutdown::Draining;
+use crate::validation::Validated;
 use crate::LobbyInfos;
 
 use super::sandbox::Sandbox;
@@ -29,7 +31,7 @@ impl Plugin for MatchmakingPlugin {
 /// Find or create a lobby for new players to join.
 fn handle_matchmaking(
     mut commands: Commands,
-    mut evr_matchmake: EventReader<MessageEvent<Matchmake>>,
+    mut evr_matchmake: EventReader<Validated<Matchmake>>,
     mut q_lobbies: Query<
         (&mut Lobby, &LobbySize, Entity),
         (Without<LobbyFull>, Without<LobbyInGame>),
@@ -37,9 +39,22 @@ fn handle_matchmaking(
     mut room_manager: ResMut<RoomManager>,
     mut connection_manager: ResMut<ConnectionManager>,
     mut lobby_infos: ResMut<LobbyInfos>,
+    settings: Res<LuminaSettings>,
+    draining: Option<Res<Draining>>,
 ) {
     for matchmake in evr_matchmake.read() {
-        let client_id = matchmake.context;
+        let client_id = *matchmake.context();
+
+        // No new games while the server is shutting down.
+        if let Some(draining) = draining.as_ref() {
+            let _ = connection_manager.send_message_to_target::<OrdReliableChannel, _>(
+                &ServerShuttingDown {
+                    remaining: draining.remaining_secs(),
+                },
+                NetworkTarget::Single(client_id),
+            );
+            continue;
+        }
 
         // Already matchmake, something is wrong...
         if lobby_infos.contains_key(&client_id) {
@@ -47,7 +62,7 @@ fn handle_matchmaking(
             continue;
         }
 
-        let lobby_size = *matchmake.message;
+        let lobby_size = **matchmake.message();
         let mut lobby_entity = None;
 
         // Find an available lobby to join.
@@ -73,7 +88,8 @@ fn handle_matchmaking(
 
         // If there is no available lobby to join, create a new one.
         let lobby_entity = lobby_entity.unwrap_or_else(|| {
-            let seed = rand::random();
+            // A fixed seed can be set to reproduce a match.
+            let seed = settings.server.lobby_seed.unwrap_or_else(rand::random);
             let entity = commands.spawn_empty().id();
             commands
                 .entity(entity)
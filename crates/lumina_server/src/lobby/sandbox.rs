@@ -2,15 +2,18 @@ use bevy::prelude::*;
 use blenvy::*;
 use lightyear::prelude::*;
 use lumina_common::prelude::*;
+use lumina_shared::game::prelude::*;
 use lumina_shared::prelude::*;
 use server::*;
 use smallvec::SmallVec;
 
 use crate::player::objective::{ObjectiveAreaManager, ResetObjectiveArea};
 use crate::player::SpawnClientPlayer;
+use crate::shutdown::Draining;
+use crate::validation::Validated;
 use crate::LobbyInfos;
 
-use super::Lobby;
+use super::{Lobby, LobbyRng, LobbySeed};
 
 pub(crate) struct SandboxPlugin;
 
@@ -25,11 +28,27 @@ fn handle_enter_sandbox(
     mut connection_manager: ResMut<ConnectionManager>,
     mut room_manager: ResMut<RoomManager>,
     mut lobbies: ResMut<LobbyInfos>,
-    mut evr_sandbox: EventReader<MessageEvent<EnterSandbox>>,
+    mut evr_sandbox: EventReader<Validated<EnterSandbox>>,
+    draining: Option<Res<Draining>>,
+    settings: Res<LuminaSettings>,
 ) {
     for sandbox in evr_sandbox.read() {
-        let client_id = sandbox.context;
+        let client_id = *sandbox.context();
+
+        // No new lobbies while the server is shutting down.
+        if let Some(draining) = draining.as_ref() {
+            let _ = connection_manager.send_message_to_target::<OrdReliableChannel, _>(
+                &ServerShuttingDown {
+                    remaining: draining.remaining_secs(),
+                },
+                NetworkTarget::Single(client_id),
+            );
+            continue;
+        }
         let world_entity = commands.spawn_empty().id();
+        // A fixed seed can be set to reproduce a match.
+        let seed = settings.server.lobby_seed.unwrap_or_else(rand::random);
+        let mut rng = LobbyRng::new(seed);
 
         commands
             .entity(world_entity)
@@ -37,14 +56,18 @@ fn handle_enter_sandbox(
                 SandboxBundle {
                     world_id: WorldIdx::from_entity(world_entity),
                     lobby: Lobby(SmallVec::from_slice(&[client_id])),
-                    ..default()
+                    seed: LobbySeed(seed),
+                    objective_manager: ObjectiveAreaManager::new(&mut rng),
+                    rng,
+                    sandbox: Sandbox,
+                    spatial: SpatialBundle::default(),
                 },
                 // Game score for the score bar to appear and interact with deposition.
                 GameScore::new(50),
             ))
             .with_children(|builder| {
                 // Spawn the sandbox level.
-                builder.spawn((MapType::Sandbox.info(), SpawnBlueprint));
+                builder.spawn((MapType::Sandbox.info(), SpawnBlueprint, GameMapRoot));
             });
 
         // Spawn player.
@@ -102,9 +125,11 @@ fn manage_objective_areas(
 #[derive(Component, Default)]
 pub(super) struct Sandbox;
 
-#[derive(Bundle, Default)]
+#[derive(Bundle)]
 struct SandboxBundle {
     pub sandbox: Sandbox,
+    pub seed: LobbySeed,
+    pub rng: LobbyRng,
     pub world_id: WorldIdx,
     pub spatial: SpatialBundle,
     pub objective_manager: ObjectiveAreaManager,
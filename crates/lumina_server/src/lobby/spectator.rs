@@ -0,0 +1,176 @@
+//! Clients that watch a lobby without a spaceship.
+//!
+//! Spectators are only the clients that explicitly ask to observe with [`Spectate`].
+//! Players respawn instead of being eliminated and matchmaking opens a new lobby
+//! instead of overflowing, so neither of them turns into a spectator.
+//! Sandbox lobbies are private practice and can't be spectated.
+use bevy::prelude::*;
+use lightyear::prelude::*;
+use lumina_common::prelude::*;
+use lumina_shared::prelude::*;
+use server::*;
+
+use crate::game::terrain::DestroyedTiles;
+use crate::player::objective::{ObjectiveAreaManager, ResetObjectiveArea};
+use crate::validation::Validated;
+use crate::LobbyInfos;
+
+use super::in_game::{ActiveObjectiveArea, SelectedMap};
+use super::sandbox::Sandbox;
+use super::{ClientExitLobby, LobbyInGame, Spectators};
+
+pub(super) struct SpectatorPlugin;
+
+impl Plugin for SpectatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PendingSpectators>().add_systems(
+            Update,
+            (
+                handle_spectate,
+                remove_pending_spectators,
+                assign_spectators,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Queue spectate requests until a lobby to watch is available.
+fn handle_spectate(
+    mut evr_spectate: EventReader<Validated<Spectate>>,
+    mut pending: ResMut<PendingSpectators>,
+) {
+    for spectate in evr_spectate.read() {
+        let client_id = *spectate.context();
+
+        if pending.iter().any(|(id, _)| *id == client_id) == false {
+            pending.push((client_id, spectate.message().room_id));
+        }
+    }
+}
+
+/// Forget spectate requests of clients that exited or disconnected.
+fn remove_pending_spectators(
+    mut evr_client_exit_lobby: EventReader<ClientExitLobby>,
+    mut pending: ResMut<PendingSpectators>,
+) {
+    for exit_client in evr_client_exit_lobby.read() {
+        pending.retain(|(id, _)| *id != exit_client.id());
+    }
+}
+
+/// Add pending spectators to the room of their lobby.
+fn assign_spectators(
+    mut q_lobbies: Query<(
+        &mut Spectators,
+        Option<&SelectedMap>,
+        Option<&GameScore>,
+        Option<&ObjectiveAreaManager>,
+        Has<LobbyInGame>,
+        Entity,
+    )>,
+    q_maps: Query<(&WorldIdx, &DestroyedTiles)>,
+    q_areas: Query<(&GlobalTransform, Option<&ResetObjectiveArea>), With<ActiveObjectiveArea>>,
+    q_sandboxes: Query<Entity, With<Sandbox>>,
+    mut pending: ResMut<PendingSpectators>,
+    mut room_manager: ResMut<RoomManager>,
+    mut connection_manager: ResMut<ConnectionManager>,
+    mut lobby_infos: ResMut<LobbyInfos>,
+) {
+    pending.retain(|&(client_id, room_id)| {
+        // Joined a lobby as a player in the meantime.
+        if lobby_infos.contains_key(&client_id) {
+            return false;
+        }
+
+        let lobby = match room_id {
+            Some(room_id) => {
+                let lobby = q_lobbies
+                    .iter_mut()
+                    .find(|(.., entity)| entity.room_id() == room_id);
+
+                if lobby.is_none() {
+                    match q_sandboxes.iter().any(|entity| entity.room_id() == room_id) {
+                        true => warn!(
+                            "Client {client_id} requested to spectate sandbox room {room_id:?}."
+                        ),
+                        false => warn!(
+                            "Client {client_id} requested to spectate unknown room {room_id:?}."
+                        ),
+                    }
+                    return false;
+                }
+                lobby
+            }
+            None => q_lobbies.iter_mut().find(|(.., in_game, _)| *in_game),
+        };
+
+        // Wait for a game to start.
+        let Some((mut spectators, selected_map, game_score, objective_manager, in_game, entity)) =
+            lobby
+        else {
+            return true;
+        };
+
+        let room_id = entity.room_id();
+        spectators.push(client_id);
+        room_manager.add_client(client_id, room_id);
+        lobby_infos.insert(client_id, entity);
+
+        let target = NetworkTarget::Single(client_id);
+        let _ = connection_manager.send_message_to_target::<OrdReliableChannel, _>(
+            &LobbyData { room_id },
+            target.clone(),
+        );
+
+        // Catch up with a game in progress.
+        if in_game {
+            if let Some(SelectedMap { map_name, map }) = selected_map.cloned() {
+                let _ = connection_manager.send_message_to_target::<OrdReliableChannel, _>(
+                    &StartGame { map_name, map },
+                    target.clone(),
+                );
+            }
+
+            if let Some(game_score) = game_score {
+                let _ = connection_manager
+                    .send_message_to_target::<OrdReliableChannel, _>(game_score, target.clone());
+            }
+
+            // Tiles destroyed before the spectator joined.
+            let world_id = WorldIdx::from_entity(entity);
+            let tiles = q_maps
+                .iter()
+                .filter(|(map_world_id, _)| **map_world_id == world_id)
+                .flat_map(|(_, destroyed_tiles)| destroyed_tiles.iter().copied())
+                .collect::<Vec<_>>();
+            if tiles.is_empty() == false {
+                let _ = connection_manager.send_message_to_target::<OrdReliableChannel, _>(
+                    &TerrainDestruction { tiles },
+                    target.clone(),
+                );
+            }
+
+            // The objective area that is currently announced or active.
+            if let Some((transform, reset)) = objective_manager
+                .and_then(|manager| manager.areas.get(manager.selected_index))
+                .and_then(|area_entity| q_areas.get(*area_entity).ok())
+            {
+                let _ = connection_manager.send_message_to_target::<OrdReliableChannel, _>(
+                    &ObjectiveAnnouncement {
+                        position: transform.translation().xy(),
+                        countdown: reset.map_or(0.0, |reset| reset.remaining_secs()),
+                    },
+                    target,
+                );
+            }
+        }
+
+        info!("Client {client_id} is spectating lobby {entity}.");
+        false
+    });
+}
+
+/// Spectate requests waiting for a lobby, with the requested room (if any).
+#[derive(Resource, Default, Deref, DerefMut)]
+struct PendingSpectators(Vec<(ClientId, Option<RoomId>)>);
@@ -12,9 +12,11 @@ use lumina_shared::protocol::SelectSpaceship;
 
 use server::*;
 
+use crate::interest::ReplicationPriority;
 use crate::lobby::LobbyRemoval;
+use crate::validation::Validated;
 
-use super::lobby::Lobby;
+use super::lobby::{Lobby, Spectators};
 use super::LobbyInfos;
 
 pub mod kda;
@@ -53,7 +55,7 @@ impl Plugin for PlayerPlugin {
 
 /// Cache client's spaceship selection on message received.
 fn handle_spaceship_selection(
-    mut events: EventReader<MessageEvent<SelectSpaceship>>,
+    mut events: EventReader<Validated<SelectSpaceship>>,
     mut selection: ResMut<ClientSpaceshipSelection>,
 ) {
     for event in events.read() {
@@ -131,6 +133,7 @@ fn replicate_item_spawn<T: Component>(
                 ..default()
             },
             relevance_mode: NetworkRelevanceMode::InterestManagement,
+            group: ReplicationPriority::Player.apply(ReplicationGroup::default()),
             ..default()
         });
 
@@ -160,7 +163,7 @@ fn replicate_action_spawn(
                     target: NetworkTarget::Single(client_id),
                     ..default()
                 },
-                group: INPUT_REPLICATION_GROUP,
+                group: ReplicationPriority::Input.apply(INPUT_REPLICATION_GROUP),
                 relevance_mode: NetworkRelevanceMode::InterestManagement,
                 ..default()
             };
@@ -179,7 +182,7 @@ fn replicate_action_spawn(
 /// Replicate the inputs (actions) of a client to other clients
 /// so that a client can predict other clients.
 fn replicate_actions(
-    q_lobbies: Query<&Lobby>,
+    q_lobbies: Query<(&Lobby, Option<&Spectators>)>,
     mut connection: ResMut<ConnectionManager>,
     mut evr_action: EventReader<MessageEvent<InputMessage<PlayerAction>>>,
     lobby_infos: Res<LobbyInfos>,
@@ -192,14 +195,18 @@ fn replicate_actions(
             continue;
         };
 
-        let Ok(lobby) = q_lobbies.get(lobby_entity) else {
+        let Ok((lobby, spectators)) = q_lobbies.get(lobby_entity) else {
             continue;
         };
 
         // OPTIONAL: Do some validation on the inputs to check that there's no cheating
 
-        // Rebroadcast the input to other clients inside the lobby.
-        for client_id in lobby.iter().filter(|id| *id != client_id) {
+        // Rebroadcast the input to other clients (and spectators) inside the lobby.
+        for client_id in lobby
+            .iter()
+            .chain(spectators.into_iter().flat_map(|s| s.iter()))
+            .filter(|id| *id != client_id)
+        {
             let _ = connection.send_message::<InputChannel, _>(*client_id, inputs);
         }
     }
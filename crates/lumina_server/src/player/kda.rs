@@ -44,7 +44,7 @@ fn on_ammo_hit(trigger: Trigger<AmmoHit>, mut commands: Commands, player_infos:
 
     if let Some(mut cmd) = commands.get_entity(hit_entity) {
         // Record the last damage player.
-        cmd.try_insert(LastDamage(Some(ammo_hit.origin_player_id)));
+        cmd.try_insert(LastDamage(ammo_hit.origin_player_id));
     }
 }
 
@@ -54,12 +54,22 @@ fn player_death(
         (&mut DeathCount, &mut StreakCount, &LastDamage, &PlayerId),
         (Added<Dead>, With<Spaceship>, With<SourceEntity>),
     >,
+    mut connection_manager: ResMut<ConnectionManager>,
 ) {
     for (mut death_count, mut streak_count, last_damage, &dead_id) in q_spaceships.iter_mut() {
         death_count.0 += 1;
         // Reset streak on death.
         streak_count.0 = 0;
 
+        // Mines and hazards clear the last damage, so the client
+        // can not rely on the last ammo hit for its death recap.
+        let _ = connection_manager.send_message::<OrdReliableChannel, _>(
+            dead_id.0,
+            &KilledBy {
+                killer_id: last_damage.0,
+            },
+        );
+
         let Some(alive_id) = last_damage.0 else {
             continue;
         };
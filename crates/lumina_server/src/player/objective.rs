@@ -5,6 +5,7 @@ use bevy::prelude::*;
 use blenvy::*;
 use lightyear::prelude::*;
 use lumina_common::prelude::*;
+use lumina_shared::game::prelude::*;
 use lumina_shared::health::init_health;
 use lumina_shared::player::objective::LuminaSpawnArea;
 use lumina_shared::player::prelude::*;
@@ -12,11 +13,10 @@ use lumina_shared::prelude::*;
 use server::*;
 
 use crate::game::PlayerDeath;
+use crate::lobby::{LobbyRng, RngStream};
+use crate::validation::Validated;
 use crate::LobbyInfos;
 
-// TODO: Allow for setting this through blender!
-pub const OBJECTIVE_AREA_COUNT: usize = 4;
-
 pub(super) struct ObjectivePlugin;
 
 impl Plugin for ObjectivePlugin {
@@ -24,11 +24,12 @@ impl Plugin for ObjectivePlugin {
         app.add_systems(
             Update,
             (
-                setup_objective_area,
+                init_objective_manager,
                 setup_lumina_spawn_area,
                 track_lumina_lifetime,
                 reset_objective_area,
                 lumina_deposition,
+                spawn_tesseract_anchors,
             ),
         )
         .add_systems(PostUpdate, setup_ores.before(init_health))
@@ -38,11 +39,37 @@ impl Plugin for ObjectivePlugin {
     }
 }
 
+/// Spawn a [TesseractType] anchor next to every tesseract blueprint.
+///
+/// Tesseract blueprints are [ClientOnly] and get despawned on the server,
+/// the anchor keeps their position for validating [DepositLumina] messages.
+pub(crate) fn spawn_tesseract_anchors(
+    mut commands: Commands,
+    q_blueprints: Query<(&BlueprintInfo, &Transform, Option<&Parent>), Added<BlueprintInfo>>,
+) {
+    let tesseract_path = TesseractType::Tesseract.info().path;
+
+    for (info, transform, parent) in q_blueprints.iter() {
+        if info.path != tesseract_path {
+            continue;
+        }
+
+        let mut anchor = commands.spawn((
+            TesseractType::Tesseract,
+            SpatialBundle::from_transform(*transform),
+            Name::new("Tesseract Anchor"),
+        ));
+        if let Some(parent) = parent {
+            anchor.set_parent(parent.get());
+        }
+    }
+}
+
 /// Listen for messages from clients that are triggering a [DepositLumina] event.
 fn lumina_deposition(
     mut q_collected_luminas: Query<(&mut CollectedLumina, &TeamType, &PlayerId)>,
     mut q_game_scores: Query<&mut GameScore>,
-    mut evr_deposit: EventReader<MessageEvent<DepositLumina>>,
+    mut evr_deposit: EventReader<Validated<DepositLumina>>,
     lobby_infos: Res<LobbyInfos>,
     player_info: Res<PlayerInfos>,
 ) {
@@ -74,20 +101,33 @@ fn lumina_deposition(
     }
 }
 
-/// Setup [ObjectiveArea] with their respective [ObjectiveAreaManager] parent.
-fn setup_objective_area(
-    q_areas: Query<Entity, Added<ObjectiveArea>>,
-    q_parents: Query<&Parent>,
-    mut q_managers: Query<&mut ObjectiveAreaManager>,
+/// Collect the [ObjectiveArea]s of a map once it has finished spawning
+/// and pick the first objective of the [ObjectiveAreaManager] parent.
+fn init_objective_manager(
+    mut commands: Commands,
+    q_maps: Query<(&Parent, Entity), Added<GameMapReady>>,
+    mut q_managers: Query<&mut ObjectiveAreaManager, Without<ObjectiveAreasReady>>,
+    q_children: Query<&Children>,
+    q_areas: Query<(&ObjectiveArea, &GlobalTransform)>,
 ) {
-    for entity in q_areas.iter() {
-        for parent in q_parents.iter_ancestors(entity) {
-            if let Ok(mut manager) = q_managers.get_mut(parent) {
-                manager.areas.push(entity);
-                // There should only be one ObjectiveAreaManager parenting an ObjectiveArea.
-                break;
-            }
-        }
+    for (parent, map_entity) in q_maps.iter() {
+        let Ok(mut manager) = q_managers.get_mut(parent.get()) else {
+            continue;
+        };
+
+        // Hierarchy order keeps the selection reproducible from the lobby seed.
+        manager.areas = q_children
+            .iter_descendants(map_entity)
+            .filter(|&entity| q_areas.contains(entity))
+            .collect();
+        manager.pick_next(&q_areas);
+
+        info!(
+            "Objective manager {} is ready with {} areas.",
+            parent.get(),
+            manager.areas.len()
+        );
+        commands.entity(parent.get()).insert(ObjectiveAreasReady);
     }
 }
 
@@ -155,6 +195,7 @@ fn ore_destruction(
     >,
     mut q_areas: Query<&mut ObjectiveArea>,
     q_spawn_areas: Query<(&GlobalTransform, &LuminaSpawnArea)>,
+    mut q_rngs: Query<&mut LobbyRng>,
 ) {
     for (health, area_target, ore, lumina_spawn_target, &world_id, entity) in q_ores.iter() {
         if **health <= 0.0 {
@@ -164,14 +205,20 @@ fn ore_destruction(
                 area.ores.set_used(entity);
             }
 
+            let Some(mut rng) = world_id.and_then(|e| q_rngs.get_mut(e).ok()) else {
+                warn!("No lobby rng found for ore {entity}.");
+                continue;
+            };
+
             if let Ok((transform, spawn_area)) = q_spawn_areas.get(lumina_spawn_target.0) {
                 let translation = transform.translation().xy();
 
-                let value = ore.rand_value();
+                let value = ore.rand_value(rng.stream(RngStream::Ores));
                 for _ in 0..value {
-                    let radian = rand::random::<f32>() % TAU;
+                    let drops = rng.stream(RngStream::Drops);
+                    let radian = drops.next_f32() % TAU;
                     let dir = Vec2::from_angle(radian);
-                    let distance = rand::random::<f32>() % spawn_area.radius;
+                    let distance = drops.next_f32() % spawn_area.radius;
 
                     commands.trigger(SpawnLumina {
                         position: Position(translation + (dir * distance)),
@@ -280,15 +327,22 @@ fn drop_lumina_on_death(
     trigger: Trigger<PlayerDeath>,
     mut commands: Commands,
     mut q_players: Query<(&mut CollectedLumina, &WorldIdx)>,
+    mut q_rngs: Query<&mut LobbyRng>,
 ) {
     let death = trigger.event();
     if let Ok((mut collected_lumina, world_id)) = q_players.get_mut(trigger.entity()) {
+        let Some(mut rng) = world_id.and_then(|e| q_rngs.get_mut(e).ok()) else {
+            warn!("No lobby rng found for player {}.", trigger.entity());
+            return;
+        };
+
         if collected_lumina.0 > 0 {
             let radius = 2.0 + (collected_lumina.0 as f32 * 0.5);
+            let drops = rng.stream(RngStream::Drops);
             for _ in 0..collected_lumina.0 {
-                let radian = rand::random::<f32>() % TAU;
+                let radian = drops.next_f32() % TAU;
                 let dir = Vec2::from_angle(radian);
-                let distance = rand::random::<f32>() % radius;
+                let distance = drops.next_f32() % radius;
                 let spawn_position = Position(death.position.0 + (dir * distance));
 
                 commands.trigger(SpawnLumina {
@@ -308,7 +362,7 @@ fn drop_lumina_on_death(
 }
 
 #[derive(Event)]
-struct SpawnLumina {
+pub(crate) struct SpawnLumina {
     // Position where the Lumina will appear.
     pub position: Position,
     pub world_id: WorldIdx,
@@ -334,17 +388,298 @@ pub struct ResetObjectiveArea(pub Timer);
 #[derive(Component)]
 struct ObjectiveAreaTarget(Entity);
 
-#[derive(Component, Debug)]
+#[derive(Component)]
 pub struct ObjectiveAreaManager {
     pub areas: Vec<Entity>,
     pub selected_index: usize,
+    /// Whether the first objective has been picked.
+    picked: bool,
+    /// Forked from the [`RngStream::Objectives`] stream of the lobby.
+    rng: XorShift32,
 }
 
-impl Default for ObjectiveAreaManager {
-    fn default() -> Self {
+impl ObjectiveAreaManager {
+    /// Create a manager with no areas yet that picks them from the lobby seed.
+    pub fn new(rng: &mut LobbyRng) -> Self {
         Self {
             areas: Vec::new(),
-            selected_index: rand::random::<u32>() as usize % OBJECTIVE_AREA_COUNT,
+            selected_index: 0,
+            picked: false,
+            rng: rng.fork(RngStream::Objectives),
+        }
+    }
+
+    /// Pick the next objective area and return its index.
+    ///
+    /// The first pick is limited to the areas with the highest [`ObjectiveArea::priority`],
+    /// the following ones to the areas far enough from the current one
+    /// (see [`ObjectiveArea::min_distance`]). The pick is then weighted by
+    /// [`ObjectiveArea::weight`].
+    pub fn pick_next(
+        &mut self,
+        q_areas: &Query<(&ObjectiveArea, &GlobalTransform)>,
+    ) -> Option<usize> {
+        let mut rng = std::mem::replace(&mut self.rng, XorShift32::new(0));
+        let index = self.pick_next_by(
+            |entity| {
+                let (area, transform) = q_areas.get(entity).ok()?;
+                Some((area, transform.translation().xy()))
+            },
+            &mut rng,
+        );
+        self.rng = rng;
+
+        index
+    }
+
+    /// See [`Self::pick_next`], areas and their positions are looked up with `get_area`.
+    fn pick_next_by<'a>(
+        &mut self,
+        get_area: impl Fn(Entity) -> Option<(&'a ObjectiveArea, Vec2)>,
+        rng: &mut XorShift32,
+    ) -> Option<usize> {
+        let current = self
+            .areas
+            .get(self.selected_index)
+            .filter(|_| self.picked)
+            .and_then(|&entity| get_area(entity))
+            .map(|(_, position)| position);
+
+        let mut candidates = self
+            .areas
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| current.is_some() == false || *index != self.selected_index)
+            .filter_map(|(index, &entity)| {
+                let (area, position) = get_area(entity)?;
+                Some((index, area, position))
+            })
+            .collect::<Vec<_>>();
+
+        match current {
+            Some(current) => {
+                let far_enough = candidates
+                    .iter()
+                    .copied()
+                    .filter(|(_, area, position)| position.distance(current) >= area.min_distance)
+                    .collect::<Vec<_>>();
+                // Ignore the distance if no area is far enough.
+                if far_enough.is_empty() == false {
+                    candidates = far_enough;
+                }
+            }
+            None => {
+                if let Some(max_priority) =
+                    candidates.iter().map(|(_, area, _)| area.priority).max()
+                {
+                    candidates.retain(|(_, area, _)| area.priority == max_priority);
+                }
+            }
+        }
+
+        if candidates.is_empty() {
+            // Only one area, keep using it.
+            return current.map(|_| self.selected_index);
+        }
+
+        let total_weight = candidates
+            .iter()
+            .map(|(_, area, _)| area.weight.max(0.0))
+            .sum::<f32>();
+        let mut target = rng.next_f32() * total_weight;
+        let index = candidates
+            .iter()
+            .find(|(_, area, _)| {
+                target -= area.weight.max(0.0);
+                target < 0.0
+            })
+            // All areas have no weight, or floating point errors left a tiny remainder.
+            .unwrap_or_else(|| &candidates[rng.next_u32() as usize % candidates.len()])
+            .0;
+
+        self.selected_index = index;
+        self.picked = true;
+        Some(index)
+    }
+}
+
+/// Inserted on the [`ObjectiveAreaManager`] once the map has finished spawning
+/// and its [`ObjectiveArea`]s are collected.
+#[derive(Component)]
+pub struct ObjectiveAreasReady;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Areas with their positions, indexed by [`Entity::index`].
+    fn manager(areas: &[(ObjectiveArea, Vec2)]) -> ObjectiveAreaManager {
+        ObjectiveAreaManager {
+            areas: (0..areas.len() as u32).map(Entity::from_raw).collect(),
+            ..ObjectiveAreaManager::new(&mut LobbyRng::new(0))
+        }
+    }
+
+    fn area(weight: f32, priority: u8, min_distance: f32) -> ObjectiveArea {
+        ObjectiveArea {
+            weight,
+            priority,
+            min_distance,
+            ..default()
+        }
+    }
+
+    fn pick(
+        manager: &mut ObjectiveAreaManager,
+        areas: &[(ObjectiveArea, Vec2)],
+        rng: &mut XorShift32,
+    ) -> Option<usize> {
+        manager.pick_next_by(
+            |entity| {
+                let (area, position) = areas.get(entity.index() as usize)?;
+                Some((area, *position))
+            },
+            rng,
+        )
+    }
+
+    /// Count how many times each area is picked after the first one.
+    fn pick_counts(areas: &[(ObjectiveArea, Vec2)], picks: usize) -> Vec<usize> {
+        let mut rng = XorShift32::new(7);
+        let mut manager = manager(areas);
+        let mut counts = vec![0; areas.len()];
+        pick(&mut manager, areas, &mut rng);
+
+        for _ in 0..picks {
+            counts[pick(&mut manager, areas, &mut rng).unwrap()] += 1;
+        }
+
+        counts
+    }
+
+    #[test]
+    fn weighted_pick() {
+        let areas = [
+            (area(1.0, 0, 0.0), Vec2::ZERO),
+            (area(1.0, 0, 0.0), Vec2::X),
+            (area(4.0, 0, 0.0), Vec2::Y),
+        ];
+        let mut rng = XorShift32::new(11);
+        let mut manager = manager(&areas);
+        pick(&mut manager, &areas, &mut rng);
+
+        let mut counts = [0; 3];
+        for _ in 0..1000 {
+            manager.selected_index = 0;
+            counts[pick(&mut manager, &areas, &mut rng).unwrap()] += 1;
         }
+
+        // Expecting 200 and 800.
+        assert_eq!(counts[0], 0);
+        assert!((120..280).contains(&counts[1]));
+        assert!((720..880).contains(&counts[2]));
+    }
+
+    #[test]
+    fn never_repeat_current_area() {
+        let areas = [
+            (area(1.0, 0, 0.0), Vec2::ZERO),
+            (area(1.0, 0, 0.0), Vec2::X),
+        ];
+        let mut rng = XorShift32::new(3);
+        let mut manager = manager(&areas);
+
+        let mut current = pick(&mut manager, &areas, &mut rng).unwrap();
+        for _ in 0..16 {
+            let next = pick(&mut manager, &areas, &mut rng).unwrap();
+            assert_ne!(next, current);
+            current = next;
+        }
+    }
+
+    #[test]
+    fn first_pick_has_highest_priority() {
+        let areas = [
+            (area(10.0, 0, 0.0), Vec2::ZERO),
+            (area(1.0, 2, 0.0), Vec2::X),
+            (area(1.0, 1, 0.0), Vec2::Y),
+            (area(1.0, 2, 0.0), Vec2::NEG_X),
+        ];
+
+        for seed in 0..32 {
+            let mut rng = XorShift32::new(seed);
+            let first = pick(&mut manager(&areas), &areas, &mut rng).unwrap();
+            assert!(first == 1 || first == 3);
+        }
+
+        // Priority only applies to the first pick.
+        let counts = pick_counts(&areas, 300);
+        assert!(counts[0] > 0);
+    }
+
+    #[test]
+    fn min_distance_fallback() {
+        let areas = [
+            (area(1.0, 1, 200.0), Vec2::ZERO),
+            (area(1.0, 0, 100.0), Vec2::new(60.0, 0.0)),
+            (area(1.0, 0, 100.0), Vec2::new(150.0, 0.0)),
+        ];
+        let mut rng = XorShift32::new(5);
+        let mut manager = manager(&areas);
+        assert_eq!(pick(&mut manager, &areas, &mut rng), Some(0));
+
+        // Only the third area is far enough from the first one.
+        for _ in 0..8 {
+            manager.selected_index = 0;
+            assert_eq!(pick(&mut manager, &areas, &mut rng), Some(2));
+        }
+
+        // No area is far enough from the third one, the distance is ignored.
+        let mut counts = [0; 3];
+        for _ in 0..64 {
+            manager.selected_index = 2;
+            counts[pick(&mut manager, &areas, &mut rng).unwrap()] += 1;
+        }
+        assert!(counts[0] > 0 && counts[1] > 0 && counts[2] == 0);
+    }
+
+    #[test]
+    fn zero_weight_fallback() {
+        let areas = [
+            (area(0.0, 0, 0.0), Vec2::ZERO),
+            (area(0.0, 0, 0.0), Vec2::X),
+            (area(-1.0, 0, 0.0), Vec2::Y),
+        ];
+
+        // Areas are picked uniformly if none of them has a weight.
+        let counts = pick_counts(&areas, 300);
+        assert!(counts.iter().all(|count| *count > 0));
+
+        // Areas without weight are never picked over the ones with weight.
+        let areas = [
+            (area(0.0, 0, 0.0), Vec2::ZERO),
+            (area(1.0, 0, 0.0), Vec2::X),
+            (area(1.0, 0, 0.0), Vec2::Y),
+        ];
+        let counts = pick_counts(&areas, 300);
+        assert_eq!(counts[0], 0);
+    }
+
+    #[test]
+    fn single_area_is_kept() {
+        let areas = [(area(1.0, 0, 0.0), Vec2::ZERO)];
+        let mut rng = XorShift32::new(1);
+        let mut manager = manager(&areas);
+
+        assert_eq!(pick(&mut manager, &areas, &mut rng), Some(0));
+        assert_eq!(pick(&mut manager, &areas, &mut rng), Some(0));
+        assert_eq!(
+            pick(
+                &mut ObjectiveAreaManager::new(&mut LobbyRng::new(0)),
+                &areas,
+                &mut rng
+            ),
+            None
+        );
     }
 }
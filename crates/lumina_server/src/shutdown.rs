@@ -0,0 +1,300 @@
+//! Graceful shutdown: drain lobbies before exiting the server.
+//!
+//! Draining can be started by a termination signal or by typing an admin
+//! command (`drain`, `shutdown`, `status`) into the server's standard input.
+//! The `export` command writes the terrain of every live arena into
+//! `.terrain.ron` files that can be curated into `assets/levels/terrain`.
+use std::fs::OpenOptions;
+use std::io::{BufRead, Write};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use lightyear::prelude::*;
+use lumina_common::prelude::*;
+use lumina_shared::game::prelude::*;
+use lumina_shared::prelude::*;
+use lumina_terrain::prelude::*;
+use server::*;
+
+use crate::lobby::{ClientExitLobby, Lobby, LobbyInGame};
+use crate::player::kda::{DeathCount, KillCount};
+
+/// File (relative to the working directory) that the stats
+/// of every finished game are appended to.
+const MATCH_STATS_PATH: &str = "match_stats.log";
+
+pub(super) struct ShutdownPlugin;
+
+impl Plugin for ShutdownPlugin {
+    fn build(&self, app: &mut App) {
+        let (sender, receiver) = mpsc::channel();
+        listen_signals(sender.clone());
+        listen_stdin(sender);
+
+        app.insert_resource(AdminCommands(Mutex::new(receiver)))
+            .add_systems(PreUpdate, handle_admin_commands)
+            .add_systems(
+                Update,
+                (
+                    notify_draining.run_if(resource_added::<Draining>),
+                    end_idle_lobbies,
+                    track_drain_timeout,
+                    exit_when_drained,
+                )
+                    .chain()
+                    .run_if(resource_exists::<Draining>),
+            )
+            .observe(save_match_stats);
+    }
+}
+
+/// Start draining on the first termination signal and
+/// shutdown immediately on the second one.
+fn listen_signals(sender: Sender<AdminCommand>) {
+    let mut received = false;
+
+    let result = ctrlc::set_handler(move || {
+        let command = match received {
+            true => AdminCommand::Shutdown,
+            false => AdminCommand::Drain,
+        };
+        received = true;
+
+        let _ = sender.send(command);
+    });
+
+    if let Err(err) = result {
+        error!("Unable to listen to termination signals: {err}");
+    }
+}
+
+fn listen_stdin(sender: Sender<AdminCommand>) {
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+
+            match AdminCommand::parse(&line) {
+                Some(command) => {
+                    if sender.send(command).is_err() {
+                        break;
+                    }
+                }
+                None => warn!("Unknown admin command: {line:?}"),
+            }
+        }
+    });
+}
+
+fn handle_admin_commands(
+    mut commands: Commands,
+    admin_commands: Res<AdminCommands>,
+    mut draining: Option<ResMut<Draining>>,
+    q_lobbies: Query<Has<LobbyInGame>, With<Lobby>>,
+    q_arenas: Query<(&ProceduralArena, Entity), With<TerrainStates>>,
+    settings: Res<LuminaSettings>,
+    mut evw_export: EventWriter<ExportTerrain>,
+) {
+    let Ok(receiver) = admin_commands.lock() else {
+        return;
+    };
+
+    for command in receiver.try_iter() {
+        match command {
+            AdminCommand::Drain => {
+                if draining.is_some() {
+                    info!("Server is already draining.");
+                    continue;
+                }
+
+                let drain_timeout = settings.server.shutdown.drain_timeout;
+                info!("Draining server, in-progress games will be ended in {drain_timeout}s.");
+                commands.insert_resource(Draining(Timer::from_seconds(
+                    drain_timeout,
+                    TimerMode::Once,
+                )));
+            }
+            AdminCommand::Shutdown => {
+                info!("Shutting down server, ending all games now.");
+                match draining.as_mut() {
+                    Some(draining) => {
+                        let remaining = draining.remaining();
+                        draining.tick(remaining);
+                    }
+                    None => commands
+                        .insert_resource(Draining(Timer::from_seconds(0.0, TimerMode::Once))),
+                }
+            }
+            AdminCommand::Status => {
+                let in_game = q_lobbies.iter().filter(|in_game| *in_game).count();
+                info!(
+                    "Lobbies: {}, in game: {in_game}, draining: {:?}",
+                    q_lobbies.iter().count(),
+                    draining.as_ref().map(|d| d.remaining_secs())
+                );
+            }
+            AdminCommand::Export => {
+                if q_arenas.is_empty() {
+                    info!("No arena terrain to export.");
+                }
+
+                for (arena, entity) in q_arenas.iter() {
+                    evw_export.send(ExportTerrain {
+                        entity,
+                        path: format!("arena_{}_{entity}.terrain.ron", arena.seed).into(),
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Notify all clients that the server is shutting down.
+fn notify_draining(draining: Res<Draining>, mut connection_manager: ResMut<ConnectionManager>) {
+    let _ = connection_manager.send_message_to_target::<OrdReliableChannel, _>(
+        &ServerShuttingDown {
+            remaining: draining.remaining_secs(),
+        },
+        NetworkTarget::All,
+    );
+}
+
+/// Remove clients from lobbies that are not in game,
+/// they will be cleaned up by the lobby plugin once empty.
+fn end_idle_lobbies(
+    mut commands: Commands,
+    q_lobbies: Query<(&Lobby, Entity), (Without<LobbyInGame>, Without<DrainedLobby>)>,
+    mut evw_client_exit: EventWriter<ClientExitLobby>,
+) {
+    for (lobby, entity) in q_lobbies.iter() {
+        evw_client_exit.send_batch(lobby.iter().map(|id| ClientExitLobby(*id)));
+        commands.entity(entity).insert(DrainedLobby);
+    }
+}
+
+/// End all in-progress games once the drain timeout is reached.
+fn track_drain_timeout(
+    mut commands: Commands,
+    q_lobbies: Query<Entity, (With<LobbyInGame>, Without<DrainedLobby>)>,
+    mut draining: ResMut<Draining>,
+    time: Res<Time>,
+) {
+    if draining.tick(time.delta()).finished() == false {
+        return;
+    }
+
+    for entity in q_lobbies.iter() {
+        info!("Drain timeout reached, ending game for lobby {entity}.");
+        commands.trigger_targets(EndGame, entity);
+        commands.entity(entity).insert(DrainedLobby);
+    }
+}
+
+/// Stop the server and exit once every lobby is gone.
+fn exit_when_drained(
+    mut commands: Commands,
+    q_lobbies: Query<(), With<Lobby>>,
+    mut evw_exit: EventWriter<AppExit>,
+) {
+    if q_lobbies.is_empty() == false {
+        return;
+    }
+
+    info!("All lobbies drained, exiting...");
+    commands.stop_server();
+    evw_exit.send(AppExit::Success);
+}
+
+/// Persist the final stats of a game before its players are removed,
+/// one line per game appended to [`MATCH_STATS_PATH`].
+fn save_match_stats(
+    trigger: Trigger<EndGame>,
+    q_lobbies: Query<(&Lobby, Option<&GameScore>)>,
+    q_kdas: Query<(&KillCount, &DeathCount)>,
+    player_infos: Res<PlayerInfos>,
+) {
+    let entity = trigger.entity();
+    let Ok((lobby, game_score)) = q_lobbies.get(entity) else {
+        return;
+    };
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+
+    let mut line = match game_score {
+        Some(s) => format!(
+            "{timestamp} lobby={entity} score={}/{}",
+            s.score, s.max_score
+        ),
+        None => format!("{timestamp} lobby={entity} score=none"),
+    };
+
+    for client_id in lobby.iter() {
+        if let Some((kill_count, death_count)) = player_infos[PlayerInfoType::Spaceship]
+            .get(&PlayerId(*client_id))
+            .and_then(|e| q_kdas.get(*e).ok())
+        {
+            line += &format!(
+                " {}:{}/{}",
+                client_id.to_bits(),
+                kill_count.0,
+                death_count.0
+            );
+        }
+    }
+
+    info!("Game ended: {line}");
+
+    let result = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(MATCH_STATS_PATH)
+        .and_then(|mut file| writeln!(file, "{line}"));
+
+    if let Err(err) = result {
+        error!("Unable to save match stats to {MATCH_STATS_PATH:?}: {err}");
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AdminCommand {
+    /// Stop accepting new lobbies and wait for games to finish.
+    Drain,
+    /// End all games and exit now.
+    Shutdown,
+    /// Log the current lobby status.
+    Status,
+    /// Export the terrain of every live arena.
+    Export,
+}
+
+impl AdminCommand {
+    fn parse(line: &str) -> Option<Self> {
+        match line.trim() {
+            "drain" => Some(Self::Drain),
+            "shutdown" | "stop" => Some(Self::Shutdown),
+            "status" => Some(Self::Status),
+            "export" => Some(Self::Export),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Resource, Deref)]
+struct AdminCommands(Mutex<Receiver<AdminCommand>>);
+
+/// Lobby that was already ended or emptied by the drain,
+/// waiting to be despawned by the lobby plugin.
+#[derive(Component)]
+struct DrainedLobby;
+
+/// Exists when the server is draining. No new lobbies are created and
+/// in-progress games are ended when the timer finishes.
+#[derive(Resource, Deref, DerefMut)]
+pub struct Draining(Timer);
@@ -16,6 +16,8 @@ impl Plugin for SourceEntityPlugin {
             .set_source::<ObjectiveArea, With<SyncTarget>>()
             .set_source::<OreType, With<SyncTarget>>()
             .set_source::<LuminaType, With<SyncTarget>>()
+            .set_source::<Neutral, With<SyncTarget>>()
+            .set_source::<Pickup, With<SyncTarget>>()
             .set_source::<Playback, With<SyncTarget>>();
     }
 }
@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+use lightyear::prelude::*;
+use lumina_shared::prelude::*;
+use lumina_shared::tuning::{ActiveTuning, TuningAsset, TuningUpdate};
+use server::*;
+
+pub(super) struct TuningPlugin;
+
+impl Plugin for TuningPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, load_tuning)
+            .add_systems(Update, (update_tuning, send_tuning_on_connect));
+    }
+}
+
+fn load_tuning(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let handle = asset_server.load("gameplay.tuning.ron");
+    commands.insert_resource(TuningHandle(handle));
+}
+
+/// Apply the tuning whenever the file is (re)loaded and push it to every client.
+/// A hot reload also sends [`AssetEvent::Modified`], only the
+/// [`AssetEvent::LoadedWithDependencies`] that follows is handled.
+fn update_tuning(
+    mut evr_asset: EventReader<AssetEvent<TuningAsset>>,
+    tunings: Res<Assets<TuningAsset>>,
+    handle: Res<TuningHandle>,
+    mut active_tuning: ResMut<ActiveTuning>,
+    mut connection_manager: ResMut<ConnectionManager>,
+) {
+    for event in evr_asset.read() {
+        if event.is_loaded_with_dependencies(&**handle) == false {
+            continue;
+        }
+
+        let Some(tuning) = tunings.get(&**handle) else {
+            continue;
+        };
+
+        info!("Applying gameplay tuning.");
+        **active_tuning = tuning.clone();
+
+        let _ = connection_manager.send_message_to_target::<OrdReliableChannel, _>(
+            &TuningUpdate(tuning.clone()),
+            NetworkTarget::All,
+        );
+    }
+}
+
+/// Clients that connect later need the current tuning too.
+fn send_tuning_on_connect(
+    mut evr_connect: EventReader<ConnectEvent>,
+    active_tuning: Res<ActiveTuning>,
+    mut connection_manager: ResMut<ConnectionManager>,
+) {
+    for event in evr_connect.read() {
+        let _ = connection_manager.send_message_to_target::<OrdReliableChannel, _>(
+            &TuningUpdate(active_tuning.clone()),
+            NetworkTarget::Single(event.client_id()),
+        );
+    }
+}
+
+#[derive(Resource, Deref, Debug)]
+struct TuningHandle(Handle<TuningAsset>);
@@ -3,6 +3,7 @@ use lumina_ui::prelude::*;
 use velyst::prelude::*;
 
 use super::lobby::Lobby;
+use super::shutdown::Draining;
 
 pub(super) struct ServerUiPlugin;
 
@@ -19,8 +20,13 @@ impl Plugin for ServerUiPlugin {
     }
 }
 
-fn lobbies(q_lobbies: Query<&Lobby>, mut lobby_func: ResMut<LobbyListFunc>) {
+fn lobbies(
+    q_lobbies: Query<&Lobby>,
+    mut lobby_func: ResMut<LobbyListFunc>,
+    draining: Option<Res<Draining>>,
+) {
     lobby_func.lobbies.clear();
+    lobby_func.draining = draining.map(|d| d.remaining_secs() as f64);
 
     for lobby in q_lobbies.iter() {
         let player_count = lobby.len();
@@ -32,6 +38,8 @@ fn lobbies(q_lobbies: Query<&Lobby>, mut lobby_func: ResMut<LobbyListFunc>) {
 #[typst_func(name = "lobby_list", layer = 1)]
 struct LobbyListFunc {
     lobbies: Vec<u32>,
+    /// Seconds left before in-progress games are ended, if draining.
+    draining: Option<f64>,
 }
 
 #[derive(TypstPath)]
@@ -0,0 +1,647 @@
+//! Validation of client messages before they are acted upon.
+//!
+//! Every client message is rate limited and checked against the
+//! server state (proximity, cooldowns, game state). Messages that pass
+//! are re-emitted as [`Validated`] events, violations are logged per
+//! client and repeat offenders get kicked.
+use std::collections::VecDeque;
+
+use avian2d::prelude::*;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use lightyear::prelude::*;
+use lumina_common::prelude::*;
+use lumina_common::settings::ValidationSettings;
+use lumina_shared::game::prelude::*;
+use lumina_shared::prelude::*;
+use server::*;
+
+use crate::game::teleporter::PlayerTeleportCooldown;
+use crate::lobby::{LobbyFull, LobbyInGame};
+use crate::LobbyInfos;
+
+/// Largest lobby size a client can matchmake for.
+pub const MAX_LOBBY_SIZE: u8 = 6;
+
+pub(super) struct ValidationPlugin;
+
+impl Plugin for ValidationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ClientViolations>()
+            .init_resource::<RateLimits>()
+            .add_event::<Validated<Matchmake>>()
+            .add_event::<Validated<Spectate>>()
+            .add_event::<Validated<EnterSandbox>>()
+            .add_event::<Validated<ExitLobby>>()
+            .add_event::<Validated<SelectSpaceship>>()
+            .add_event::<Validated<Teleport>>()
+            .add_event::<Validated<DepositLumina>>()
+            .add_event::<Validated<MapVote>>()
+            .add_event::<Validated<MapLoaded>>()
+            .add_event::<Validated<CameraView>>()
+            .add_systems(
+                PreUpdate,
+                (
+                    validate_matchmake,
+                    validate_spectate,
+                    validate_enter_sandbox,
+                    validate_exit_lobby,
+                    validate_spaceship_selection,
+                    validate_teleport,
+                    validate_deposit,
+                    validate_map_vote,
+                    validate_map_loaded,
+                    validate_camera_view,
+                )
+                    .after(MainSet::Receive),
+            )
+            .add_systems(
+                Update,
+                (
+                    decay_violations,
+                    kick_repeat_offenders,
+                    cleanup_disconnected,
+                )
+                    .chain(),
+            );
+    }
+}
+
+fn validate_matchmake(
+    mut evr_matchmake: EventReader<MessageEvent<Matchmake>>,
+    mut evw_matchmake: EventWriter<Validated<Matchmake>>,
+    lobby_infos: Res<LobbyInfos>,
+    mut validator: MessageValidator,
+) {
+    for matchmake in evr_matchmake.read() {
+        let client_id = *matchmake.context();
+        let lobby_size = **matchmake.message();
+
+        let result = if lobby_infos.contains_key(&client_id) {
+            Err(Violation::InvalidState("already in a lobby"))
+        } else if lobby_size == 0 || lobby_size > MAX_LOBBY_SIZE || lobby_size % 2 != 0 {
+            Err(Violation::InvalidValue)
+        } else {
+            Ok(())
+        };
+
+        if validator.validate(client_id, result) {
+            evw_matchmake.send(Validated::new(client_id, *matchmake.message()));
+        }
+    }
+}
+
+fn validate_spectate(
+    mut evr_spectate: EventReader<MessageEvent<Spectate>>,
+    mut evw_spectate: EventWriter<Validated<Spectate>>,
+    lobby_infos: Res<LobbyInfos>,
+    mut validator: MessageValidator,
+) {
+    for spectate in evr_spectate.read() {
+        let client_id = *spectate.context();
+
+        let result = match lobby_infos.contains_key(&client_id) {
+            true => Err(Violation::InvalidState("already in a lobby")),
+            false => Ok(()),
+        };
+
+        if validator.validate(client_id, result) {
+            evw_spectate.send(Validated::new(client_id, *spectate.message()));
+        }
+    }
+}
+
+fn validate_enter_sandbox(
+    mut evr_sandbox: EventReader<MessageEvent<EnterSandbox>>,
+    mut evw_sandbox: EventWriter<Validated<EnterSandbox>>,
+    lobby_infos: Res<LobbyInfos>,
+    mut validator: MessageValidator,
+) {
+    for sandbox in evr_sandbox.read() {
+        let client_id = *sandbox.context();
+
+        let result = match lobby_infos.contains_key(&client_id) {
+            true => Err(Violation::InvalidState("already in a lobby")),
+            false => Ok(()),
+        };
+
+        if validator.validate(client_id, result) {
+            evw_sandbox.send(Validated::new(client_id, *sandbox.message()));
+        }
+    }
+}
+
+fn validate_exit_lobby(
+    mut evr_exit_lobby: EventReader<MessageEvent<ExitLobby>>,
+    mut evw_exit_lobby: EventWriter<Validated<ExitLobby>>,
+    mut validator: MessageValidator,
+) {
+    for exit in evr_exit_lobby.read() {
+        // Exiting is always allowed, only rate limit it.
+        let client_id = *exit.context();
+
+        if validator.validate(client_id, Ok(())) {
+            evw_exit_lobby.send(Validated::new(client_id, *exit.message()));
+        }
+    }
+}
+
+fn validate_spaceship_selection(
+    mut evr_selection: EventReader<MessageEvent<SelectSpaceship>>,
+    mut evw_selection: EventWriter<Validated<SelectSpaceship>>,
+    q_in_game: Query<(), With<LobbyInGame>>,
+    lobby_infos: Res<LobbyInfos>,
+    mut validator: MessageValidator,
+) {
+    for selection in evr_selection.read() {
+        let client_id = *selection.context();
+
+        let in_game = lobby_infos
+            .get(&client_id)
+            .is_some_and(|e| q_in_game.contains(*e));
+
+        let result = match in_game {
+            true => Err(Violation::InvalidState("spaceship selection during a game")),
+            false => Ok(()),
+        };
+
+        if validator.validate(client_id, result) {
+            evw_selection.send(Validated::new(client_id, *selection.message()));
+        }
+    }
+}
+
+fn validate_teleport(
+    mut evr_teleport: EventReader<MessageEvent<Teleport>>,
+    mut evw_teleport: EventWriter<Validated<Teleport>>,
+    q_teleporters: Query<
+        (
+            &Teleporter,
+            &GlobalTransform,
+            &WorldIdx,
+            Has<TeleporterCooldown>,
+        ),
+        With<TeleporterStart>,
+    >,
+    q_spaceships: AliveQuery<(&Position, &WorldIdx, Has<PlayerTeleportCooldown>), With<Spaceship>>,
+    player_infos: Res<PlayerInfos>,
+    mut validator: MessageValidator,
+) {
+    let teleport_radius = validator.settings().teleport_radius;
+
+    for teleport in evr_teleport.read() {
+        let client_id = *teleport.context();
+        let message = teleport.message();
+
+        let result = (|| {
+            let (position, world_id, player_on_cooldown) = player_infos[PlayerInfoType::Spaceship]
+                .get(&PlayerId(client_id))
+                .and_then(|e| q_spaceships.get(*e).ok())
+                .ok_or(Violation::InvalidState("no alive spaceship"))?;
+
+            // A teleporter can have multiple starts, check the closest one.
+            let (_, transform, _, on_cooldown) = q_teleporters
+                .iter()
+                .filter(|(teleporter, _, teleporter_world, _)| {
+                    **teleporter == message.teleporter && *teleporter_world == world_id
+                })
+                .min_by(|(_, a, ..), (_, b, ..)| {
+                    let distance_a = a.translation().xy().distance_squared(position.0);
+                    let distance_b = b.translation().xy().distance_squared(position.0);
+                    distance_a.total_cmp(&distance_b)
+                })
+                .ok_or(Violation::UnknownTarget)?;
+
+            if on_cooldown || player_on_cooldown {
+                return Err(Violation::OnCooldown);
+            }
+
+            let distance = transform.translation().xy().distance(position.0);
+            if distance > teleport_radius {
+                return Err(Violation::OutOfRange {
+                    distance,
+                    max: teleport_radius,
+                });
+            }
+
+            Ok(())
+        })();
+
+        if validator.validate(client_id, result) {
+            evw_teleport.send(Validated::new(client_id, message.clone()));
+        }
+    }
+}
+
+fn validate_deposit(
+    mut evr_deposit: EventReader<MessageEvent<DepositLumina>>,
+    mut evw_deposit: EventWriter<Validated<DepositLumina>>,
+    q_tesseracts: Query<(&GlobalTransform, &WorldIdx), With<TesseractType>>,
+    q_spaceships: AliveQuery<(&Position, &WorldIdx), With<Spaceship>>,
+    q_game_scores: Query<(), With<GameScore>>,
+    lobby_infos: Res<LobbyInfos>,
+    player_infos: Res<PlayerInfos>,
+    mut validator: MessageValidator,
+) {
+    let deposit_radius = validator.settings().deposit_radius;
+
+    for deposit in evr_deposit.read() {
+        let client_id = *deposit.context();
+
+        let result = (|| {
+            // Deposition is only meaningful in lobbies that keep a score.
+            if lobby_infos
+                .get(&client_id)
+                .is_some_and(|e| q_game_scores.contains(*e))
+                == false
+            {
+                return Err(Violation::InvalidState("no game score"));
+            }
+
+            let (position, world_id) = player_infos[PlayerInfoType::Spaceship]
+                .get(&PlayerId(client_id))
+                .and_then(|e| q_spaceships.get(*e).ok())
+                .ok_or(Violation::InvalidState("no alive spaceship"))?;
+
+            let distance = closest_distance(
+                q_tesseracts
+                    .iter()
+                    .filter(|(_, tesseract_world)| *tesseract_world == world_id)
+                    .map(|(transform, _)| transform.translation().xy()),
+                position.0,
+            )
+            .ok_or(Violation::UnknownTarget)?;
+
+            if distance > deposit_radius {
+                return Err(Violation::OutOfRange {
+                    distance,
+                    max: deposit_radius,
+                });
+            }
+
+            Ok(())
+        })();
+
+        if validator.validate(client_id, result) {
+            evw_deposit.send(Validated::new(client_id, *deposit.message()));
+        }
+    }
+}
+
+fn validate_map_vote(
+    mut evr_map_vote: EventReader<MessageEvent<MapVote>>,
+    mut evw_map_vote: EventWriter<Validated<MapVote>>,
+    q_starting: Query<(), (With<LobbyFull>, Without<LobbyInGame>)>,
+    lobby_infos: Res<LobbyInfos>,
+    mut validator: MessageValidator,
+) {
+    for map_vote in evr_map_vote.read() {
+        let client_id = *map_vote.context();
+        let starting = lobby_infos
+            .get(&client_id)
+            .is_some_and(|e| q_starting.contains(*e));
+
+        // The map name is checked against the candidates when the vote is counted.
+        let result = match starting {
+            true => Ok(()),
+            false => Err(Violation::InvalidState("no game starting")),
+        };
+
+        if validator.validate(client_id, result) {
+            evw_map_vote.send(Validated::new(client_id, map_vote.message().clone()));
+        }
+    }
+}
+
+fn validate_map_loaded(
+    mut evr_map_loaded: EventReader<MessageEvent<MapLoaded>>,
+    mut evw_map_loaded: EventWriter<Validated<MapLoaded>>,
+    q_starting: Query<(), (With<LobbyFull>, Without<LobbyInGame>)>,
+    q_in_game: Query<(), With<LobbyInGame>>,
+    lobby_infos: Res<LobbyInfos>,
+    mut validator: MessageValidator,
+) {
+    for map_loaded in evr_map_loaded.read() {
+        let client_id = *map_loaded.context();
+        let lobby = lobby_infos.get(&client_id);
+
+        // Slow clients can finish loading after the load timeout started the game.
+        if lobby.is_some_and(|e| q_in_game.contains(*e)) {
+            continue;
+        }
+
+        let starting = lobby.is_some_and(|e| q_starting.contains(*e));
+
+        let result = match starting {
+            true => Ok(()),
+            false => Err(Violation::InvalidState("no game starting")),
+        };
+
+        if validator.validate(client_id, result) {
+            evw_map_loaded.send(Validated::new(client_id, *map_loaded.message()));
+        }
+    }
+}
+
+fn validate_camera_view(
+    mut evr_camera_view: EventReader<MessageEvent<CameraView>>,
+    mut evw_camera_view: EventWriter<Validated<CameraView>>,
+    lobby_infos: Res<LobbyInfos>,
+    mut validator: MessageValidator,
+) {
+    for camera_view in evr_camera_view.read() {
+        let client_id = *camera_view.context();
+        let view = *camera_view.message();
+
+        // Views sent right before leaving a lobby are dropped silently.
+        if lobby_infos.contains_key(&client_id) == false {
+            continue;
+        }
+
+        let result = match view.center.is_finite()
+            && view.half_extents.is_finite()
+            && view.half_extents.cmpge(Vec2::ZERO).all()
+        {
+            true => Ok(()),
+            false => Err(Violation::InvalidValue),
+        };
+
+        if validator.validate(client_id, result) {
+            evw_camera_view.send(Validated::new(client_id, view));
+        }
+    }
+}
+
+/// Distance from `position` to the closest target, [`None`] if there are no targets.
+fn closest_distance(targets: impl Iterator<Item = Vec2>, position: Vec2) -> Option<f32> {
+    targets
+        .map(|target| target.distance(position))
+        .min_by(f32::total_cmp)
+}
+
+/// Slowly forgive violations over time.
+fn decay_violations(
+    mut violations: ResMut<ClientViolations>,
+    settings: Res<LuminaSettings>,
+    time: Res<Time>,
+) {
+    let decay = settings.server.validation.violation_decay * time.delta_seconds();
+
+    for log in violations.values_mut() {
+        log.score = (log.score - decay).max(0.0);
+    }
+}
+
+/// Disconnect clients whose violation score exceeds the kick threshold.
+fn kick_repeat_offenders(
+    mut violations: ResMut<ClientViolations>,
+    mut connections: ResMut<ServerConnections>,
+    settings: Res<LuminaSettings>,
+) {
+    let kick_threshold = settings.server.validation.kick_threshold;
+
+    for (client_id, log) in violations.iter_mut() {
+        if log.score < kick_threshold || log.kicked {
+            continue;
+        }
+
+        warn!(
+            "Kicking {client_id:?} after {} violations, recent: {:?}",
+            log.total, log.recent
+        );
+        if let Err(err) = connections.disconnect(*client_id) {
+            error!("Unable to kick {client_id:?}: {err:?}");
+        }
+        log.kicked = true;
+    }
+}
+
+fn cleanup_disconnected(
+    mut evr_disconnect: EventReader<DisconnectEvent>,
+    mut violations: ResMut<ClientViolations>,
+    mut rate_limits: ResMut<RateLimits>,
+) {
+    for disconnect in evr_disconnect.read() {
+        violations.remove(&disconnect.client_id);
+        rate_limits.remove(&disconnect.client_id);
+    }
+}
+
+/// Rate limits and records violations of client messages.
+#[derive(SystemParam)]
+struct MessageValidator<'w> {
+    violations: ResMut<'w, ClientViolations>,
+    rate_limits: ResMut<'w, RateLimits>,
+    settings: Res<'w, LuminaSettings>,
+    time: Res<'w, Time<Real>>,
+}
+
+impl MessageValidator<'_> {
+    fn settings(&self) -> ValidationSettings {
+        self.settings.server.validation
+    }
+
+    /// Consume a message from the client's rate limit and record
+    /// the violation if any. Returns true if the message is valid.
+    fn validate(&mut self, client_id: ClientId, result: Result<(), Violation>) -> bool {
+        let settings = self.settings();
+        let now = self.time.elapsed_seconds();
+
+        let rate_limit = self
+            .rate_limits
+            .entry(client_id)
+            .or_insert_with(|| TokenBucket::new(settings.message_burst, now));
+
+        let result = match rate_limit.consume(settings.message_rate, settings.message_burst, now) {
+            true => result,
+            false => Err(Violation::RateLimited),
+        };
+
+        match result {
+            Ok(()) => true,
+            Err(violation) => {
+                self.record(client_id, violation);
+                false
+            }
+        }
+    }
+
+    fn record(&mut self, client_id: ClientId, violation: Violation) {
+        warn!("Rejected message from {client_id:?}: {violation:?}");
+        self.violations
+            .entry(client_id)
+            .or_default()
+            .push(violation);
+    }
+}
+
+/// Client message that passed validation.
+#[derive(Event, Debug)]
+pub struct Validated<M> {
+    context: ClientId,
+    message: M,
+}
+
+impl<M> Validated<M> {
+    pub fn new(context: ClientId, message: M) -> Self {
+        Self { context, message }
+    }
+
+    /// The client that sent the message.
+    pub fn context(&self) -> &ClientId {
+        &self.context
+    }
+
+    pub fn message(&self) -> &M {
+        &self.message
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Violation {
+    /// Client sent more messages than allowed.
+    RateLimited,
+    /// Message is not allowed in the client's current state.
+    InvalidState(&'static str),
+    /// Message contains a value that a legit client can't send.
+    InvalidValue,
+    /// Message refers to a target that does not exist in the client's world.
+    UnknownTarget,
+    /// Client is too far away from the target.
+    OutOfRange { distance: f32, max: f32 },
+    /// Target is still cooling down.
+    OnCooldown,
+}
+
+impl Violation {
+    /// Score added to the client's violation score.
+    /// Rate limiting and state mismatches can happen
+    /// to legit clients under bad network conditions.
+    pub fn score(&self) -> f32 {
+        match self {
+            Violation::RateLimited => 0.1,
+            Violation::InvalidState(_) | Violation::OnCooldown => 0.5,
+            Violation::OutOfRange { .. } => 2.0,
+            Violation::InvalidValue | Violation::UnknownTarget => 5.0,
+        }
+    }
+}
+
+/// Violations of each client.
+#[derive(Resource, Default, Debug, Deref, DerefMut)]
+pub struct ClientViolations(HashMap<ClientId, ViolationLog>);
+
+#[derive(Default, Debug)]
+pub struct ViolationLog {
+    /// Decaying score used to decide whether to kick the client.
+    pub score: f32,
+    /// Total number of violations.
+    pub total: u32,
+    /// Most recent violations.
+    pub recent: VecDeque<Violation>,
+    /// Already kicked, waiting for the disconnection.
+    pub kicked: bool,
+}
+
+impl ViolationLog {
+    const MAX_RECENT: usize = 16;
+
+    pub fn push(&mut self, violation: Violation) {
+        self.score += violation.score();
+        self.total += 1;
+
+        if self.recent.len() == Self::MAX_RECENT {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(violation);
+    }
+}
+
+#[derive(Resource, Default, Debug, Deref, DerefMut)]
+struct RateLimits(HashMap<ClientId, TokenBucket>);
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f32,
+    last_refill: f32,
+}
+
+impl TokenBucket {
+    fn new(burst: f32, now: f32) -> Self {
+        Self {
+            tokens: burst,
+            last_refill: now,
+        }
+    }
+
+    /// Refill the bucket and consume a token if available.
+    fn consume(&mut self, rate: f32, burst: f32, now: f32) -> bool {
+        self.tokens = (self.tokens + (now - self.last_refill) * rate).min(burst);
+        self.last_refill = now;
+
+        if self.tokens < 1.0 {
+            return false;
+        }
+
+        self.tokens -= 1.0;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::transform::TransformPlugin;
+    use blenvy::BlueprintInfo;
+
+    use super::*;
+    use crate::player::objective::spawn_tesseract_anchors;
+
+    fn tesseract_anchors(app: &mut App) -> Vec<Vec2> {
+        let mut q_anchors = app
+            .world_mut()
+            .query_filtered::<&GlobalTransform, With<TesseractType>>();
+
+        q_anchors
+            .iter(app.world())
+            .map(|transform| transform.translation().xy())
+            .collect()
+    }
+
+    #[test]
+    fn deposit_next_to_blueprint_tesseract() {
+        let mut app = App::new();
+        app.add_plugins((TransformPlugin, HierarchyPlugin))
+            .add_systems(Update, spawn_tesseract_anchors);
+
+        // Blueprint maps place the (client only) tesseract inside the map hierarchy.
+        app.world_mut()
+            .spawn(SpatialBundle::from_transform(Transform::from_xyz(
+                100.0, 0.0, 0.0,
+            )))
+            .with_children(|builder| {
+                builder.spawn((
+                    TesseractType::Tesseract.info(),
+                    SpatialBundle::from_transform(Transform::from_xyz(20.0, -40.0, 0.0)),
+                ));
+                builder.spawn((
+                    BlueprintInfo::from_path("blueprints/Door.glb"),
+                    SpatialBundle::default(),
+                ));
+            });
+        app.update();
+
+        let anchors = tesseract_anchors(&mut app);
+        assert_eq!(anchors, vec![Vec2::new(120.0, -40.0)]);
+
+        let deposit_radius = ValidationSettings::default().deposit_radius;
+        let position = Vec2::new(120.0 + deposit_radius * 0.5, -40.0);
+        let distance = closest_distance(anchors.into_iter(), position).unwrap();
+        assert!(distance <= deposit_radius);
+    }
+
+    #[test]
+    fn deposit_without_tesseract() {
+        assert_eq!(closest_distance(std::iter::empty(), Vec2::ZERO), None);
+    }
+}
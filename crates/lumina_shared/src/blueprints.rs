@@ -1,5 +1,6 @@
 use blenvy::*;
 use lightyear::prelude::*;
+use lumina_common::prelude::XorShift32;
 use strum::{AsRefStr, EnumCount, EnumIter};
 
 /// Marker for replicating the entity over the network.
@@ -73,7 +74,9 @@ pub struct ServerOnly;
 #[reflect(Component)]
 pub struct ClientOnly;
 
-#[derive(Component, Reflect, AsRefStr, Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[derive(
+    Component, Reflect, AsRefStr, EnumIter, Serialize, Deserialize, Debug, Clone, Copy, PartialEq,
+)]
 #[reflect(Component)]
 #[strum(prefix = "levels/maps/")]
 pub enum MapType {
@@ -84,7 +87,18 @@ pub enum MapType {
 }
 
 #[derive(
-    Component, Reflect, AsRefStr, Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq,
+    Component,
+    Reflect,
+    AsRefStr,
+    Serialize,
+    Deserialize,
+    Default,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
 )]
 #[reflect(Component)]
 #[strum(prefix = "spaceship_blueprints/")]
@@ -103,7 +117,9 @@ impl SpaceshipType {
     }
 }
 
-#[derive(Component, Reflect, AsRefStr, Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[derive(
+    Component, Reflect, AsRefStr, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash,
+)]
 #[reflect(Component)]
 #[strum(prefix = "weapon_blueprints/")]
 pub enum WeaponType {
@@ -137,27 +153,31 @@ pub enum AmmoType {
 
 #[derive(Component, Reflect, AsRefStr, Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
 #[reflect(Component)]
-#[strum(prefix = "levels/tesseracts/")]
+#[strum(prefix = "blueprints/")]
 pub enum TesseractType {
     Tesseract,
 }
 
 // TODO: Move this into objective instead.
-#[derive(Component, Reflect, Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[derive(Component, Reflect, AsRefStr, Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
 #[reflect(Component)]
+#[strum(prefix = "blueprints/")]
 pub enum OreType {
     /// Drops 1-2 [LuminaType::Normal].
+    #[strum(serialize = "SmallOre")]
     Small,
     /// Drops 3-5 [LuminaType::Normal].
+    #[strum(serialize = "MediumOre")]
     Medium,
     /// Drops 5-8 [LuminaType::Normal].
+    #[strum(serialize = "LargeOre")]
     Large,
 }
 
 impl OreType {
     /// Calculate random value based on ore type.
-    pub fn rand_value(&self) -> u8 {
-        let mut rand_val = rand::random();
+    pub fn rand_value(&self, rng: &mut XorShift32) -> u8 {
+        let mut rand_val = rng.next_u32() as u8;
         rand_val = match self {
             OreType::Small => (rand_val % 2) + 1,
             OreType::Medium => (rand_val % 3) + 3,
@@ -1,10 +1,25 @@
 use bevy::prelude::*;
 
 pub mod animator;
+pub mod arena;
+pub mod map_preload;
+pub mod map_registry;
+pub mod navigation;
+pub mod neutral;
+pub mod pickup;
 pub mod teleporter;
 
 pub mod prelude {
-    pub use super::animator::{Animator, Playback, RepeatMode};
+    pub use super::animator::{
+        AnimationHazard, AnimationMarker, Animator, AnimatorEvent, AnimatorQuery, AnimatorSwitch,
+        HazardActive, Playback, RepeatMode, SwitchAction,
+    };
+    pub use super::arena::{ArenaTerrainFile, GameMap, GameMapReady, GameMapRoot, ProceduralArena};
+    pub use super::map_preload::BlueprintPreload;
+    pub use super::map_registry::{MapInfo, MapRegistry, MapRegistryAsset, MapSource};
+    pub use super::navigation::{NavGrid, NavGrids, NavPath, NavQuery};
+    pub use super::neutral::{Neutral, NeutralDrone, NeutralMine, NeutralShot, NeutralTurret};
+    pub use super::pickup::{Pickup, PickupCooldown, PickupType};
     pub use super::teleporter::{
         Teleporter, TeleporterCooldown, TeleporterEffect, TeleporterEnd, TeleporterStart,
     };
@@ -20,6 +35,15 @@ pub(super) struct GamePlugin;
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((animator::AnimatorPlugin, teleporter::TeleporterPlugin));
+        app.add_plugins((
+            animator::AnimatorPlugin,
+            arena::ArenaPlugin,
+            map_preload::MapPreloadPlugin,
+            map_registry::MapRegistryPlugin,
+            navigation::NavigationPlugin,
+            neutral::NeutralPlugin,
+            pickup::PickupPlugin,
+            teleporter::TeleporterPlugin,
+        ));
     }
 }
@@ -1,7 +1,9 @@
 use avian2d::prelude::*;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use blenvy::*;
 use lightyear::prelude::*;
+use lumina_common::prelude::*;
 
 // For docs.
 #[allow(unused_imports)]
@@ -19,7 +21,10 @@ impl Plugin for AnimatorPlugin {
                 update_animation_physics,
             ),
         )
-        .add_systems(Update, (init_animation_player, update_animation_player));
+        .add_systems(Update, (init_animation_player, update_animation_player))
+        .observe(setup_animation_hazard)
+        .observe(setup_animator_switch)
+        .observe(toggle_animation_hazards);
     }
 }
 
@@ -112,6 +117,7 @@ fn advance_animator(
     for (mut animator, mut playback, entity) in q_animators.iter_mut() {
         animator.prev_time = animator.time;
         animator.time = playback.time;
+        let start_time = playback.time;
         playback.time += time.delta_seconds() * playback.time_scale;
 
         if playback.time > animator.duration {
@@ -137,6 +143,32 @@ fn advance_animator(
         }
 
         playback.time = playback.time.clamp(0.0, animator.duration);
+
+        let reverse = playback.time < start_time;
+        for marker in animator
+            .events
+            .iter()
+            .filter(|marker| marker_crossed(marker.time, start_time, playback.time))
+        {
+            commands.trigger_targets(
+                AnimatorEvent {
+                    name: marker.name.clone(),
+                    reverse,
+                },
+                entity,
+            );
+        }
+    }
+}
+
+/// Whether a marker at `time` is crossed when the playback moves from `from` to `to`.
+///
+/// Markers at the start are only crossed when playing forward from the start.
+fn marker_crossed(time: f32, from: f32, to: f32) -> bool {
+    if from < to {
+        (from < time || from == 0.0) && time <= to
+    } else {
+        to <= time && time < from
     }
 }
 
@@ -169,6 +201,161 @@ fn track_delay_timer(
     }
 }
 
+fn setup_animation_hazard(
+    trigger: Trigger<OnAdd, AnimationHazard>,
+    mut commands: Commands,
+    q_hazards: Query<&AnimationHazard>,
+) {
+    let entity = trigger.entity();
+    let Ok(hazard) = q_hazards.get(entity) else {
+        return;
+    };
+
+    let mut cmd = commands.entity(entity);
+    cmd.insert(CollidingEntities::default());
+    if hazard.activate.is_empty() {
+        cmd.insert(HazardActive);
+    }
+}
+
+fn setup_animator_switch(trigger: Trigger<OnAdd, AnimatorSwitch>, mut commands: Commands) {
+    commands
+        .entity(trigger.entity())
+        .insert(CollidingEntities::default());
+}
+
+/// Toggle the [`AnimationHazard`]s of an [`Animator`] based on its [`AnimatorEvent`]s.
+fn toggle_animation_hazards(
+    trigger: Trigger<AnimatorEvent>,
+    mut commands: Commands,
+    q_hazards: Query<&AnimationHazard>,
+    q_children: Query<&Children>,
+) {
+    let entity = trigger.entity();
+    let event = trigger.event();
+
+    for hazard_entity in std::iter::once(entity).chain(q_children.iter_descendants(entity)) {
+        let Ok(hazard) = q_hazards.get(hazard_entity) else {
+            continue;
+        };
+
+        // Markers have the opposite effect when playing in reverse.
+        let active = if event.name == hazard.activate {
+            event.reverse == false
+        } else if event.name == hazard.deactivate {
+            event.reverse
+        } else {
+            continue;
+        };
+
+        match active {
+            true => commands.entity(hazard_entity).insert(HazardActive),
+            false => commands.entity(hazard_entity).remove::<HazardActive>(),
+        };
+    }
+}
+
+/// Control [`Animator`]s by their [`Name`] (the object name in Blender).
+///
+/// Changes made on the server are replicated through the [`Playback`].
+#[derive(SystemParam)]
+pub struct AnimatorQuery<'w, 's> {
+    commands: Commands<'w, 's>,
+    q_animators: Query<
+        'w,
+        's,
+        (
+            &'static Animator,
+            &'static mut Playback,
+            &'static Name,
+            &'static WorldIdx,
+            Entity,
+        ),
+    >,
+    q_hazards: Query<'w, 's, &'static AnimationHazard>,
+    q_children: Query<'w, 's, &'static Children>,
+}
+
+impl AnimatorQuery<'_, '_> {
+    /// Play forward from the current time.
+    /// Returns false if no animator with this name exists in the world.
+    pub fn play(&mut self, world_id: WorldIdx, name: &str) -> bool {
+        self.control(world_id, name, |animator, playback| {
+            playback.time_scale = animator.time_scale;
+        })
+    }
+
+    /// Play in reverse from the current time.
+    /// Returns false if no animator with this name exists in the world.
+    pub fn reverse(&mut self, world_id: WorldIdx, name: &str) -> bool {
+        self.control(world_id, name, |animator, playback| {
+            playback.time_scale = -animator.time_scale;
+        })
+    }
+
+    /// Pause at the current time.
+    /// Returns false if no animator with this name exists in the world.
+    pub fn stop(&mut self, world_id: WorldIdx, name: &str) -> bool {
+        self.control(world_id, name, |_, playback| {
+            playback.time_scale = 0.0;
+        })
+    }
+
+    /// Jump to `time` without triggering the [`AnimatorEvent`]s in between,
+    /// the [`AnimationHazard`]s are toggled to their state at that time instead.
+    /// Returns false if no animator with this name exists in the world.
+    pub fn seek(&mut self, world_id: WorldIdx, name: &str, time: f32) -> bool {
+        let found = self.control(world_id, name, |animator, playback| {
+            playback.time = time.clamp(0.0, animator.duration);
+        });
+
+        for (animator, playback, animator_name, &animator_world, entity) in self.q_animators.iter()
+        {
+            if animator_world != world_id || animator_name.as_str() != name {
+                continue;
+            }
+
+            for hazard_entity in
+                std::iter::once(entity).chain(self.q_children.iter_descendants(entity))
+            {
+                let Ok(hazard) = self.q_hazards.get(hazard_entity) else {
+                    continue;
+                };
+
+                match hazard.active_at(&animator.events, playback.time) {
+                    true => self.commands.entity(hazard_entity).insert(HazardActive),
+                    false => self.commands.entity(hazard_entity).remove::<HazardActive>(),
+                };
+            }
+        }
+
+        found
+    }
+
+    fn control(
+        &mut self,
+        world_id: WorldIdx,
+        name: &str,
+        mut f: impl FnMut(&Animator, &mut Playback),
+    ) -> bool {
+        let mut found = false;
+        for (animator, mut playback, animator_name, &animator_world, entity) in
+            self.q_animators.iter_mut()
+        {
+            if animator_world != world_id || animator_name.as_str() != name {
+                continue;
+            }
+
+            f(animator, &mut playback);
+            // Manual control overrides any pending repeat.
+            self.commands.entity(entity).remove::<DelayTimer>();
+            found = true;
+        }
+
+        found
+    }
+}
+
 #[derive(Component, Reflect)]
 #[reflect(Component)]
 pub struct Animator {
@@ -180,6 +367,9 @@ pub struct Animator {
     repeat_mode: RepeatMode,
     /// A non-negative time scale for reference when repetition occurs.
     time_scale: f32,
+    /// Markers that trigger an [`AnimatorEvent`] when the playback crosses them.
+    #[reflect(default)]
+    events: Vec<AnimationMarker>,
     #[reflect(ignore)]
     /// The current playback time used for seeking the [`ActiveAnimation`].
     time: f32,
@@ -189,6 +379,91 @@ pub struct Animator {
     prev_time: f32,
 }
 
+/// A named point in time of an [`Animator`], e.g. "door closed" or "laser on".
+#[derive(Reflect, Debug, Clone, PartialEq)]
+pub struct AnimationMarker {
+    pub name: String,
+    pub time: f32,
+}
+
+/// Triggered on the [`Animator`] entity when its playback crosses an [`AnimationMarker`].
+///
+/// Clients may receive it again during rollbacks,
+/// gameplay logic should observe it on the server.
+#[derive(Event, Debug, Clone)]
+pub struct AnimatorEvent {
+    pub name: String,
+    /// True if the marker was crossed while playing in reverse.
+    pub reverse: bool,
+}
+
+/// Damages spaceships inside its collider while active.
+///
+/// Must be on an [`Animator`] entity or one of its descendants and requires a sensor collider.
+/// The hazard is toggled by the [`AnimatorEvent`]s of the [`Animator`],
+/// markers crossed in reverse have the opposite effect.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct AnimationHazard {
+    pub damage_per_second: f32,
+    /// Marker name that activates the hazard, empty to be active from the start.
+    #[reflect(default)]
+    pub activate: String,
+    /// Marker name that deactivates the hazard.
+    #[reflect(default)]
+    pub deactivate: String,
+}
+
+impl AnimationHazard {
+    /// Whether the hazard is active after playing forward from the start to `time`.
+    pub fn active_at(&self, markers: &[AnimationMarker], time: f32) -> bool {
+        let mut crossed = markers
+            .iter()
+            .filter(|marker| marker.time <= time)
+            .collect::<Vec<_>>();
+        crossed.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+        crossed
+            .into_iter()
+            .fold(self.activate.is_empty(), |active, marker| {
+                if marker.name == self.activate {
+                    true
+                } else if marker.name == self.deactivate {
+                    false
+                } else {
+                    active
+                }
+            })
+    }
+}
+
+/// Controls an [`Animator`] (by its [`Name`]) when a spaceship enters
+/// the sensor collider of this entity, e.g. a pressure plate that opens a door.
+/// Only the server reacts to switches.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct AnimatorSwitch {
+    /// Name of the [`Animator`] in the same world.
+    pub animator: String,
+    pub action: SwitchAction,
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwitchAction {
+    /// Play forward from the current time.
+    Play,
+    /// Play in reverse from the current time.
+    Reverse,
+    /// Pause at the current time.
+    Stop,
+    /// Seek to the start and play forward.
+    Restart,
+}
+
+/// Marker component for [`AnimationHazard`]s that are dealing damage.
+#[derive(Component)]
+pub struct HazardActive;
+
 #[derive(Component, Reflect, Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[reflect(Component)]
 pub struct Playback {
@@ -228,3 +503,59 @@ pub enum RepeatMode {
 
 #[derive(Component, Deref, DerefMut, Serialize, Deserialize, PartialEq)]
 pub struct DelayTimer(#[deref] Timer, bool);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markers_are_crossed_once_per_direction() {
+        // Forward.
+        assert!(marker_crossed(1.0, 0.5, 1.0));
+        assert!(marker_crossed(1.0, 1.0, 1.5) == false);
+        // Reverse.
+        assert!(marker_crossed(1.0, 1.5, 1.0));
+        assert!(marker_crossed(1.0, 1.0, 0.5) == false);
+        // Stopped.
+        assert!(marker_crossed(1.0, 1.0, 1.0) == false);
+    }
+
+    #[test]
+    fn hazard_state_after_seeking() {
+        let markers = [
+            AnimationMarker {
+                name: "laser off".to_string(),
+                time: 2.0,
+            },
+            AnimationMarker {
+                name: "laser on".to_string(),
+                time: 1.0,
+            },
+        ];
+        let hazard = AnimationHazard {
+            damage_per_second: 1.0,
+            activate: "laser on".to_string(),
+            deactivate: "laser off".to_string(),
+        };
+
+        assert!(hazard.active_at(&markers, 0.5) == false);
+        assert!(hazard.active_at(&markers, 1.0));
+        assert!(hazard.active_at(&markers, 1.5));
+        assert!(hazard.active_at(&markers, 2.5) == false);
+
+        // Active from the start until deactivated.
+        let hazard = AnimationHazard {
+            activate: String::new(),
+            ..hazard
+        };
+        assert!(hazard.active_at(&markers, 0.0));
+        assert!(hazard.active_at(&markers, 2.0) == false);
+    }
+
+    #[test]
+    fn start_marker_is_crossed_when_playing_from_the_start() {
+        assert!(marker_crossed(0.0, 0.0, 0.1));
+        assert!(marker_crossed(0.0, 0.1, 0.0));
+        assert!(marker_crossed(0.0, 0.0, 0.0) == false);
+    }
+}
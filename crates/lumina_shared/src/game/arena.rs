@@ -0,0 +1,437 @@
+//! Procedurally generated arenas built on top of [`lumina_terrain`].
+//!
+//! Both the server and the clients generate the terrain locally from the same
+//! seed (or load the same terrain file), so terrain tiles never need to be
+//! replicated. Spawn points, objective areas and the tesseract are then placed
+//! onto the generated layout.
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use blenvy::*;
+use lightyear::prelude::*;
+use lumina_common::prelude::*;
+use lumina_terrain::config::TerrainConfigAsset;
+use lumina_terrain::prelude::*;
+use strum::EnumCount;
+
+use crate::blueprints::{OreType, TesseractType};
+use crate::player::prelude::*;
+use crate::player::GameLayer;
+
+pub(super) struct ArenaPlugin;
+
+impl Plugin for ArenaPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (generate_arena, layout_arena, mark_blueprint_map_ready),
+        )
+        .observe(clear_arena);
+    }
+}
+
+/// Generate the terrain (or load it from its [`ArenaTerrainFile`])
+/// once the arena knows which world it belongs to.
+fn generate_arena(
+    q_arenas: Query<
+        (
+            &ProceduralArena,
+            Option<&ArenaTerrainFile>,
+            &WorldIdx,
+            Entity,
+        ),
+        Added<WorldIdx>,
+    >,
+    mut evw_generate: EventWriter<GenerateTerrain>,
+    mut evw_load: EventWriter<LoadTerrain>,
+    asset_server: Res<AssetServer>,
+) {
+    for (arena, file, &world_id, entity) in q_arenas.iter() {
+        let layers = CollisionLayers::new(GameLayer::Terrain, LayerMask::ALL);
+
+        match file {
+            Some(file) => evw_load.send(LoadTerrain {
+                handle: asset_server.load(&file.0),
+                entity,
+                layers,
+                world_id,
+            }),
+            None => evw_generate.send(GenerateTerrain {
+                seed: arena.seed,
+                entity,
+                layers,
+                world_id,
+            }),
+        };
+    }
+}
+
+/// Place spawn points, objective areas and the tesseract onto the generated terrain.
+fn layout_arena(
+    mut commands: Commands,
+    q_arenas: Query<
+        (&ProceduralArena, &TerrainStates, &TerrainMapConfig, Entity),
+        Added<TerrainStates>,
+    >,
+    network_identity: NetworkIdentity,
+) {
+    for (arena, states, config, entity) in q_arenas.iter() {
+        let Some(layout) = ArenaLayout::new(states, config, arena.seed, arena.objective_count)
+        else {
+            error!(
+                "Unable to fit a layout into arena {entity} (seed: {}).",
+                arena.seed
+            );
+            continue;
+        };
+
+        let tile_to_world = |tile: UVec2| tile.as_vec2() * config.tile_size;
+        let center = tile_to_world(layout.tesseract);
+
+        commands.entity(entity).with_children(|builder| {
+            builder
+                .spawn((SpawnPointParent::default(), SpatialBundle::default()))
+                .with_children(|builder| {
+                    for (team_type, &base) in [TeamType::A, TeamType::B].iter().zip(&layout.bases) {
+                        let base = tile_to_world(base);
+                        let rotation = Quat::from_rotation_z((center - base).to_angle());
+
+                        for offset in [-1.0, 0.0, 1.0] {
+                            let translation = base + Vec2::new(0.0, offset * config.tile_size);
+
+                            builder.spawn((
+                                spawn_point_info(*team_type),
+                                SpawnBlueprint,
+                                TransformBundle::from_transform(
+                                    Transform::from_translation(translation.extend(0.0))
+                                        .with_rotation(rotation),
+                                ),
+                            ));
+                        }
+                    }
+                });
+
+            builder.spawn((
+                TesseractType::Tesseract.info(),
+                SpawnBlueprint,
+                TransformBundle::from_transform(Transform::from_translation(center.extend(0.0))),
+            ));
+
+            for &area in layout.objective_areas.iter() {
+                let mut area_builder = builder.spawn((
+                    ObjectiveArea::default(),
+                    SpatialBundle::from_transform(Transform::from_translation(
+                        tile_to_world(area).extend(0.0),
+                    )),
+                ));
+
+                // Ores are replicated from the server.
+                if network_identity.is_server() == false {
+                    continue;
+                }
+
+                area_builder.with_children(|builder| {
+                    for (ore_type, offset) in ORE_PLACEMENTS {
+                        builder.spawn((
+                            ore_type.info(),
+                            SpawnBlueprint,
+                            TransformBundle::from_transform(Transform::from_translation(
+                                (offset * config.tile_size).extend(0.0),
+                            )),
+                        ));
+                    }
+                });
+            }
+        });
+
+        commands.entity(entity).insert(GameMapReady);
+    }
+}
+
+/// Blueprint maps are ready once Blenvy has spawned the whole hierarchy.
+fn mark_blueprint_map_ready(
+    mut commands: Commands,
+    q_maps: Query<Entity, (With<GameMapRoot>, Added<BlueprintInstanceReady>)>,
+) {
+    for entity in q_maps.iter() {
+        commands.entity(entity).insert(GameMapReady);
+    }
+}
+
+/// Terrain tiles are not children of the arena, release them back into the pool.
+fn clear_arena(trigger: Trigger<OnRemove, ProceduralArena>, mut terrain: Terrain) {
+    terrain.clear_terrain(trigger.entity());
+}
+
+/// Ores placed in every objective area (offsets are in tiles).
+const ORE_PLACEMENTS: [(OreType, Vec2); 7] = [
+    (OreType::Large, Vec2::ZERO),
+    (OreType::Medium, Vec2::new(-1.0, 0.0)),
+    (OreType::Medium, Vec2::new(1.0, 0.0)),
+    (OreType::Small, Vec2::new(-0.5, 0.9)),
+    (OreType::Small, Vec2::new(0.5, 0.9)),
+    (OreType::Small, Vec2::new(-0.5, -0.9)),
+    (OreType::Small, Vec2::new(0.5, -0.9)),
+];
+
+fn spawn_point_info(team_type: TeamType) -> BlueprintInfo {
+    BlueprintInfo::from_path(match team_type {
+        TeamType::A => "blueprints/SpawnPointA.glb",
+        TeamType::B => "blueprints/SpawnPointB.glb",
+    })
+}
+
+/// Tile coordinates of everything placed onto a [`ProceduralArena`].
+#[derive(Debug, Clone)]
+pub struct ArenaLayout {
+    /// Center of the spawn base of each [`TeamType`].
+    pub bases: [UVec2; TeamType::COUNT],
+    pub tesseract: UVec2,
+    pub objective_areas: Vec<UVec2>,
+}
+
+impl ArenaLayout {
+    /// Clearance (in tiles) needed around an objective area.
+    const AREA_CLEARANCE: u32 = 1;
+
+    /// Compute a layout from the terrain states, returns [`None`]
+    /// if there is not enough empty space.
+    pub fn new(
+        states: &TerrainStates,
+        config: &TerrainConfigAsset,
+        seed: u32,
+        objective_count: usize,
+    ) -> Option<Self> {
+        let width = states.width() as u32;
+        let height = states.height() as u32;
+        let size = UVec2::new(width, height);
+
+        // Bases are reserved (and guaranteed to be connected) by the terrain generation.
+        let [base_a, base_b] = TerrainStates::base_tiles(config);
+        let tesseract = find_clear_tile(states, size / 2, 1)?;
+
+        // Farthest point sampling: every area is placed as far as possible
+        // from the bases, the tesseract and the other areas.
+        // Areas are placed in mirrored pairs so that neither team is favored.
+        let mut rng = XorShift32::new(seed);
+        let mut placed = vec![base_a, base_b, tesseract];
+        let mut objective_areas = Vec::with_capacity(objective_count);
+        let clear = clear_tiles(states, Self::AREA_CLEARANCE).collect::<Vec<_>>();
+        let mut candidates = clear
+            .iter()
+            .copied()
+            // Only pick from the first half, the other half is mirrored.
+            .filter(|c| c.x * 2 < width)
+            .filter(|c| {
+                let mirrored = config.symmetry.mirror(*c, size);
+                // Center tiles of odd sized maps mirror onto themselves.
+                mirrored != *c && clear.contains(&mirrored)
+            })
+            .collect::<Vec<_>>();
+
+        while objective_areas.len() < objective_count {
+            let distances = candidates
+                .iter()
+                .map(|c| {
+                    placed
+                        .iter()
+                        .map(|p| c.as_vec2().distance_squared(p.as_vec2()))
+                        .fold(f32::MAX, f32::min)
+                })
+                .collect::<Vec<_>>();
+            let max_distance = distances.iter().copied().fold(0.0, f32::max);
+
+            // Randomly pick among the candidates that are almost as far as the farthest one.
+            let far_candidates = candidates
+                .iter()
+                .zip(distances)
+                .filter(|(_, d)| *d > 0.0 && *d >= max_distance * 0.8)
+                .map(|(c, _)| *c)
+                .collect::<Vec<_>>();
+            if far_candidates.is_empty() {
+                return None;
+            }
+
+            let area = far_candidates[rng.next_u32() as usize % far_candidates.len()];
+            candidates.retain(|c| *c != area);
+
+            for area in [area, config.symmetry.mirror(area, size)] {
+                if objective_areas.len() < objective_count {
+                    placed.push(area);
+                    objective_areas.push(area);
+                }
+            }
+        }
+
+        Some(Self {
+            bases: [base_a, base_b],
+            tesseract,
+            objective_areas,
+        })
+    }
+}
+
+/// Find the empty tile closest to `target` with at least `clearance` empty tiles around it.
+/// The clearance is reduced until a tile is found.
+fn find_clear_tile(states: &TerrainStates, target: UVec2, clearance: u32) -> Option<UVec2> {
+    (0..=clearance).rev().find_map(|clearance| {
+        clear_tiles(states, clearance)
+            .min_by_key(|tile| tile.as_ivec2().distance_squared(target.as_ivec2()))
+    })
+}
+
+/// Iterate over empty tiles with at least `clearance` empty tiles around them.
+fn clear_tiles(states: &TerrainStates, clearance: u32) -> impl Iterator<Item = UVec2> + '_ {
+    let clearance = clearance as usize;
+
+    states.iter().filter_map(move |(x, y, _)| {
+        if x < clearance
+            || y < clearance
+            || x + clearance >= states.width()
+            || y + clearance >= states.height()
+        {
+            return None;
+        }
+
+        for cy in (y - clearance)..=(y + clearance) {
+            for cx in (x - clearance)..=(x + clearance) {
+                if *states.get(cx, cy) {
+                    return None;
+                }
+            }
+        }
+
+        Some(UVec2::new(x as u32, y as u32))
+    })
+}
+
+/// Holder of a procedurally generated arena, terrain will be generated
+/// from the seed once a [`WorldIdx`] is added to the entity.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ProceduralArena {
+    /// Seed of the terrain and of the arena layout.
+    pub seed: u32,
+    /// Number of objective areas placed onto the terrain.
+    pub objective_count: usize,
+}
+
+/// Load the terrain of a [`ProceduralArena`] from a `.terrain.ron` file
+/// (asset path) instead of generating it, only the layout is seeded.
+#[derive(Component, Debug, Clone)]
+pub struct ArenaTerrainFile(pub String);
+
+/// Root entity of a map spawned from a [`GameMap`].
+#[derive(Component, Default, Debug, Clone, Copy)]
+pub struct GameMapRoot;
+
+/// Inserted on the [`GameMapRoot`] once everything in the map has been spawned.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct GameMapReady;
+
+/// The map to be played in a game.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum GameMap {
+    /// A map exported from Blender (blueprint path).
+    Blueprint {
+        path: String,
+    },
+    Procedural {
+        seed: u32,
+        objective_count: usize,
+    },
+    /// A fixed terrain loaded from a `.terrain.ron` file (asset path).
+    Terrain {
+        path: String,
+        seed: u32,
+        objective_count: usize,
+    },
+}
+
+impl GameMap {
+    /// Spawn the map. A [`WorldIdx`] must be inserted or propagated from
+    /// the parent for the [`ProceduralArena`] to be generated.
+    pub fn spawn<'a>(&self, commands: &'a mut Commands) -> EntityCommands<'a> {
+        match *self {
+            GameMap::Blueprint { ref path } => {
+                commands.spawn((BlueprintInfo::from_path(path), SpawnBlueprint, GameMapRoot))
+            }
+            GameMap::Procedural {
+                seed,
+                objective_count,
+            } => commands.spawn((
+                ProceduralArena {
+                    seed,
+                    objective_count,
+                },
+                SpatialBundle::default(),
+                GameMapRoot,
+            )),
+            GameMap::Terrain {
+                ref path,
+                seed,
+                objective_count,
+            } => commands.spawn((
+                ProceduralArena {
+                    seed,
+                    objective_count,
+                },
+                ArenaTerrainFile(path.clone()),
+                SpatialBundle::default(),
+                GameMapRoot,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lumina_terrain::config::TerrainSymmetry;
+
+    use super::*;
+
+    fn config(size: UVec2, symmetry: TerrainSymmetry) -> TerrainConfigAsset {
+        TerrainConfigAsset {
+            size,
+            tile_size: 1.0,
+            chunk_size: 16,
+            noise_surr_width: 1,
+            base_size: UVec2::splat(3),
+            noise_scale: 0.03,
+            noise_threshold: 0.4,
+            gradient_pow: 1.3,
+            symmetry,
+            max_attempts: 1,
+            passes: Vec::new(),
+            tile_health: None,
+        }
+    }
+
+    #[test]
+    fn objective_areas_are_mirrored_pairs() {
+        // Odd width so that the center column mirrors onto itself.
+        let size = UVec2::new(15, 21);
+        let states = TerrainStates::from(Vec2d::new_from_default(15, 21));
+
+        for symmetry in [TerrainSymmetry::Axis, TerrainSymmetry::Point] {
+            let config = config(size, symmetry);
+
+            for seed in 0..32 {
+                let layout = ArenaLayout::new(&states, &config, seed, 4).unwrap();
+                let areas = &layout.objective_areas;
+
+                assert_eq!(areas.len(), 4);
+                for (i, area) in areas.iter().enumerate() {
+                    assert!(areas[i + 1..].contains(area) == false);
+                    assert!(areas.contains(&symmetry.mirror(*area, size)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn no_layout_without_space() {
+        let size = UVec2::new(15, 21);
+        let states = TerrainStates::from(Vec2d::new(15, 21, true));
+
+        assert!(ArenaLayout::new(&states, &config(size, TerrainSymmetry::Axis), 0, 2).is_none());
+    }
+}
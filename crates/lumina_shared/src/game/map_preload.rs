@@ -0,0 +1,120 @@
+//! Preloading of blueprint assets, read from the `.meta.ron` files exported by blenvy.
+use bevy::asset::io::Reader;
+use bevy::asset::{ron, AssetLoader, AsyncReadExt, LoadContext, LoadState};
+use bevy::gltf::Gltf;
+use bevy::prelude::*;
+use serde::Deserialize;
+use thiserror::Error;
+
+pub(super) struct MapPreloadPlugin;
+
+impl Plugin for MapPreloadPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<BlueprintPreload>()
+            .init_asset_loader::<BlueprintPreloadLoader>();
+    }
+}
+
+/// Every asset needed to spawn a blueprint.
+///
+/// Load it from the blueprint path with [`BlueprintPreload::meta_path`].
+/// Blenvy reuses the loaded assets as long as this asset is kept alive.
+#[derive(Asset, TypePath, Debug)]
+pub struct BlueprintPreload {
+    /// The blueprint followed by its dependencies.
+    #[dependency]
+    pub assets: Vec<Handle<Gltf>>,
+}
+
+impl BlueprintPreload {
+    /// Path of the `.meta.ron` file of a blueprint.
+    pub fn meta_path(blueprint_path: &str) -> String {
+        blueprint_path.replace(".glb", ".meta.ron")
+    }
+
+    /// Fraction of the assets that finished loading (failed assets are counted as finished).
+    pub fn progress(&self, asset_server: &AssetServer) -> f32 {
+        if self.assets.is_empty() {
+            return 1.0;
+        }
+
+        let finished = self
+            .assets
+            .iter()
+            .filter(|handle| {
+                asset_server.is_loaded_with_dependencies(handle.id())
+                    || matches!(asset_server.load_state(handle.id()), LoadState::Failed(_))
+            })
+            .count();
+
+        finished as f32 / self.assets.len() as f32
+    }
+}
+
+/// Layout of the `.meta.ron` files.
+#[derive(Deserialize)]
+struct BlueprintMeta {
+    assets: Vec<(String, BlueprintMetaFile)>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "File")]
+struct BlueprintMetaFile {
+    path: String,
+}
+
+impl AssetLoader for BlueprintPreloadLoader {
+    type Asset = BlueprintPreload;
+    type Settings = ();
+    type Error = BlueprintPreloadLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a Self::Settings,
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let meta = ron::de::from_bytes::<BlueprintMeta>(&bytes)?;
+
+        let blueprint_path = load_context
+            .path()
+            .to_string_lossy()
+            .replace(".meta.ron", ".glb");
+
+        // The meta file lists shared assets multiple times.
+        let mut paths = vec![blueprint_path];
+        for (_, file) in meta.assets {
+            if paths.contains(&file.path) == false {
+                paths.push(file.path);
+            }
+        }
+
+        let assets = paths
+            .into_iter()
+            .map(|path| load_context.load::<Gltf>(path))
+            .collect();
+
+        Ok(BlueprintPreload { assets })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["meta.ron"]
+    }
+}
+
+/// Registered alongside the blenvy loader of the same extension,
+/// the requested asset type decides which one is used.
+#[derive(Default)]
+pub struct BlueprintPreloadLoader;
+
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum BlueprintPreloadLoaderError {
+    #[error("Could not load file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not deserialize ron: {0}")]
+    Serde(#[from] ron::de::SpannedError),
+}
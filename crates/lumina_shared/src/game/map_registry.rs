@@ -0,0 +1,139 @@
+//! Registry of the maps that can be played in a match, loaded from `maps.registry.ron`.
+use bevy::asset::io::Reader;
+use bevy::asset::{ron, AssetLoader, AsyncReadExt, LoadContext};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::arena::GameMap;
+
+pub(super) struct MapRegistryPlugin;
+
+impl Plugin for MapRegistryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<MapRegistryAsset>()
+            .init_asset_loader::<MapRegistryAssetLoader>()
+            .add_systems(PreStartup, load_registry);
+    }
+}
+
+fn load_registry(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let handle = asset_server.load("maps.registry.ron");
+    commands.insert_resource(MapRegistryHandle(handle));
+}
+
+#[derive(bevy::ecs::system::SystemParam)]
+pub struct MapRegistry<'w> {
+    pub assets: Res<'w, Assets<MapRegistryAsset>>,
+    pub handle: Res<'w, MapRegistryHandle>,
+}
+
+impl MapRegistry<'_> {
+    pub fn get(&self) -> Option<&MapRegistryAsset> {
+        self.assets.get(&**self.handle)
+    }
+}
+
+#[derive(Resource, Deref, Debug)]
+pub struct MapRegistryHandle(Handle<MapRegistryAsset>);
+
+#[derive(Asset, TypePath, Serialize, Deserialize, Debug, Clone)]
+pub struct MapRegistryAsset {
+    /// Maps in rotation order.
+    pub maps: Vec<MapInfo>,
+    /// Name of the map played when no map supports the lobby size.
+    pub default: String,
+}
+
+impl MapRegistryAsset {
+    pub fn find(&self, name: &str) -> Option<&MapInfo> {
+        self.maps.iter().find(|map| map.name == name)
+    }
+
+    /// The [`Self::default`] map, or the first map if it is not in the registry.
+    pub fn default_map(&self) -> Option<&MapInfo> {
+        self.find(&self.default).or(self.maps.first())
+    }
+
+    /// Maps that can be played with `team_size` players per team.
+    pub fn supporting(&self, team_size: u8) -> impl Iterator<Item = &MapInfo> {
+        self.maps
+            .iter()
+            .filter(move |map| map.team_sizes.contains(&team_size))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MapInfo {
+    /// Unique display name of the map.
+    pub name: String,
+    /// Number of players per team this map is designed for.
+    pub team_sizes: Vec<u8>,
+    /// Number of objective areas in the map.
+    pub objective_count: usize,
+    pub source: MapSource,
+}
+
+impl MapInfo {
+    pub fn game_map(&self, seed: u32) -> GameMap {
+        match &self.source {
+            MapSource::Blueprint(path) => GameMap::Blueprint { path: path.clone() },
+            MapSource::Procedural => GameMap::Procedural {
+                seed,
+                objective_count: self.objective_count,
+            },
+            MapSource::Terrain(path) => GameMap::Terrain {
+                path: path.clone(),
+                seed,
+                objective_count: self.objective_count,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum MapSource {
+    /// A map exported from Blender (blueprint path).
+    Blueprint(String),
+    /// A procedurally generated arena seeded by the lobby.
+    Procedural,
+    /// A fixed terrain map (`.terrain.ron` asset path),
+    /// only the placement of the objective areas is seeded by the lobby.
+    Terrain(String),
+}
+
+impl AssetLoader for MapRegistryAssetLoader {
+    type Asset = MapRegistryAsset;
+    type Settings = ();
+    type Error = MapRegistryAssetLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a Self::Settings,
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let registry = ron::de::from_bytes(&bytes)?;
+
+        Ok(registry)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["registry.ron"]
+    }
+}
+
+#[derive(Default)]
+pub struct MapRegistryAssetLoader;
+
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum MapRegistryAssetLoaderError {
+    #[error("Could not load file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not deserialize ron: {0}")]
+    Serde(#[from] ron::de::SpannedError),
+}
@@ -0,0 +1,568 @@
+//! Grid based navigation built from the map data of every physics world.
+//!
+//! Procedural arenas are rasterized from their [`TerrainStates`] (one cell per tile)
+//! while static colliders (e.g. walls exported from Blender) are rasterized on top.
+//! Every cell stores its clearance (distance to the closest blocked cell) so that
+//! paths can be queried for agents of any radius.
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use avian2d::prelude::*;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+use lumina_common::prelude::*;
+use lumina_terrain::prelude::*;
+
+use crate::health::Health;
+
+pub(super) struct NavigationPlugin;
+
+impl Plugin for NavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NavGrids>()
+            .init_resource::<DirtyNavWorlds>()
+            .add_systems(
+                PostUpdate,
+                (mark_dirty_worlds, rebuild_nav_grids)
+                    .chain()
+                    .after(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+/// Mark worlds whose map data has changed.
+fn mark_dirty_worlds(
+    q_terrains: Query<&WorldIdx, Changed<TerrainStates>>,
+    q_colliders: Query<(&WorldIdx, &RigidBody), (Added<Collider>, NavObstacleFilter)>,
+    mut dirty: ResMut<DirtyNavWorlds>,
+) {
+    let mut changed = false;
+    let static_colliders = q_colliders
+        .iter()
+        .filter(|(_, rigidbody)| rigidbody.is_static())
+        .map(|(world_id, _)| world_id);
+
+    for &world_id in q_terrains.iter().chain(static_colliders) {
+        dirty.worlds.insert(world_id);
+        changed = true;
+    }
+
+    // Blueprints are spawned over multiple frames,
+    // wait for them to settle before rebuilding.
+    dirty.settled = changed == false;
+}
+
+fn rebuild_nav_grids(
+    q_terrains: Query<(
+        &TerrainStates,
+        &TerrainMapConfig,
+        &GlobalTransform,
+        &WorldIdx,
+    )>,
+    q_colliders: Query<
+        (
+            &Collider,
+            &ColliderAabb,
+            &GlobalTransform,
+            &WorldIdx,
+            &RigidBody,
+        ),
+        NavObstacleFilter,
+    >,
+    mut dirty: ResMut<DirtyNavWorlds>,
+    mut grids: ResMut<NavGrids>,
+) {
+    if dirty.settled == false || dirty.worlds.is_empty() {
+        return;
+    }
+
+    for world_id in dirty.worlds.drain() {
+        let terrain = q_terrains
+            .iter()
+            .find(|(states, .., id)| **id == world_id && states.width() > 0);
+        let colliders = q_colliders
+            .iter()
+            .filter(|(.., id, rigidbody)| **id == world_id && rigidbody.is_static())
+            .collect::<Vec<_>>();
+
+        let mut grid = match terrain {
+            Some((states, config, transform, _)) => {
+                let mut grid = NavGrid::new(
+                    transform.translation().xy(),
+                    config.tile_size,
+                    UVec2::new(states.width() as u32, states.height() as u32),
+                );
+                grid.block_states(states);
+                grid
+            }
+            None => {
+                let Some(aabb) = colliders
+                    .iter()
+                    .map(|(_, aabb, ..)| *aabb)
+                    .reduce(|a, b| a.merged(b))
+                else {
+                    grids.remove(&world_id);
+                    continue;
+                };
+
+                let cell_size = NavGrid::COLLIDER_CELL_SIZE;
+                let size = ((aabb.max - aabb.min) / cell_size).ceil().as_uvec2() + 1;
+                NavGrid::new(aabb.min, cell_size, size)
+            }
+        };
+
+        for (collider, _, transform, ..) in colliders {
+            let (_, rotation, translation) = transform.to_scale_rotation_translation();
+            grid.block_collider(
+                collider,
+                translation.xy(),
+                rotation.to_euler(EulerRot::ZYX).0,
+            );
+        }
+
+        grid.compute_clearance();
+        grids.insert(world_id, grid);
+    }
+}
+
+/// Colliders that agents have to navigate around (only static rigidbodies are used).
+/// Terrain chunks are excluded as they are rasterized from [`TerrainStates`],
+/// destructible bodies (e.g. ores) are excluded as they will eventually be cleared.
+type NavObstacleFilter = (
+    With<RigidBody>,
+    Without<Sensor>,
+    Without<TerrainChunkOf>,
+    Without<Health>,
+);
+
+#[derive(Resource, Default)]
+struct DirtyNavWorlds {
+    worlds: HashSet<WorldIdx>,
+    settled: bool,
+}
+
+/// [`NavGrid`] of every physics world.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct NavGrids(HashMap<WorldIdx, NavGrid>);
+
+/// Query API for bots and guidance ui.
+#[derive(SystemParam)]
+pub struct NavQuery<'w> {
+    pub grids: Res<'w, NavGrids>,
+}
+
+impl NavQuery<'_> {
+    /// Find a path from `start` to `goal` (in world space) that keeps
+    /// at least `radius` distance away from any obstacle.
+    pub fn find_path(
+        &self,
+        world_id: WorldIdx,
+        start: Vec2,
+        goal: Vec2,
+        radius: f32,
+    ) -> Option<NavPath> {
+        self.grids.get(&world_id)?.find_path(start, goal, radius)
+    }
+}
+
+/// A list of waypoints (in world space) from the start to the goal.
+#[derive(Debug, Clone, Deref)]
+pub struct NavPath(Vec<Vec2>);
+
+impl NavPath {
+    /// The first waypoint after the start.
+    pub fn next_waypoint(&self) -> Vec2 {
+        self.0.get(1).copied().unwrap_or(self.0[0])
+    }
+
+    /// Total length of the path.
+    pub fn length(&self) -> f32 {
+        self.0.windows(2).map(|w| w[0].distance(w[1])).sum()
+    }
+
+    /// Follow the path from a position that moved along it, returns the next
+    /// waypoint and the remaining length to the goal.
+    /// [`None`] if the position strayed further than `max_offset` from the path.
+    pub fn follow(&self, position: Vec2, max_offset: f32) -> Option<(Vec2, f32)> {
+        let goal = *self.0.last()?;
+        if self.0.len() == 1 {
+            let dist = position.distance(goal);
+            return (dist <= max_offset).then_some((goal, dist));
+        }
+
+        // Closest segment, prefer later segments on ties.
+        let (index, offset) = self
+            .0
+            .windows(2)
+            .map(|w| {
+                let segment = w[1] - w[0];
+                let t = (position - w[0])
+                    .dot(segment)
+                    .clamp(0.0, segment.length_squared())
+                    / segment.length_squared().max(f32::EPSILON);
+                position.distance(w[0] + segment * t)
+            })
+            .enumerate()
+            .reduce(|a, b| if b.1 <= a.1 { b } else { a })?;
+
+        if offset > max_offset {
+            return None;
+        }
+
+        let waypoint = self.0[index + 1];
+        let remaining = self.0[index + 1..]
+            .windows(2)
+            .map(|w| w[0].distance(w[1]))
+            .sum::<f32>();
+
+        Some((waypoint, position.distance(waypoint) + remaining))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NavGrid {
+    /// World position of the center of cell (0, 0).
+    origin: Vec2,
+    cell_size: f32,
+    blocked: Vec2d<bool>,
+    /// Distance (in world space) from the cell center to the closest obstacle.
+    clearance: Vec2d<f32>,
+}
+
+impl NavGrid {
+    /// Cell size used when the world has no terrain to align with.
+    pub const COLLIDER_CELL_SIZE: f32 = 40.0;
+
+    pub fn new(origin: Vec2, cell_size: f32, size: UVec2) -> Self {
+        let (width, height) = (size.x as usize, size.y as usize);
+
+        Self {
+            origin,
+            cell_size,
+            blocked: Vec2d::new_from_default(width, height),
+            clearance: Vec2d::new(width, height, f32::MAX),
+        }
+    }
+
+    pub fn size(&self) -> UVec2 {
+        UVec2::new(self.blocked.width() as u32, self.blocked.height() as u32)
+    }
+
+    /// Block every filled tile, the grid must have the same size as the states.
+    pub fn block_states(&mut self, states: &Vec2d<bool>) {
+        for (x, y, &state) in states.iter() {
+            if state {
+                self.blocked.set(x, y, true);
+            }
+        }
+    }
+
+    /// Block every cell overlapped by the collider.
+    pub fn block_collider(&mut self, collider: &Collider, translation: Vec2, rotation: f32) {
+        let rotation = Rotation::radians(rotation);
+        let aabb = collider.aabb(translation, rotation);
+        let half_cell = self.cell_size * 0.5;
+
+        let min = self.world_to_cell_unclamped(aabb.min - half_cell);
+        let max = self.world_to_cell_unclamped(aabb.max + half_cell);
+        let size = self.size().as_ivec2();
+
+        for y in min.y.max(0)..=max.y.min(size.y - 1) {
+            for x in min.x.max(0)..=max.x.min(size.x - 1) {
+                let center = self.cell_to_world(UVec2::new(x as u32, y as u32));
+                if collider.distance_to_point(translation, rotation, center, true) < half_cell {
+                    self.blocked.set(x as usize, y as usize, true);
+                }
+            }
+        }
+    }
+
+    /// Compute the clearance of every cell using a chamfer distance transform.
+    /// Cells outside of the grid are considered blocked.
+    pub fn compute_clearance(&mut self) {
+        let (width, height) = (self.blocked.width(), self.blocked.height());
+        const DIAGONAL: f32 = std::f32::consts::SQRT_2;
+
+        // Distance in cells.
+        let mut dist = Vec2d::new(width, height, f32::MAX);
+        for (x, y, &blocked) in self.blocked.iter() {
+            let edge = x.min(y).min(width - 1 - x).min(height - 1 - y) as f32 + 1.0;
+            dist.set(x, y, if blocked { 0.0 } else { edge });
+        }
+
+        let relax = |dist: &mut Vec2d<f32>, x: usize, y: usize, offsets: &[(isize, isize)]| {
+            let mut d = *dist.get(x, y);
+            for &(dx, dy) in offsets {
+                let (Some(nx), Some(ny)) = (x.checked_add_signed(dx), y.checked_add_signed(dy))
+                else {
+                    continue;
+                };
+                if nx >= width || ny >= height {
+                    continue;
+                }
+
+                let cost = if dx != 0 && dy != 0 { DIAGONAL } else { 1.0 };
+                d = d.min(dist.get(nx, ny) + cost);
+            }
+            dist.set(x, y, d);
+        };
+
+        for y in 0..height {
+            for x in 0..width {
+                relax(&mut dist, x, y, &[(-1, 0), (-1, -1), (0, -1), (1, -1)]);
+            }
+        }
+        for y in (0..height).rev() {
+            for x in (0..width).rev() {
+                relax(&mut dist, x, y, &[(1, 0), (1, 1), (0, 1), (-1, 1)]);
+            }
+        }
+
+        // Distance to the edge of the closest blocked cell.
+        for (x, y, clearance) in self.clearance.iter_mut() {
+            *clearance = ((dist.get(x, y) - 0.5) * self.cell_size).max(0.0);
+        }
+    }
+
+    pub fn cell_to_world(&self, cell: UVec2) -> Vec2 {
+        self.origin + cell.as_vec2() * self.cell_size
+    }
+
+    fn world_to_cell_unclamped(&self, position: Vec2) -> IVec2 {
+        ((position - self.origin) / self.cell_size)
+            .round()
+            .as_ivec2()
+    }
+
+    /// Returns [`None`] if the position is outside of the grid.
+    pub fn world_to_cell(&self, position: Vec2) -> Option<UVec2> {
+        let cell = self.world_to_cell_unclamped(position);
+        let size = self.size().as_ivec2();
+
+        (cell.cmpge(IVec2::ZERO).all() && cell.cmplt(size).all()).then(|| cell.as_uvec2())
+    }
+
+    pub fn is_walkable(&self, cell: UVec2, radius: f32) -> bool {
+        let (x, y) = (cell.x as usize, cell.y as usize);
+        *self.blocked.get(x, y) == false && *self.clearance.get(x, y) >= radius
+    }
+
+    /// Closest walkable cell to `position` within a few cells.
+    fn closest_walkable(&self, position: Vec2, radius: f32) -> Option<UVec2> {
+        const SEARCH_RADIUS: i32 = 4;
+
+        let center = self.world_to_cell_unclamped(position);
+        let size = self.size().as_ivec2();
+
+        (-SEARCH_RADIUS..=SEARCH_RADIUS)
+            .flat_map(|y| (-SEARCH_RADIUS..=SEARCH_RADIUS).map(move |x| center + IVec2::new(x, y)))
+            .filter(|cell| cell.cmpge(IVec2::ZERO).all() && cell.cmplt(size).all())
+            .map(|cell| cell.as_uvec2())
+            .filter(|&cell| self.is_walkable(cell, radius))
+            .min_by(|&a, &b| {
+                let a = self.cell_to_world(a).distance_squared(position);
+                let b = self.cell_to_world(b).distance_squared(position);
+                a.total_cmp(&b)
+            })
+    }
+
+    /// A* search over the 8 neighboring cells, followed by line of sight smoothing.
+    pub fn find_path(&self, start: Vec2, goal: Vec2, radius: f32) -> Option<NavPath> {
+        // Costs are in tenth of a cell so that they can be ordered as integers.
+        const STRAIGHT: u32 = 10;
+        const DIAGONAL: u32 = 14;
+
+        let start_cell = self.closest_walkable(start, radius)?;
+        let goal_cell = self.closest_walkable(goal, radius)?;
+
+        let width = self.blocked.width();
+        let index = |cell: UVec2| cell.x as usize + cell.y as usize * width;
+        let heuristic = |cell: UVec2| {
+            let d = (cell.as_ivec2() - goal_cell.as_ivec2()).abs().as_uvec2();
+            STRAIGHT * d.max_element() + (DIAGONAL - STRAIGHT) * d.min_element()
+        };
+
+        let cell_count = width * self.blocked.height();
+        let mut costs = vec![u32::MAX; cell_count];
+        let mut parents = vec![None; cell_count];
+        let mut open = BinaryHeap::new();
+
+        costs[index(start_cell)] = 0;
+        open.push(Reverse((heuristic(start_cell), start_cell.x, start_cell.y)));
+
+        while let Some(Reverse((_, x, y))) = open.pop() {
+            let cell = UVec2::new(x, y);
+            if cell == goal_cell {
+                break;
+            }
+
+            let cost = costs[index(cell)];
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    if dx == 0 && dy == 0 {
+                        continue;
+                    }
+
+                    let Some(next) = self.offset_cell(cell, IVec2::new(dx, dy)) else {
+                        continue;
+                    };
+                    if self.is_walkable(next, radius) == false {
+                        continue;
+                    }
+
+                    let diagonal = dx != 0 && dy != 0;
+                    // Do not cut corners.
+                    if diagonal
+                        && (self.is_walkable(UVec2::new(next.x, cell.y), radius) == false
+                            || self.is_walkable(UVec2::new(cell.x, next.y), radius) == false)
+                    {
+                        continue;
+                    }
+
+                    let next_cost = cost + if diagonal { DIAGONAL } else { STRAIGHT };
+                    if next_cost < costs[index(next)] {
+                        costs[index(next)] = next_cost;
+                        parents[index(next)] = Some(cell);
+                        open.push(Reverse((next_cost + heuristic(next), next.x, next.y)));
+                    }
+                }
+            }
+        }
+
+        if costs[index(goal_cell)] == u32::MAX {
+            return None;
+        }
+
+        let mut cells = vec![goal_cell];
+        while let Some(parent) = parents[index(*cells.last().unwrap())] {
+            cells.push(parent);
+        }
+        cells.reverse();
+
+        // Only keep the cells that are needed to preserve line of sight.
+        let mut waypoints = vec![start];
+        let mut anchor = start;
+        for pair in cells.windows(2) {
+            let next = self.cell_to_world(pair[1]);
+            if self.line_of_sight(anchor, next, radius) == false {
+                anchor = self.cell_to_world(pair[0]);
+                waypoints.push(anchor);
+            }
+        }
+        waypoints.push(goal);
+
+        Some(NavPath(waypoints))
+    }
+
+    /// Returns true if an agent of `radius` can travel in a straight line from `a` to `b`.
+    pub fn line_of_sight(&self, a: Vec2, b: Vec2, radius: f32) -> bool {
+        let step_count = (a.distance(b) / (self.cell_size * 0.5)).ceil().max(1.0) as u32;
+
+        (0..=step_count).all(|step| {
+            let position = a.lerp(b, step as f32 / step_count as f32);
+            self.world_to_cell(position)
+                .is_some_and(|cell| self.is_walkable(cell, radius))
+        })
+    }
+
+    fn offset_cell(&self, cell: UVec2, offset: IVec2) -> Option<UVec2> {
+        let next = cell.as_ivec2() + offset;
+        (next.cmpge(IVec2::ZERO).all() && next.cmplt(self.size().as_ivec2()).all())
+            .then(|| next.as_uvec2())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Rows of characters where `#` is blocked, the first row is at `y = 0`.
+    fn grid_from_rows(rows: &[&str]) -> NavGrid {
+        let size = UVec2::new(rows[0].len() as u32, rows.len() as u32);
+        let mut grid = NavGrid::new(Vec2::ZERO, 1.0, size);
+
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                grid.blocked.set(x, y, c == '#');
+            }
+        }
+
+        grid.compute_clearance();
+        grid
+    }
+
+    #[test]
+    fn path_goes_around_walls() {
+        #[rustfmt::skip]
+        let grid = grid_from_rows(&[
+            "..........",
+            "..........",
+            "....#.....",
+            "....#.....",
+            "....#.....",
+            "..........",
+        ]);
+
+        let start = Vec2::new(1.0, 3.0);
+        let goal = Vec2::new(8.0, 3.0);
+        let path = grid.find_path(start, goal, 0.0).unwrap();
+
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&goal));
+        // Straight line is blocked, so the path must bend.
+        assert!(path.len() > 2);
+        for pair in path.windows(2) {
+            assert!(grid.line_of_sight(pair[0], pair[1], 0.0));
+        }
+    }
+
+    #[test]
+    fn follow_path_until_strayed() {
+        let path = NavPath(vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, 10.0),
+        ]);
+
+        assert_eq!(
+            path.follow(Vec2::new(4.0, 1.0), 2.0),
+            Some((Vec2::new(10.0, 0.0), 37.0f32.sqrt() + 10.0))
+        );
+        assert_eq!(
+            path.follow(Vec2::new(10.0, 4.0), 2.0),
+            Some((Vec2::new(10.0, 10.0), 6.0))
+        );
+        assert_eq!(path.follow(Vec2::new(4.0, 5.0), 2.0), None);
+    }
+
+    #[test]
+    fn radius_blocks_narrow_gaps() {
+        #[rustfmt::skip]
+        let grid = grid_from_rows(&[
+            "....#....",
+            ".........",
+            "....#....",
+        ]);
+
+        let start = Vec2::new(0.0, 1.0);
+        let goal = Vec2::new(8.0, 1.0);
+
+        assert!(grid.find_path(start, goal, 0.4).is_some());
+        assert!(grid.find_path(start, goal, 1.0).is_none());
+    }
+
+    #[test]
+    fn clearance_is_distance_to_closest_obstacle() {
+        #[rustfmt::skip]
+        let grid = grid_from_rows(&[
+            "#....",
+            ".....",
+            ".....",
+        ]);
+
+        assert_eq!(*grid.clearance.get(0, 0), 0.0);
+        assert_eq!(*grid.clearance.get(3, 0), 0.5);
+        assert_eq!(*grid.clearance.get(2, 1), 1.5);
+    }
+}
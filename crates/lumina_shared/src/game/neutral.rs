@@ -0,0 +1,90 @@
+//! Neutral entities placed on maps from Blender (turrets, mines and drones).
+//!
+//! Neutrals do not belong to any team: every player can damage them with
+//! their ammos and destroying them drops lumina. Their behaviours run on the server.
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use lightyear::prelude::*;
+
+use crate::health::Health;
+
+pub(super) struct NeutralPlugin;
+
+impl Plugin for NeutralPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, toggle_destroyed_neutrals);
+    }
+}
+
+/// Hide destroyed neutrals and let everything pass through them.
+fn toggle_destroyed_neutrals(
+    mut commands: Commands,
+    mut q_neutrals: Query<(&Health, &mut Visibility, Entity), (With<Neutral>, Changed<Health>)>,
+) {
+    for (health, mut visibility, entity) in q_neutrals.iter_mut() {
+        if **health <= 0.0 {
+            *visibility = Visibility::Hidden;
+            commands.entity(entity).insert(Sensor);
+        } else {
+            *visibility = Visibility::Inherited;
+            commands.entity(entity).remove::<Sensor>();
+        }
+    }
+}
+
+/// A team-agnostic entity that drops lumina when destroyed.
+///
+/// Requires a [`MaxHealth`](crate::health::MaxHealth), a solid collider and
+/// [`ReplicateFromServer`](crate::blueprints::ReplicateFromServer) with prediction.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Neutral {
+    /// Number of lumina dropped when destroyed.
+    pub lumina_reward: u8,
+    /// Time before it is restored after being destroyed, `0` to never restore it.
+    #[reflect(default)]
+    pub respawn_duration: f32,
+}
+
+/// Fires at the closest spaceship in range using the
+/// [`AmmoStat`](crate::player::prelude::AmmoStat) of the same entity.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct NeutralTurret {
+    pub range: f32,
+    /// Time between each shot.
+    pub fire_interval: f32,
+}
+
+/// Explodes when a spaceship gets close or when it is destroyed,
+/// damaging every spaceship around regardless of their team.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct NeutralMine {
+    pub trigger_radius: f32,
+    pub blast_radius: f32,
+    pub damage: f32,
+}
+
+/// Roams around its spawn position and chases spaceships in range.
+///
+/// Requires a dynamic or kinematic rigidbody.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct NeutralDrone {
+    pub speed: f32,
+    /// Maximum distance from the spawn position while roaming.
+    pub patrol_radius: f32,
+    pub chase_range: f32,
+    /// Distance kept from obstacles while navigating.
+    pub radius: f32,
+}
+
+/// The last shot of a [`NeutralTurret`], replicated so that clients can replay it.
+#[derive(Component, Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq)]
+pub struct NeutralShot {
+    /// Increased on every shot.
+    pub count: u32,
+    pub direction: Vec2,
+}
@@ -0,0 +1,57 @@
+//! Pickups placed on maps from Blender (health packs, energy cells and ammo refills).
+//!
+//! Pickups are collected on the server, the [`PickupCooldown`] is replicated
+//! to the clients and marks the pickup as unavailable until it respawns.
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use lightyear::prelude::*;
+use lumina_common::prelude::*;
+
+use crate::player::GameLayer;
+
+pub(super) struct PickupPlugin;
+
+impl Plugin for PickupPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(AutoTimerPlugin::<PickupRespawn>::default())
+            .observe(setup_pickup_col_layer);
+    }
+}
+
+/// Setup pickup collision layer such that it can only collide with spaceships.
+fn setup_pickup_col_layer(trigger: Trigger<OnAdd, Pickup>, mut commands: Commands) {
+    let entity = trigger.entity();
+    commands.entity(entity).insert((
+        CollisionLayers::new(GameLayer::Pickup, [GameLayer::Spaceship]),
+        CollidingEntities::default(),
+    ));
+}
+
+/// Time until the pickup can be collected again.
+pub type PickupCooldown = AutoTimer<PickupRespawn>;
+
+/// Marker for the [`PickupCooldown`] timer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PickupRespawn;
+
+/// An item that spaceships collect by flying through it.
+///
+/// Requires a sensor collider and
+/// [`ReplicateFromServer`](crate::blueprints::ReplicateFromServer) with prediction.
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct Pickup {
+    pub pickup_type: PickupType,
+    /// Health, energy or number of ammos restored.
+    pub amount: f32,
+    /// Time before it can be collected again after being collected.
+    pub respawn_duration: f32,
+}
+
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickupType {
+    Health,
+    Energy,
+    Ammo,
+}
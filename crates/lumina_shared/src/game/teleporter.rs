@@ -15,12 +15,21 @@ pub type TeleporterEffect = EffectTimer<Teleporter>;
 pub type TeleporterCooldown = CooldownTimer<Teleporter>;
 
 /// The starting point of the teleporter.
+///
+/// A teleporter leads to every [`TeleporterEnd`] and two-way [`TeleporterStart`]
+/// with the same [`Teleporter`] ID, the exit is chosen by the player's aim direction.
 #[derive(Component, Reflect)]
 #[reflect(Component)]
 pub struct TeleporterStart {
     /// How long it stays active until cooldown happens.
     active_duration: f32,
     cooldown_duration: f32,
+    /// Time before the same player can teleport again, `0` to disable.
+    #[reflect(default)]
+    pub player_cooldown: f32,
+    /// Allow the other starts of the teleporter to exit here.
+    #[reflect(default)]
+    pub two_way: bool,
 }
 
 impl CooldownEffectConfig for TeleporterStart {
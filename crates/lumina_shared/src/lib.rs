@@ -11,6 +11,7 @@ pub mod game;
 pub mod health;
 pub mod player;
 pub mod protocol;
+pub mod tuning;
 
 mod type_registry;
 
@@ -35,7 +36,9 @@ impl Plugin for SharedPlugin {
                 game::GamePlugin,
                 health::HealthPlugin,
                 type_registry::TypeRegistryPlugin,
-            ));
+                tuning::TuningPlugin,
+            ))
+            .add_plugins(lumina_terrain::TerrainPlugin);
     }
 }
 
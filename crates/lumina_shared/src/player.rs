@@ -19,7 +19,7 @@ pub mod spawn_point;
 pub mod weapon;
 
 pub mod prelude {
-    pub use super::ammo::{AmmoEffect, AmmoHit, AmmoLifetime, AmmoStat, FireAmmo};
+    pub use super::ammo::{AmmoEffect, AmmoHit, AmmoLifetime, AmmoStat, AmmoTuning, FireAmmo};
     pub use super::objective::{CollectedLumina, LuminaCollected, LuminaStat, ObjectiveArea};
     pub use super::spaceship::ability::{
         AbilityActive, AbilityConfig, AbilityCooldownTimer, AbilityEffectTimer, CancelAbility,
@@ -32,7 +32,7 @@ pub mod prelude {
     pub use super::spawn_point::{
         SpawnPoint, SpawnPointEntity, SpawnPointParent, SpawnPointUsed, TeamType,
     };
-    pub use super::weapon::{Weapon, WeaponMagazine, WeaponRecharge, WeaponReload};
+    pub use super::weapon::{Weapon, WeaponMagazine, WeaponRecharge, WeaponReload, WeaponTuning};
     pub use super::{PlayerInfoType, PlayerInfos};
 }
 
@@ -133,4 +133,6 @@ pub enum GameLayer {
     Spaceship,
     Ammo,
     Lumina,
+    Pickup,
+    Terrain,
 }
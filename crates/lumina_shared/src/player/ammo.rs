@@ -4,6 +4,7 @@ use bevy_transform_interpolation::*;
 use blenvy::*;
 use lightyear::prelude::*;
 use lumina_common::prelude::*;
+use lumina_terrain::prelude::*;
 use strum::IntoEnumIterator;
 
 use crate::blueprints::AmmoType;
@@ -45,7 +46,7 @@ fn setup_ammmo_ref(
 fn fire_ammo(
     trigger: Trigger<FireAmmo>,
     mut commands: Commands,
-    q_weapons: Query<(&AmmoStat, &PlayerId, &TeamType, &WorldIdx)>,
+    q_weapons: Query<(&AmmoStat, Option<&PlayerId>, Option<&TeamType>, &WorldIdx)>,
     q_ammo_refs: Query<&Collider, With<AmmoRef>>,
     mut ammo_pools: ResMut<EntityPools<AmmoType>>,
     ammo_refs: Res<RefEntityMap<AmmoType>>,
@@ -63,8 +64,8 @@ fn fire_ammo(
             ammo_type,
             ..
         },
-        &player_id,
-        &team_type,
+        player_id,
+        team_type,
         &world_id,
     )) = q_weapons.get(weapon_entity)
     else {
@@ -88,9 +89,14 @@ fn fire_ammo(
     });
 
     // Initialize fire ammo components.
-    commands.entity(ammo_entity).insert(FireAmmoBundle {
-        player_id,
-        team_type,
+    let mut ammo_cmd = commands.entity(ammo_entity);
+    match (player_id, team_type) {
+        (Some(&player_id), Some(&team_type)) => ammo_cmd.insert((player_id, team_type)),
+        // Fired by a neutral entity, pooled ammos might still have an owner.
+        _ => ammo_cmd.remove::<(PlayerId, TeamType)>(),
+    };
+
+    ammo_cmd.insert(FireAmmoBundle {
         world_id,
         position: position.into(),
         rotation: Rotation::radians(direction.to_angle()),
@@ -151,8 +157,8 @@ fn ammo_collision(
             &mut AmmoLifetime,
             Ref<CollidingEntities>,
             &Visibility,
-            &PlayerId,
-            &TeamType,
+            Option<&PlayerId>,
+            Option<&TeamType>,
         ),
         (
             Changed<CollidingEntities>,
@@ -161,7 +167,9 @@ fn ammo_collision(
         ),
     >,
     q_rigidbodies: Query<&RigidBody>,
-    // network_identity: NetworkIdentity,
+    q_chunks: Query<&TerrainChunkOf>,
+    mut evw_damage_tile: EventWriter<DamageTile>,
+    network_identity: NetworkIdentity,
 ) {
     for (position, rotation, weapon_ref, mut lifetime, colliding, viz, id, team_type) in
         q_ammos.iter_mut()
@@ -186,25 +194,39 @@ fn ammo_collision(
         let mut hit_id = None;
 
         for &entity in colliding.iter() {
-            // Ignore if we are colliding with entity that
+            // Ignore if we are colliding with the weapon itself, entity that
             // has similar player id or has Sensor component.
-            if q_col_criteria
-                .get(entity)
-                .is_ok_and(|(col_id, has_sensor)| {
-                    col_id.is_some_and(|col_id| col_id == id) || has_sensor
-                })
+            if entity == weapon_ref.0
+                || q_col_criteria
+                    .get(entity)
+                    .is_ok_and(|(col_id, has_sensor)| {
+                        col_id.is_some_and(|col_id| Some(col_id) == id) || has_sensor
+                    })
             {
                 continue;
             }
 
-            // Apply damage if not in the same team or there is no team.
+            // Apply damage if not in the same team, entities without
+            // a team (e.g. ores and neutrals) are damaged by all teams.
             if let Ok((mut health, col_team_type, id)) = q_healths.get_mut(entity) {
-                if col_team_type.is_some_and(|t| t != team_type) || col_team_type.is_none() {
+                if col_team_type != team_type {
                     **health -= effect.damage;
                     hit_id = id.copied();
                 }
             }
 
+            // Damage terrain tiles on the server only,
+            // destroyed tiles are replicated as tile coordinates.
+            if let Ok(chunk_of) = q_chunks.get(entity) {
+                if network_identity.is_server() {
+                    evw_damage_tile.send(DamageTile {
+                        entity: **chunk_of,
+                        position: position.0,
+                        damage: effect.damage,
+                    });
+                }
+            }
+
             // Apply force if possible.
             if q_rigidbodies
                 .get(entity)
@@ -231,8 +253,9 @@ fn ammo_collision(
             lifetime.tick(duration);
             commands.trigger(AmmoHit {
                 position: *position,
-                origin_player_id: *id,
+                origin_player_id: id.copied(),
                 hit_player_id: hit_id,
+                damage: effect.damage,
             });
         }
     }
@@ -257,7 +280,10 @@ impl InitAmmoBundle {
             ammo_type,
             mass_properties: MassPropertiesBundle::new_computed(&collider, 1.0),
             collider,
-            layers: CollisionLayers::new(GameLayer::Ammo, [GameLayer::Spaceship]),
+            layers: CollisionLayers::new(
+                GameLayer::Ammo,
+                [GameLayer::Spaceship, GameLayer::Terrain],
+            ),
             rigidbody: RigidBody::Dynamic,
             sensor: Sensor,
             spatial: SpatialBundle {
@@ -273,8 +299,6 @@ impl InitAmmoBundle {
 
 #[derive(Bundle)]
 pub struct FireAmmoBundle {
-    pub player_id: PlayerId,
-    pub team_type: TeamType,
     pub world_id: WorldIdx,
     pub position: Position,
     pub rotation: Rotation,
@@ -300,9 +324,11 @@ pub struct FireAmmo {
 pub struct AmmoHit {
     /// The position that the ammo hits something.
     pub position: Position,
-    /// The original player that fires the ammo.
-    pub origin_player_id: PlayerId,
+    /// The original player that fires the ammo, [`None`] for neutral entities.
+    pub origin_player_id: Option<PlayerId>,
     pub hit_player_id: Option<PlayerId>,
+    /// The damage carried by the ammo.
+    pub damage: f32,
 }
 
 /// Reference to the weapon entity that fired the ammo.
@@ -324,7 +350,7 @@ pub struct AmmoRef;
 pub struct AmmoLifetime(Timer);
 
 /// Attached to the [`super::Weapon`] entity to store the stat of the weapon.
-#[derive(Component, Reflect)]
+#[derive(Component, Reflect, Clone, PartialEq)]
 #[reflect(Component)]
 pub struct AmmoStat {
     /// The duration the ammo lives.
@@ -340,7 +366,7 @@ pub struct AmmoStat {
 }
 
 /// Ammo effect applied to when it hits a [`Collider`].
-#[derive(Reflect)]
+#[derive(Reflect, Clone, PartialEq)]
 pub struct AmmoEffect {
     damage: f32,
     /// Optional AOE.
@@ -351,7 +377,7 @@ pub struct AmmoEffect {
 }
 
 /// Initial fire ammo physics.
-#[derive(Reflect)]
+#[derive(Reflect, Clone, PartialEq)]
 pub struct AmmoFire {
     /// Initial impulse linear velocity when the ammo is fired.
     linear_impulse: f32,
@@ -364,7 +390,7 @@ pub struct AmmoFire {
 }
 
 /// Knockback effect of an ammo when it hits the target.
-#[derive(Reflect)]
+#[derive(Reflect, Clone, PartialEq)]
 pub struct AmmoKnockback {
     /// Knockback impulse,
     /// used for [`ExternalImpulse::apply_impulse`].
@@ -373,3 +399,47 @@ pub struct AmmoKnockback {
     /// used for [`ExternalImpulse::apply_impulse_at_point`].
     angular_impulse: f32,
 }
+
+impl AmmoStat {
+    /// Apply the overrides on top of this stat.
+    pub fn tuned(&self, tuning: &AmmoTuning) -> Self {
+        Self {
+            lifetime: tuning.lifetime.unwrap_or(self.lifetime),
+            fire: AmmoFire {
+                linear_impulse: tuning.linear_impulse.unwrap_or(self.fire.linear_impulse),
+                angular_impulse: tuning.angular_impulse.unwrap_or(self.fire.angular_impulse),
+                linear_damping: tuning.linear_damping.unwrap_or(self.fire.linear_damping),
+                angular_damping: tuning.angular_damping.unwrap_or(self.fire.angular_damping),
+            },
+            ammo_type: self.ammo_type,
+            effect: AmmoEffect {
+                damage: tuning.damage.unwrap_or(self.effect.damage),
+                radius: tuning.radius.unwrap_or(self.effect.radius),
+                bounce: tuning.bounce.unwrap_or(self.effect.bounce),
+            },
+            knockback: AmmoKnockback {
+                impulse: tuning.knockback_impulse.unwrap_or(self.knockback.impulse),
+                angular_impulse: tuning
+                    .knockback_angular_impulse
+                    .unwrap_or(self.knockback.angular_impulse),
+            },
+        }
+    }
+}
+
+/// Overrides for [`AmmoStat`], fields left as [`None`] keep the blueprint value.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct AmmoTuning {
+    pub lifetime: Option<f32>,
+    pub linear_impulse: Option<f32>,
+    pub angular_impulse: Option<f32>,
+    pub linear_damping: Option<f32>,
+    pub angular_damping: Option<f32>,
+    pub damage: Option<f32>,
+    /// `Some(None)` removes the AOE.
+    pub radius: Option<Option<f32>>,
+    pub bounce: Option<bool>,
+    pub knockback_impulse: Option<f32>,
+    pub knockback_angular_impulse: Option<f32>,
+}
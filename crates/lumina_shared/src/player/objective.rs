@@ -8,6 +8,9 @@ use crate::prelude::LuminaType;
 
 use super::GameLayer;
 
+/// Number of [`ObjectiveArea`]s a map has when it does not specify one.
+pub const DEFAULT_OBJECTIVE_AREA_COUNT: usize = 4;
+
 pub struct ObjectivePlugin;
 
 impl Plugin for ObjectivePlugin {
@@ -23,7 +26,7 @@ fn setup_lumina_col_layer(trigger: Trigger<OnAdd, LuminaType>, mut commands: Com
     let entity = trigger.entity();
     commands.entity(entity).insert(CollisionLayers::new(
         GameLayer::Lumina,
-        [GameLayer::Spaceship, GameLayer::Lumina],
+        [GameLayer::Spaceship, GameLayer::Lumina, GameLayer::Terrain],
     ));
 }
 
@@ -77,4 +80,30 @@ pub struct ObjectiveArea {
     /// [Health]: crate::health::Health
     #[reflect(ignore)]
     pub ores: EntityPool,
+    /// Relative chance of this area being picked as the next objective.
+    #[reflect(default = "ObjectiveArea::default_weight")]
+    pub weight: f32,
+    /// Areas with the highest priority are picked as the first objective of a game.
+    #[reflect(default)]
+    pub priority: u8,
+    /// This area is only picked if it is at least this far from the previous objective.
+    #[reflect(default)]
+    pub min_distance: f32,
+}
+
+impl ObjectiveArea {
+    fn default_weight() -> f32 {
+        1.0
+    }
+}
+
+impl Default for ObjectiveArea {
+    fn default() -> Self {
+        Self {
+            ores: EntityPool::default(),
+            weight: Self::default_weight(),
+            priority: 0,
+            min_distance: 0.0,
+        }
+    }
 }
@@ -278,7 +278,7 @@ fn mimic_spaceship_comp<T: Component + Clone>(
 }
 
 // TODO: Implement recoil, add reload.
-#[derive(Reflect, Debug)]
+#[derive(Reflect, Debug, Clone, PartialEq)]
 #[reflect(Component)]
 pub struct Weapon {
     /// Interval in seconds between each fire.
@@ -302,6 +302,32 @@ impl Weapon {
     pub fn reload_duration(&self) -> f32 {
         self.reload_duration
     }
+
+    pub fn firing_rate(&self) -> f32 {
+        self.firing_rate
+    }
+
+    /// Apply the overrides on top of this weapon.
+    pub fn tuned(&self, tuning: &WeaponTuning) -> Self {
+        Self {
+            firing_rate: tuning.firing_rate.unwrap_or(self.firing_rate),
+            magazine_size: tuning.magazine_size.unwrap_or(self.magazine_size),
+            recoil: tuning.recoil.unwrap_or(self.recoil),
+            fire_radius: tuning.fire_radius.unwrap_or(self.fire_radius),
+            reload_duration: tuning.reload_duration.unwrap_or(self.reload_duration),
+        }
+    }
+}
+
+/// Overrides for [`Weapon`], fields left as [`None`] keep the blueprint value.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct WeaponTuning {
+    pub firing_rate: Option<f32>,
+    pub magazine_size: Option<u32>,
+    pub recoil: Option<f32>,
+    pub fire_radius: Option<f32>,
+    pub reload_duration: Option<f32>,
 }
 
 impl Component for Weapon {
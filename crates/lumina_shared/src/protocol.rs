@@ -12,6 +12,7 @@ use crate::game::prelude::*;
 use crate::health::{Health, MaxHealth};
 use crate::player::objective::CollectedLumina;
 use crate::player::prelude::*;
+use crate::tuning::TuningUpdate;
 
 pub const INPUT_REPLICATION_GROUP: ReplicationGroup = ReplicationGroup::new_id(1);
 
@@ -25,22 +26,36 @@ impl Plugin for ProtocolPlugin {
             mode: ChannelMode::OrderedReliable(ReliableSettings::default()),
             ..default()
         });
+        app.add_channel::<SeqUnreliableChannel>(ChannelSettings {
+            mode: ChannelMode::SequencedUnreliable,
+            ..default()
+        });
 
         // Messages
         // ==============================
         app.register_message::<EnterSandbox>(ChannelDirection::Bidirectional);
         app.register_message::<Matchmake>(ChannelDirection::ClientToServer);
+        app.register_message::<Spectate>(ChannelDirection::ClientToServer);
         app.register_message::<ExitLobby>(ChannelDirection::ClientToServer);
         app.register_message::<LobbyUpdate>(ChannelDirection::ServerToClient);
         app.register_message::<LobbyData>(ChannelDirection::ServerToClient);
+        app.register_message::<MapVoteStart>(ChannelDirection::ServerToClient);
+        app.register_message::<MapVote>(ChannelDirection::ClientToServer);
+        app.register_message::<PreloadMap>(ChannelDirection::ServerToClient);
+        app.register_message::<MapLoaded>(ChannelDirection::ClientToServer);
         app.register_message::<StartGame>(ChannelDirection::ServerToClient);
         app.register_message::<EndGame>(ChannelDirection::ServerToClient);
         app.register_message::<GameScore>(ChannelDirection::ServerToClient);
-        app.register_message::<ObjectivePosition>(ChannelDirection::ServerToClient);
+        app.register_message::<ObjectiveAnnouncement>(ChannelDirection::ServerToClient);
         app.register_message::<KilledPlayer>(ChannelDirection::ServerToClient);
+        app.register_message::<KilledBy>(ChannelDirection::ServerToClient);
+        app.register_message::<ServerShuttingDown>(ChannelDirection::ServerToClient);
+        app.register_message::<TerrainDestruction>(ChannelDirection::ServerToClient);
+        app.register_message::<TuningUpdate>(ChannelDirection::ServerToClient);
         app.register_message::<DepositLumina>(ChannelDirection::ClientToServer);
         app.register_message::<SelectSpaceship>(ChannelDirection::ClientToServer);
         app.register_message::<Teleport>(ChannelDirection::ClientToServer);
+        app.register_message::<CameraView>(ChannelDirection::ClientToServer);
 
         // ==============================
         // Input
@@ -84,6 +99,12 @@ impl Plugin for ProtocolPlugin {
         app.register_component::<TeleporterCooldown>(ChannelDirection::ServerToClient)
             .add_prediction(ComponentSyncMode::Simple);
 
+        app.register_component::<PickupCooldown>(ChannelDirection::ServerToClient)
+            .add_prediction(ComponentSyncMode::Simple);
+
+        app.register_component::<NeutralShot>(ChannelDirection::ServerToClient)
+            .add_prediction(ComponentSyncMode::Simple);
+
         app.register_component::<Playback>(ChannelDirection::ServerToClient)
             .add_prediction(ComponentSyncMode::Simple)
             .add_linear_correction_fn();
@@ -179,6 +200,16 @@ pub struct EnterSandbox;
 #[derive(Serialize, Deserialize, Debug, Deref, DerefMut, Clone, Copy, PartialEq)]
 pub struct Matchmake(pub u8);
 
+/// Spectate command sent from client to server to watch a lobby without a spaceship.
+///
+/// The server answers with [`LobbyData`] once a lobby to watch is found
+/// and with [`StartGame`] if the game is already in progress.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Spectate {
+    /// Room of the lobby to watch, or the first lobby in game if [`None`].
+    pub room_id: Option<RoomId>,
+}
+
 /// Update on lobby status sent from server to client.
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 pub struct LobbyUpdate {
@@ -195,9 +226,47 @@ pub struct LobbyData {
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 pub struct ExitLobby;
 
-/// Start game command sent from server to client when the lobby room is full.
-#[derive(Event, Serialize, Deserialize, Debug, Clone, Copy)]
-pub struct StartGame;
+/// Sent from server to clients when the lobby room is full
+/// to vote between the next maps of the rotation.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MapVoteStart {
+    /// Names of the maps in the map registry.
+    pub maps: Vec<String>,
+    /// Time (in seconds) left to vote.
+    pub duration: f32,
+}
+
+/// Vote for a map of the [`MapVoteStart`], sent from client to server.
+/// Voting again replaces the previous vote.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MapVote {
+    /// Name of the map in the map registry.
+    pub map_name: String,
+}
+
+/// Sent from server to clients once the map is selected so that
+/// they can load the map assets during the countdown.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PreloadMap {
+    /// Name of the map in the map registry.
+    pub map_name: String,
+    /// The map to be played.
+    pub map: GameMap,
+}
+
+/// Sent from client to server once the [`PreloadMap`] assets are loaded.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct MapLoaded;
+
+/// Start game command sent from server to client when the lobby room is full
+/// and every client loaded the map (or the load timeout is reached).
+#[derive(Event, Serialize, Deserialize, Debug, Clone)]
+pub struct StartGame {
+    /// Name of the map in the map registry.
+    pub map_name: String,
+    /// The map to be played.
+    pub map: GameMap,
+}
 
 /// End game command sent from server to client either when 1 team wins or timer runs out.
 #[derive(Event, Serialize, Deserialize, Debug, Clone, Copy)]
@@ -216,8 +285,23 @@ pub struct Teleport {
     pub teleporter: Teleporter,
 }
 
+/// Visible area of the game camera sent from client to server
+/// so that interest management follows what the client can see.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct CameraView {
+    /// World position of the camera.
+    pub center: Vec2,
+    /// Half extents of the area seen by the camera (zoom included).
+    pub half_extents: Vec2,
+}
+
+/// Sent from server to clients when the next objective area is picked.
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
-pub struct ObjectivePosition(pub Vec2);
+pub struct ObjectiveAnnouncement {
+    pub position: Vec2,
+    /// Seconds until the ores of the area respawn.
+    pub countdown: f32,
+}
 
 /// Sent from server to a specific client when that client killed an enemy.
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
@@ -226,6 +310,32 @@ pub struct KilledPlayer {
     pub streak_count: u8,
 }
 
+/// Sent from server to a specific client when the spaceship of that client is destroyed.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct KilledBy {
+    /// The player that dealt the last damage, [`None`] for neutral entities and hazards.
+    pub killer_id: Option<PlayerId>,
+}
+
+/// Sent from server to clients when the server starts draining for a shutdown.
+/// No new lobbies can be joined, in-progress games will be ended after `remaining` seconds.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct ServerShuttingDown {
+    pub remaining: f32,
+}
+
+/// Sent from server to clients in a lobby when terrain tiles are destroyed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TerrainDestruction {
+    /// Tile coordinates of the destroyed tiles.
+    pub tiles: Vec<[u16; 2]>,
+}
+
 /// A [`ChannelMode::OrderedReliable`] channel with a priority of 1.0.
 #[derive(Channel)]
 pub struct OrdReliableChannel;
+
+/// A [`ChannelMode::SequencedUnreliable`] channel for frequent updates
+/// where only the latest message matters.
+#[derive(Channel)]
+pub struct SeqUnreliableChannel;
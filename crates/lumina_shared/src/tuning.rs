@@ -0,0 +1,297 @@
+//! Gameplay tuning overrides applied on top of the values exported from Blender.
+//!
+//! The server loads the overrides from `gameplay.tuning.ron` (hot reloaded with the
+//! `dev_native` feature) and pushes them to every client with [`TuningUpdate`].
+//! Both sides then patch the live [`Spaceship`], [`Weapon`] and [`AmmoStat`] components.
+use std::collections::HashMap;
+
+use bevy::asset::io::Reader;
+use bevy::asset::{ron, AssetLoader, AsyncReadExt, LoadContext};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::blueprints::{SpaceshipType, WeaponType};
+use crate::player::prelude::*;
+use crate::player::spaceship::movement::{
+    BoostConfig, BrakeConfig, DashConfig, EnergyConfig, MovementConfig,
+};
+
+pub(super) struct TuningPlugin;
+
+impl Plugin for TuningPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<TuningAsset>()
+            .init_asset_loader::<TuningAssetLoader>()
+            .init_resource::<ActiveTuning>()
+            .add_systems(
+                PreUpdate,
+                (
+                    tune_component::<Spaceship>,
+                    tune_component::<AmmoStat>,
+                    (tune_component::<Weapon>, sync_weapon).chain(),
+                ),
+            );
+    }
+}
+
+/// Patch newly added components, or every component when the tuning changes.
+fn tune_component<T: Tunable>(
+    mut commands: Commands,
+    mut q_components: Query<(&mut T, &T::Key, Option<&Untuned<T>>, Entity)>,
+    tuning: Res<ActiveTuning>,
+) {
+    for (mut component, &key, untuned, entity) in q_components.iter_mut() {
+        if tuning.is_changed() == false && component.is_added() == false {
+            continue;
+        }
+
+        // Keep the blueprint value so that removed overrides can be reverted.
+        let base = match untuned {
+            Some(untuned) => untuned.0.clone(),
+            None => {
+                commands.entity(entity).insert(Untuned(component.clone()));
+                component.clone()
+            }
+        };
+
+        let tuned = match T::tuning(&tuning, key) {
+            Some(overrides) => base.tuned(overrides),
+            None => base,
+        };
+        component.set_if_neq(tuned);
+    }
+}
+
+/// The recharge timer and magazine are created from the untuned weapon when it is added.
+/// Newly added weapons start with a full tuned magazine, live ones keep
+/// their remaining ammo within the tuned magazine size.
+fn sync_weapon(
+    mut q_weapons: Query<(Ref<Weapon>, &mut WeaponRecharge, &mut WeaponMagazine), Changed<Weapon>>,
+) {
+    for (weapon, mut recharge, mut magazine) in q_weapons.iter_mut() {
+        recharge.set_duration(std::time::Duration::from_secs_f32(weapon.firing_rate()));
+
+        let ammo = match weapon.is_added() {
+            true => weapon.magazine_size(),
+            false => magazine.min(weapon.magazine_size()),
+        };
+        magazine.set_if_neq(WeaponMagazine(ammo));
+    }
+}
+
+/// A component that can be patched by the [`ActiveTuning`].
+pub trait Tunable: Component + Clone + PartialEq {
+    /// Component identifying which overrides to apply.
+    type Key: Component + Copy;
+    type Tuning;
+
+    fn tuning(tuning: &TuningAsset, key: Self::Key) -> Option<&Self::Tuning>;
+
+    fn tuned(&self, tuning: &Self::Tuning) -> Self;
+}
+
+impl Tunable for Spaceship {
+    type Key = SpaceshipType;
+    type Tuning = SpaceshipTuning;
+
+    fn tuning(tuning: &TuningAsset, key: Self::Key) -> Option<&Self::Tuning> {
+        tuning.spaceships.get(&key)
+    }
+
+    fn tuned(&self, tuning: &Self::Tuning) -> Self {
+        Self {
+            movement: tuning.movement.unwrap_or(self.movement),
+            brake: tuning.brake.unwrap_or(self.brake),
+            boost: tuning.boost.unwrap_or(self.boost),
+            dash: tuning.dash.unwrap_or(self.dash),
+            energy: tuning.energy.unwrap_or(self.energy),
+        }
+    }
+}
+
+impl Tunable for Weapon {
+    type Key = WeaponType;
+    type Tuning = WeaponTuning;
+
+    fn tuning(tuning: &TuningAsset, key: Self::Key) -> Option<&Self::Tuning> {
+        tuning.weapons.get(&key)
+    }
+
+    fn tuned(&self, tuning: &Self::Tuning) -> Self {
+        Weapon::tuned(self, tuning)
+    }
+}
+
+impl Tunable for AmmoStat {
+    type Key = WeaponType;
+    type Tuning = AmmoTuning;
+
+    fn tuning(tuning: &TuningAsset, key: Self::Key) -> Option<&Self::Tuning> {
+        tuning.ammos.get(&key)
+    }
+
+    fn tuned(&self, tuning: &Self::Tuning) -> Self {
+        AmmoStat::tuned(self, tuning)
+    }
+}
+
+/// The value of a component before any tuning is applied.
+#[derive(Component)]
+pub struct Untuned<T>(pub T);
+
+/// The tuning that is currently applied.
+/// Set by the server from the asset and by the client from [`TuningUpdate`].
+#[derive(Resource, Default, Deref, DerefMut, Debug)]
+pub struct ActiveTuning(pub TuningAsset);
+
+/// Sent from server to clients when the tuning changes or when a client connects.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TuningUpdate(pub TuningAsset);
+
+#[derive(Asset, TypePath, Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct TuningAsset {
+    pub spaceships: HashMap<SpaceshipType, SpaceshipTuning>,
+    pub weapons: HashMap<WeaponType, WeaponTuning>,
+    /// Stats of the ammo fired by each weapon.
+    pub ammos: HashMap<WeaponType, AmmoTuning>,
+}
+
+/// Overrides for [`Spaceship`], configs left as [`None`] keep the blueprint value.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct SpaceshipTuning {
+    pub movement: Option<MovementConfig>,
+    pub brake: Option<BrakeConfig>,
+    pub boost: Option<BoostConfig>,
+    pub dash: Option<DashConfig>,
+    pub energy: Option<EnergyConfig>,
+}
+
+impl AssetLoader for TuningAssetLoader {
+    type Asset = TuningAsset;
+    type Settings = ();
+    type Error = TuningAssetLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a Self::Settings,
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let tuning = ron::de::from_bytes(&bytes)?;
+
+        Ok(tuning)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tuning.ron"]
+    }
+}
+
+#[derive(Default)]
+pub struct TuningAssetLoader;
+
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum TuningAssetLoaderError {
+    #[error("Could not load file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not deserialize ron: {0}")]
+    Serde(#[from] ron::de::SpannedError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tuned by the weapon firing rate.
+    #[derive(Component, Debug, Clone, PartialEq)]
+    struct Stat(f32);
+
+    impl Tunable for Stat {
+        type Key = WeaponType;
+        type Tuning = WeaponTuning;
+
+        fn tuning(tuning: &TuningAsset, key: Self::Key) -> Option<&Self::Tuning> {
+            tuning.weapons.get(&key)
+        }
+
+        fn tuned(&self, tuning: &Self::Tuning) -> Self {
+            Self(tuning.firing_rate.unwrap_or(self.0))
+        }
+    }
+
+    fn set_firing_rate(app: &mut App, firing_rate: Option<f32>) {
+        let mut tuning = app.world_mut().resource_mut::<ActiveTuning>();
+        tuning.weapons.clear();
+        if let Some(firing_rate) = firing_rate {
+            tuning.weapons.insert(
+                WeaponType::Cannon,
+                WeaponTuning {
+                    firing_rate: Some(firing_rate),
+                    ..default()
+                },
+            );
+        }
+    }
+
+    fn stat(app: &App, entity: Entity) -> f32 {
+        app.world().get::<Stat>(entity).unwrap().0
+    }
+
+    #[test]
+    fn tune_and_revert_through_untuned() {
+        let mut app = App::new();
+        app.init_resource::<ActiveTuning>()
+            .add_systems(Update, tune_component::<Stat>);
+
+        let cannon = app.world_mut().spawn((Stat(1.0), WeaponType::Cannon)).id();
+        let gattling = app
+            .world_mut()
+            .spawn((Stat(1.0), WeaponType::GattlingGun))
+            .id();
+        app.update();
+        assert_eq!(stat(&app, cannon), 1.0);
+        assert_eq!(
+            app.world().get::<Untuned<Stat>>(cannon).unwrap().0,
+            Stat(1.0)
+        );
+
+        set_firing_rate(&mut app, Some(2.0));
+        app.update();
+        assert_eq!(stat(&app, cannon), 2.0);
+        assert_eq!(stat(&app, gattling), 1.0);
+
+        // Overrides are applied on the blueprint value, not on top of each other.
+        set_firing_rate(&mut app, Some(3.0));
+        app.update();
+        assert_eq!(stat(&app, cannon), 3.0);
+
+        // Removed overrides revert to the blueprint value.
+        set_firing_rate(&mut app, None);
+        app.update();
+        assert_eq!(stat(&app, cannon), 1.0);
+    }
+
+    #[test]
+    fn added_components_are_tuned() {
+        let mut app = App::new();
+        app.init_resource::<ActiveTuning>()
+            .add_systems(Update, tune_component::<Stat>);
+        set_firing_rate(&mut app, Some(2.0));
+        app.update();
+
+        let cannon = app.world_mut().spawn((Stat(1.0), WeaponType::Cannon)).id();
+        app.update();
+        assert_eq!(stat(&app, cannon), 2.0);
+        assert_eq!(
+            app.world().get::<Untuned<Stat>>(cannon).unwrap().0,
+            Stat(1.0)
+        );
+    }
+}
@@ -38,6 +38,13 @@ impl Plugin for TypeRegistryPlugin {
             .register_type::<Teleporter>()
             .register_type::<Animator>()
             .register_type::<Playback>()
+            .register_type::<AnimationHazard>()
+            .register_type::<AnimatorSwitch>()
+            .register_type::<Neutral>()
+            .register_type::<NeutralTurret>()
+            .register_type::<NeutralMine>()
+            .register_type::<NeutralDrone>()
+            .register_type::<Pickup>()
             // Player
             .register_type::<Spaceship>()
             .register_type::<SpaceshipType>() // Needed for ui.
@@ -0,0 +1,215 @@
+//! Compare the per tile terrain (one entity per tile, one collider per border tile)
+//! against the chunked terrain (greedy merged colliders and batched meshes)
+//! using the default `terrain_config.ron`.
+//!
+//! Run with `cargo bench -p lumina_terrain`.
+use std::time::{Duration, Instant};
+
+use avian2d::prelude::*;
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy::sprite::Mesh2dHandle;
+use bevy::time::TimeUpdateStrategy;
+use lumina_common::prelude::*;
+use lumina_terrain::config::TerrainConfigAsset;
+use lumina_terrain::prelude::*;
+use lumina_terrain::{GenerateTerrain, TerrainType};
+
+const SEEDS: [u32; 4] = [1, 42, 1337, 98765];
+const WARMUP_STEPS: usize = 10;
+const MEASURED_STEPS: usize = 200;
+/// Dynamic bodies roaming the map so that the physics step has something to resolve.
+const BODY_COUNT: usize = 64;
+
+fn main() {
+    let config = load_config();
+
+    println!(
+        "Terrain {}x{} (chunk size: {}), {BODY_COUNT} dynamic bodies, {MEASURED_STEPS} steps",
+        config.size.x, config.size.y, config.chunk_size
+    );
+    println!(
+        "{:>8} {:>10} {:>10} {:>10} {:>14}",
+        "seed", "mode", "meshes", "colliders", "step (us)"
+    );
+
+    for seed in SEEDS {
+        for mode in [Mode::PerTile, Mode::Chunked] {
+            let result = run(&config, seed, mode);
+
+            println!(
+                "{seed:>8} {:>10} {:>10} {:>10} {:>14.1}",
+                mode.name(),
+                result.mesh_count,
+                result.collider_count,
+                result.step_time.as_secs_f64() * 1e6,
+            );
+        }
+    }
+}
+
+fn load_config() -> TerrainConfigAsset {
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../assets/terrain_config.ron"
+    );
+    let bytes = std::fs::read(path).expect("Unable to read terrain_config.ron");
+
+    bevy::asset::ron::de::from_bytes(&bytes).expect("Unable to deserialize terrain_config.ron")
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Mode {
+    /// The previous implementation: one mesh entity per filled tile
+    /// and one collider per tile with less than 4 filled neighbors.
+    PerTile,
+    /// [`Terrain::generate_terrain`].
+    Chunked,
+}
+
+impl Mode {
+    fn name(&self) -> &'static str {
+        match self {
+            Mode::PerTile => "per tile",
+            Mode::Chunked => "chunked",
+        }
+    }
+}
+
+#[derive(Component)]
+struct BenchBody;
+
+struct BenchResult {
+    mesh_count: usize,
+    collider_count: usize,
+    step_time: Duration,
+}
+
+fn run(config: &TerrainConfigAsset, seed: u32, mode: Mode) -> BenchResult {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        TransformPlugin,
+        HierarchyPlugin,
+        AssetPlugin::default(),
+        PhysicsPlugins::default(),
+    ))
+    .init_asset::<Mesh>()
+    .init_asset::<ColorMaterial>()
+    .init_resource::<EntityPools<TerrainType>>()
+    .insert_resource(Gravity::ZERO)
+    .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+        1.0 / 60.0,
+    )));
+
+    let material = app
+        .world_mut()
+        .resource_mut::<Assets<ColorMaterial>>()
+        .add(Color::WHITE);
+    app.insert_resource(TileRef { material });
+
+    let holder = app.world_mut().spawn_empty().id();
+    let gen = GenerateTerrain {
+        seed,
+        entity: holder,
+        layers: CollisionLayers::default(),
+        world_id: WorldIdx::default(),
+    };
+
+    let states = TerrainStates::new_map(config, &gen);
+    match mode {
+        Mode::PerTile => spawn_per_tile(app.world_mut(), config, &states),
+        Mode::Chunked => {
+            let config = config.clone();
+            app.world_mut()
+                .run_system_once(move |mut terrain: Terrain| {
+                    terrain.generate_terrain(holder, &config, &gen);
+                });
+        }
+    }
+    spawn_bodies(app.world_mut(), config, &states, seed);
+
+    for _ in 0..WARMUP_STEPS {
+        app.update();
+    }
+
+    let start = Instant::now();
+    for _ in 0..MEASURED_STEPS {
+        app.update();
+    }
+    let step_time = start.elapsed() / MEASURED_STEPS as u32;
+
+    let world = app.world_mut();
+    let mesh_count = world
+        .query_filtered::<(), With<Mesh2dHandle>>()
+        .iter(world)
+        .count();
+    let collider_count = world
+        .query_filtered::<(), (With<Collider>, Without<BenchBody>)>()
+        .iter(world)
+        .count();
+
+    BenchResult {
+        mesh_count,
+        collider_count,
+        step_time,
+    }
+}
+
+fn spawn_per_tile(world: &mut World, config: &TerrainConfigAsset, states: &TerrainStates) {
+    let mesh = world
+        .resource_mut::<Assets<Mesh>>()
+        .add(Rectangle::new(1.0, 1.0));
+    let material = world.resource::<TileRef>().material.clone();
+
+    for (x, y, &state) in states.iter() {
+        if state == false {
+            continue;
+        }
+
+        let position = Vec2::new(x as f32, y as f32) * config.tile_size;
+        let mut entity = world.spawn((
+            ColorMesh2dBundle {
+                mesh: Mesh2dHandle(mesh.clone()),
+                material: material.clone(),
+                transform: Transform::from_translation(position.extend(0.0))
+                    .with_scale(Vec3::splat(config.tile_size)),
+                ..default()
+            },
+            Position(position),
+        ));
+
+        let filled_neighbor_count = states
+            .get_neighbors(x, y)
+            .iter()
+            .filter(|&&s| s.is_some_and(|&s| s) || s.is_none())
+            .count();
+
+        if filled_neighbor_count < 4 {
+            entity.insert((Collider::rectangle(1.0, 1.0), RigidBody::Static));
+        }
+    }
+}
+
+fn spawn_bodies(world: &mut World, config: &TerrainConfigAsset, states: &TerrainStates, seed: u32) {
+    let empty_tiles = states
+        .iter()
+        .filter(|(_, _, &state)| state == false)
+        .map(|(x, y, _)| Vec2::new(x as f32, y as f32) * config.tile_size)
+        .collect::<Vec<_>>();
+
+    let mut rng = XorShift32::new(seed);
+    for _ in 0..BODY_COUNT {
+        let position = empty_tiles[rng.next_u32() as usize % empty_tiles.len()];
+        let direction = Vec2::from_angle(rng.next_u32() as f32);
+
+        world.spawn((
+            RigidBody::Dynamic,
+            Collider::circle(config.tile_size * 0.4),
+            Position(position),
+            LinearVelocity(direction * config.tile_size * 10.0),
+            Restitution::new(1.0),
+            BenchBody,
+        ));
+    }
+}
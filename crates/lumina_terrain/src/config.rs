@@ -1,9 +1,12 @@
 use bevy::asset::io::Reader;
 use bevy::asset::{ron, AssetLoader, AsyncReadExt, LoadContext};
 use bevy::prelude::*;
+use lumina_common::prelude::*;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::pass::TerrainPass;
+
 pub struct TerrainConfigPlugin;
 
 impl Plugin for TerrainConfigPlugin {
@@ -35,13 +38,16 @@ impl TerrainConfig<'_> {
 pub struct TerrainHandle(Handle<TerrainConfigAsset>);
 
 /// Configuration to generate the terrain procedurally.
-#[derive(Asset, TypePath, Deserialize, Serialize, Debug)]
+#[derive(Asset, TypePath, Deserialize, Serialize, Debug, Clone)]
 pub struct TerrainConfigAsset {
     /// The size of the terrain (in tile number).
     pub size: UVec2,
     /// Size of a single tile.
     /// This will not interfere with the procedural algorithm in any way.
     pub tile_size: f32,
+    /// Size of a chunk (in tile number).
+    /// Tiles in a chunk are batched into a single mesh and collider.
+    pub chunk_size: u32,
     /// Width of noise surrounding the map.
     pub noise_surr_width: u32,
     /// Size of the spawn point base.
@@ -52,6 +58,62 @@ pub struct TerrainConfigAsset {
     pub noise_threshold: f32,
     /// The power of gradient from the edges.
     pub gradient_pow: f32,
+    /// How the map is mirrored so that both bases are equivalent.
+    pub symmetry: TerrainSymmetry,
+    /// Maximum number of generation attempts before
+    /// a corridor is carved to connect the bases.
+    pub max_attempts: u32,
+    /// Passes applied in order after the border noise is generated.
+    #[serde(default)]
+    pub passes: Vec<TerrainPass>,
+    /// Health of a single tile, tiles are indestructible if [`None`].
+    #[serde(default)]
+    pub tile_health: Option<f32>,
+}
+
+#[derive(Deserialize, Serialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerrainSymmetry {
+    /// No symmetry, bases are still placed at opposite ends.
+    #[default]
+    None,
+    /// Mirror the left half onto the right half.
+    Axis,
+    /// Rotate the left half by 180 degrees onto the right half.
+    Point,
+}
+
+impl TerrainSymmetry {
+    /// Mirror a tile into the other half of a map of `size`.
+    /// [`TerrainSymmetry::None`] mirrors along the vertical axis.
+    pub fn mirror(&self, tile: UVec2, size: UVec2) -> UVec2 {
+        let max = size - 1;
+
+        match self {
+            TerrainSymmetry::None | TerrainSymmetry::Axis => UVec2::new(max.x - tile.x, tile.y),
+            TerrainSymmetry::Point => max - tile,
+        }
+    }
+
+    /// Overwrite the right half of the map with the mirrored left half.
+    pub fn apply(&self, states: &mut Vec2d<bool>) {
+        if *self == TerrainSymmetry::None {
+            return;
+        }
+
+        let size = UVec2::new(states.width() as u32, states.height() as u32);
+        for y in 0..size.y {
+            for x in 0..size.x.div_ceil(2) {
+                // The center column of an odd width map mirrors onto itself.
+                if x * 2 + 1 == size.x && (*self == TerrainSymmetry::Axis || y * 2 >= size.y) {
+                    continue;
+                }
+
+                let mirrored = self.mirror(UVec2::new(x, y), size);
+                let state = *states.get(x as usize, y as usize);
+                states.set(mirrored.x as usize, mirrored.y as usize, state);
+            }
+        }
+    }
 }
 
 impl AssetLoader for TerrainConfigAssetLoader {
@@ -85,3 +147,71 @@ pub enum TerrainConfigAssetLoaderError {
     #[error("Could not deserialize ron: {0}")]
     Serde(#[from] ron::de::SpannedError),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn random_states(width: usize, height: usize, seed: u32) -> Vec2d<bool> {
+        let mut rng = XorShift32::new(seed);
+        let mut states = Vec2d::new_from_default(width, height);
+        for (_, _, state) in states.iter_mut() {
+            *state = rng.next_u32() % 2 == 0;
+        }
+
+        states
+    }
+
+    #[test]
+    fn mirror_odd_width_center() {
+        let size = UVec2::new(7, 5);
+
+        // Center column mirrors onto itself along the axis.
+        assert_eq!(
+            TerrainSymmetry::Axis.mirror(UVec2::new(3, 1), size),
+            UVec2::new(3, 1)
+        );
+        // Only the center tile mirrors onto itself with point symmetry.
+        assert_eq!(
+            TerrainSymmetry::Point.mirror(UVec2::new(3, 2), size),
+            UVec2::new(3, 2)
+        );
+        assert_eq!(
+            TerrainSymmetry::Point.mirror(UVec2::new(3, 1), size),
+            UVec2::new(3, 3)
+        );
+    }
+
+    #[test]
+    fn apply_makes_states_symmetric() {
+        for symmetry in [TerrainSymmetry::Axis, TerrainSymmetry::Point] {
+            for (width, height) in [(7, 5), (8, 5), (7, 6), (8, 6)] {
+                let mut states = random_states(width, height, 3);
+                symmetry.apply(&mut states);
+
+                let size = UVec2::new(width as u32, height as u32);
+                for (x, y, state) in states.iter() {
+                    let mirrored = symmetry.mirror(UVec2::new(x as u32, y as u32), size);
+                    assert_eq!(
+                        *state,
+                        *states.get(mirrored.x as usize, mirrored.y as usize),
+                        "{symmetry:?} ({width}x{height}) at ({x}, {y})"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn apply_keeps_left_half() {
+        let original = random_states(7, 5, 9);
+        let mut states = original.clone();
+        TerrainSymmetry::Point.apply(&mut states);
+
+        for (x, y, state) in original.iter() {
+            if x < 3 {
+                assert_eq!(*state, *states.get(x, y));
+            }
+        }
+    }
+}
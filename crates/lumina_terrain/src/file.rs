@@ -0,0 +1,348 @@
+//! A plain text file format for [`TerrainStates`], so that a generated map can be
+//! hand edited, diffed in git and shipped as a fixed level.
+//!
+//! Tiles are stored as rows of `#` (filled) and `.` (empty) characters, the first row
+//! being the top of the map (highest y) so that the file reads like the map in game.
+
+use std::path::PathBuf;
+
+use avian2d::prelude::*;
+use bevy::asset::io::Reader;
+use bevy::asset::{ron, AssetLoader, AsyncReadExt, LoadContext};
+use bevy::prelude::*;
+use lumina_common::prelude::*;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::config::TerrainConfigAsset;
+use crate::map::{Terrain, TerrainMapConfig, TerrainSeed, TerrainStates};
+
+pub struct TerrainFilePlugin;
+
+impl Plugin for TerrainFilePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<TerrainMapAsset>()
+            .init_asset_loader::<TerrainMapAssetLoader>()
+            .add_event::<LoadTerrain>()
+            .add_event::<ExportTerrain>()
+            .add_systems(Update, (load_terrain, export_terrain));
+    }
+}
+
+fn load_terrain(
+    mut commands: Commands,
+    mut terrain: Terrain,
+    maps: Res<Assets<TerrainMapAsset>>,
+    mut evr_load: EventReader<LoadTerrain>,
+    mut queue: Local<Vec<LoadTerrain>>,
+) {
+    // Use a queuing system so that we don't miss any events if the map asset is not ready yet.
+    queue.extend(evr_load.read().cloned());
+
+    queue.retain(|load| {
+        let Some(map) = maps.get(&load.handle) else {
+            return true;
+        };
+
+        match map.to_states() {
+            Ok(states) => {
+                let config = map.sized_config(&states);
+                terrain.spawn_terrain(load.entity, states, &config, load.layers, load.world_id);
+
+                if let Some(seed) = map.seed {
+                    commands.entity(load.entity).insert(TerrainSeed(seed));
+                }
+            }
+            Err(err) => error!("Unable to load terrain map {:?}: {err}", load.handle.path()),
+        }
+
+        false
+    });
+}
+
+/// Export with the config the terrain was generated with, not the live one.
+fn export_terrain(
+    q_maps: Query<(&TerrainStates, &TerrainMapConfig, Option<&TerrainSeed>)>,
+    mut evr_export: EventReader<ExportTerrain>,
+) {
+    for export in evr_export.read() {
+        let Ok((states, config, seed)) = q_maps.get(export.entity) else {
+            warn!("No terrain to export from {}.", export.entity);
+            continue;
+        };
+
+        let map = TerrainMapAsset::new(states, config, seed.map(|seed| **seed));
+        match map.save(&export.path) {
+            Ok(()) => info!("Exported terrain to {:?}.", export.path),
+            Err(err) => error!("Unable to export terrain to {:?}: {err}", export.path),
+        }
+    }
+}
+
+/// Spawn the terrain from a [`TerrainMapAsset`] instead of generating it.
+/// The terrain is spawned once the asset is loaded.
+#[derive(Event, Debug, Clone)]
+pub struct LoadTerrain {
+    pub handle: Handle<TerrainMapAsset>,
+    /// The entity that is supposed to hold the [`crate::map::TerrainMapBundle`].
+    pub entity: Entity,
+    pub layers: CollisionLayers,
+    pub world_id: WorldIdx,
+}
+
+/// Write the terrain held by an entity into a `.terrain.ron` file.
+#[derive(Event, Debug, Clone)]
+pub struct ExportTerrain {
+    /// The entity holding the [`crate::map::TerrainMapBundle`].
+    pub entity: Entity,
+    pub path: PathBuf,
+}
+
+/// A terrain map stored as a file.
+#[derive(Asset, TypePath, Deserialize, Serialize, Debug, Clone)]
+pub struct TerrainMapAsset {
+    /// The seed the map was generated from, [`None`] if it was made by hand.
+    pub seed: Option<u32>,
+    /// The config used to generate and spawn the map.
+    /// The size of the map is defined by the rows instead of [`TerrainConfigAsset::size`].
+    pub config: TerrainConfigAsset,
+    /// Rows of tiles from top to bottom, `#` is filled and `.` is empty.
+    pub rows: Vec<String>,
+}
+
+impl TerrainMapAsset {
+    pub const FILLED: char = '#';
+    pub const EMPTY: char = '.';
+
+    pub fn new(states: &TerrainStates, config: &TerrainConfigAsset, seed: Option<u32>) -> Self {
+        let rows = (0..states.height())
+            .rev()
+            .map(|y| {
+                (0..states.width())
+                    .map(|x| match *states.get(x, y) {
+                        true => Self::FILLED,
+                        false => Self::EMPTY,
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut config = config.clone();
+        config.size = UVec2::new(states.width() as u32, states.height() as u32);
+
+        Self { seed, config, rows }
+    }
+
+    pub fn to_states(&self) -> Result<TerrainStates, TerrainMapError> {
+        let height = self.rows.len();
+        let width = self
+            .rows
+            .first()
+            .map(|row| row.chars().count())
+            .unwrap_or(0);
+        if width == 0 || height == 0 {
+            return Err(TerrainMapError::Empty);
+        }
+
+        let mut states = Vec2d::new_from_default(width, height);
+        for (row_idx, row) in self.rows.iter().enumerate() {
+            let y = height - row_idx - 1;
+
+            let row_width = row.chars().count();
+            if row_width != width {
+                return Err(TerrainMapError::RowWidth {
+                    row: row_idx,
+                    width: row_width,
+                    expected: width,
+                });
+            }
+
+            for (x, c) in row.chars().enumerate() {
+                let state = match c {
+                    Self::FILLED => true,
+                    Self::EMPTY => false,
+                    _ => {
+                        return Err(TerrainMapError::InvalidTile {
+                            row: row_idx,
+                            tile: c,
+                        })
+                    }
+                };
+                states.set(x, y, state);
+            }
+        }
+
+        Ok(states.into())
+    }
+
+    /// [`Self::config`] with its size overridden by the size of the `states` (see [`Self::to_states`]).
+    pub fn sized_config(&self, states: &TerrainStates) -> TerrainConfigAsset {
+        let mut config = self.config.clone();
+        config.size = UVec2::new(states.width() as u32, states.height() as u32);
+
+        config
+    }
+
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+
+    pub fn save(&self, path: &std::path::Path) -> Result<(), TerrainMapError> {
+        std::fs::write(path, self.to_ron()?)?;
+        Ok(())
+    }
+}
+
+impl AssetLoader for TerrainMapAssetLoader {
+    type Asset = TerrainMapAsset;
+    type Settings = ();
+    type Error = TerrainMapError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a Self::Settings,
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let map: TerrainMapAsset = ron::de::from_bytes(&bytes)?;
+        // Catch malformed rows early instead of when spawning.
+        map.to_states()?;
+
+        Ok(map)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["terrain.ron"]
+    }
+}
+
+#[derive(Default)]
+pub struct TerrainMapAssetLoader;
+
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum TerrainMapError {
+    #[error("Could not read or write file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not deserialize ron: {0}")]
+    Deserialize(#[from] ron::de::SpannedError),
+    #[error("Could not serialize ron: {0}")]
+    Serialize(#[from] ron::Error),
+    #[error("Terrain map has no tiles.")]
+    Empty,
+    #[error("Row {row} has {width} tiles, expected {expected}.")]
+    RowWidth {
+        row: usize,
+        width: usize,
+        expected: usize,
+    },
+    #[error("Row {row} has an invalid tile '{tile}'.")]
+    InvalidTile { row: usize, tile: char },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> TerrainConfigAsset {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../assets/terrain_config.ron"
+        );
+        let bytes = std::fs::read(path).expect("Unable to read terrain_config.ron");
+
+        ron::de::from_bytes(&bytes).expect("Unable to deserialize terrain_config.ron")
+    }
+
+    #[test]
+    fn round_trip_through_ron() {
+        let map = TerrainMapAsset {
+            seed: Some(42),
+            config: config(),
+            rows: vec!["#..#".into(), "....".into(), "##.#".into()],
+        };
+
+        let states = map.to_states().unwrap();
+        assert_eq!((states.width(), states.height()), (4, 3));
+        // First row is the top of the map.
+        assert!(*states.get(0, 2) && *states.get(1, 2) == false);
+        assert!(*states.get(1, 0));
+
+        let exported = TerrainMapAsset::new(&states, &map.config, map.seed);
+        assert_eq!(exported.rows, map.rows);
+        assert_eq!(exported.config.size, UVec2::new(4, 3));
+
+        let ron = exported.to_ron().unwrap();
+        let imported: TerrainMapAsset = ron::de::from_str(&ron).unwrap();
+        assert_eq!(imported.rows, map.rows);
+        assert_eq!(imported.seed, Some(42));
+    }
+
+    #[test]
+    fn size_follows_rows() {
+        let map = TerrainMapAsset {
+            seed: None,
+            config: config(),
+            rows: vec!["#....".into(), "....#".into()],
+        };
+        assert_ne!(map.config.size, UVec2::new(5, 2));
+
+        let states = map.to_states().unwrap();
+        assert_eq!(map.sized_config(&states).size, UVec2::new(5, 2));
+    }
+
+    #[test]
+    fn reject_malformed_rows() {
+        let mut map = TerrainMapAsset {
+            seed: None,
+            config: config(),
+            rows: vec!["#.".into(), "#".into()],
+        };
+        assert!(matches!(
+            map.to_states(),
+            Err(TerrainMapError::RowWidth { row: 1, .. })
+        ));
+
+        map.rows = vec!["#x".into()];
+        assert!(matches!(
+            map.to_states(),
+            Err(TerrainMapError::InvalidTile { tile: 'x', .. })
+        ));
+    }
+
+    #[test]
+    fn shipped_maps_are_playable() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/../../assets/levels/terrain");
+
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            let bytes = std::fs::read(&path).unwrap();
+            let map: TerrainMapAsset = ron::de::from_bytes(&bytes).unwrap();
+
+            let states = map.to_states().unwrap();
+            let config = map.sized_config(&states);
+
+            // Both bases are empty and connected.
+            let [base_a, base_b] = TerrainStates::base_tiles(&config);
+            let reachable = states.reachable_from(base_a);
+            assert!(
+                *reachable.get(base_b.x as usize, base_b.y as usize),
+                "{path:?}"
+            );
+
+            for (x, y, state) in states.iter() {
+                let mirrored = config
+                    .symmetry
+                    .mirror(UVec2::new(x as u32, y as u32), config.size);
+                assert_eq!(
+                    *state,
+                    *states.get(mirrored.x as usize, mirrored.y as usize),
+                    "{path:?} is not symmetric at ({x}, {y})"
+                );
+            }
+        }
+    }
+}
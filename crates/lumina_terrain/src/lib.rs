@@ -1,17 +1,24 @@
 use avian2d::prelude::*;
 use bevy::prelude::*;
+use bevy::utils::HashMap;
 use config::TerrainConfig;
 use lumina_common::prelude::*;
 use map::{Terrain, TileRef};
 use strum::EnumCount;
 
 pub mod config;
+pub mod file;
 pub mod map;
+pub mod pass;
 
 pub mod prelude {
     pub use crate::config::TerrainConfig;
-    pub use crate::map::{Terrain, TerrainStates, TerrainTiles, TileRef};
-    pub use crate::{ClearTerrain, GenerateTerrain};
+    pub use crate::file::{ExportTerrain, LoadTerrain, TerrainMapAsset};
+    pub use crate::map::{
+        Terrain, TerrainChunkOf, TerrainChunks, TerrainMapConfig, TerrainSeed, TerrainStates,
+        TileRef,
+    };
+    pub use crate::{ClearTerrain, DamageTile, DestroyTiles, GenerateTerrain, TilesDestroyed};
 }
 
 pub struct TerrainPlugin;
@@ -21,22 +28,25 @@ impl Plugin for TerrainPlugin {
         app.init_resource::<EntityPools<TerrainType>>()
             .add_event::<GenerateTerrain>()
             .add_event::<ClearTerrain>()
-            .add_plugins(config::TerrainConfigPlugin)
+            .add_event::<DamageTile>()
+            .add_event::<DestroyTiles>()
+            .add_event::<TilesDestroyed>()
+            .add_plugins((config::TerrainConfigPlugin, file::TerrainFilePlugin))
             .add_systems(Startup, setup)
-            .add_systems(Update, (clear_terrain, generate_terrain).chain());
+            .add_systems(
+                Update,
+                (clear_terrain, generate_terrain, damage_tiles, destroy_tiles).chain(),
+            );
     }
 }
 
 fn setup(
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     palette: Res<ColorPalette>,
 ) {
     commands.insert_resource(TileRef {
-        mesh: meshes.add(Rectangle::new(1.0, 1.0)),
         material: materials.add(palette.base1),
-        collider: Collider::rectangle(1.0, 1.0),
     })
 }
 
@@ -67,6 +77,41 @@ fn generate_terrain(
     }
 }
 
+fn damage_tiles(
+    mut terrain: Terrain,
+    mut evr_damage: EventReader<DamageTile>,
+    mut evw_destroyed: EventWriter<TilesDestroyed>,
+) {
+    let mut destroyed = HashMap::<Entity, Vec<UVec2>>::new();
+    for damage in evr_damage.read() {
+        if let Some(tile) = terrain.damage_tile(damage.entity, damage.position, damage.damage) {
+            destroyed.entry(damage.entity).or_default().push(tile);
+        }
+    }
+
+    evw_destroyed.send_batch(
+        destroyed
+            .into_iter()
+            .map(|(entity, tiles)| TilesDestroyed { entity, tiles }),
+    );
+}
+
+fn destroy_tiles(
+    mut terrain: Terrain,
+    mut evr_destroy: EventReader<DestroyTiles>,
+    mut evw_destroyed: EventWriter<TilesDestroyed>,
+) {
+    for destroy in evr_destroy.read() {
+        let tiles = terrain.destroy_tiles(destroy.entity, &destroy.tiles);
+        if tiles.is_empty() == false {
+            evw_destroyed.send(TilesDestroyed {
+                entity: destroy.entity,
+                tiles,
+            });
+        }
+    }
+}
+
 #[derive(Event, Debug, Clone, Copy)]
 pub struct GenerateTerrain {
     pub seed: u32,
@@ -79,7 +124,49 @@ pub struct GenerateTerrain {
 #[derive(Event, Debug, Deref, Clone, Copy)]
 pub struct ClearTerrain(pub Entity);
 
+/// Damage the tile closest to a position, only applies to
+/// maps with destructible tiles (see [`config::TerrainConfigAsset::tile_health`]).
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DamageTile {
+    /// The entity holding the [`map::TerrainMapBundle`].
+    pub entity: Entity,
+    /// Position of the hit (in world space).
+    pub position: Vec2,
+    pub damage: f32,
+}
+
+/// Destroy tiles regardless of their health.
+#[derive(Event, Debug, Clone)]
+pub struct DestroyTiles {
+    /// The entity holding the [`map::TerrainMapBundle`].
+    pub entity: Entity,
+    pub tiles: Vec<UVec2>,
+}
+
+/// Sent when tiles are destroyed either by [`DamageTile`] or [`DestroyTiles`].
+#[derive(Event, Debug, Clone)]
+pub struct TilesDestroyed {
+    /// The entity holding the [`map::TerrainMapBundle`].
+    pub entity: Entity,
+    pub tiles: Vec<UVec2>,
+}
+
 #[derive(EnumCount)]
 pub enum TerrainType {
-    Tile,
+    Chunk,
+}
+
+/// Create states from rows of characters where `#` is filled,
+/// the first row is at `y = 0`.
+#[cfg(test)]
+pub(crate) fn states_from_rows(rows: &[&str]) -> Vec2d<bool> {
+    let mut states = Vec2d::new_from_default(rows[0].len(), rows.len());
+
+    for (y, row) in rows.iter().enumerate() {
+        for (x, c) in row.chars().enumerate() {
+            states.set(x, y, c == '#');
+        }
+    }
+
+    states
 }
@@ -1,5 +1,7 @@
 use avian2d::prelude::*;
 use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
 use bevy::sprite::Mesh2dHandle;
 use bevy_transform_interpolation::NoTranslationInterpolation;
 use lumina_common::prelude::*;
@@ -11,17 +13,28 @@ use crate::{GenerateTerrain, TerrainType};
 #[derive(bevy::ecs::system::SystemParam)]
 pub struct Terrain<'w, 's> {
     commands: Commands<'w, 's>,
-    pub q_maps: Query<'w, 's, (&'static mut TerrainTiles, &'static mut TerrainStates)>,
+    pub q_maps: Query<'w, 's, (&'static mut TerrainChunks, &'static mut TerrainStates)>,
+    pub q_healths: Query<'w, 's, &'static mut TerrainTileHealths>,
+    pub q_configs: Query<'w, 's, &'static TerrainMapConfig>,
+    pub q_global_transforms: Query<'w, 's, &'static GlobalTransform>,
     pub pools: ResMut<'w, EntityPools<TerrainType>>,
+    pub meshes: ResMut<'w, Assets<Mesh>>,
     pub tile_ref: Res<'w, TileRef>,
 }
 
 impl Terrain<'_, '_> {
+    /// World space translation of the holder entity, chunks are placed relative to it.
+    pub fn holder_translation(&self, map_entity: Entity) -> Vec2 {
+        self.q_global_transforms
+            .get(map_entity)
+            .map_or(Vec2::ZERO, |transform| transform.translation().xy())
+    }
+
     /// Returns true if successfully cleared the terrain and false if failed to find the entity.
     pub fn clear_terrain(&mut self, entity: Entity) -> bool {
-        if let Ok((mut tiles, mut states)) = self.q_maps.get_mut(entity) {
-            for TerrainTile { entity, .. } in tiles.drain(..) {
-                self.pools[TerrainType::Tile as usize].set_unused(entity);
+        if let Ok((mut chunks, mut states)) = self.q_maps.get_mut(entity) {
+            for TerrainChunk { entity, .. } in chunks.drain(..) {
+                self.pools[TerrainType::Chunk as usize].set_unused(entity);
                 self.commands
                     .entity(entity)
                     .remove::<(RigidBody, Collider)>()
@@ -41,72 +54,508 @@ impl Terrain<'_, '_> {
         config: &TerrainConfigAsset,
         gen: &GenerateTerrain,
     ) {
-        self.clear_terrain(map_entity);
         let states = TerrainStates::new_map(config, gen);
-        let mut tiles = TerrainTiles::default();
+        self.spawn_terrain(map_entity, states, config, gen.layers, gen.world_id);
+        self.commands
+            .entity(map_entity)
+            .insert(TerrainSeed(gen.seed));
+    }
 
-        // Spawn the tiles.
-        for (x, y, &state) in states.iter() {
-            if state == false {
+    /// Spawn the chunks of already existing terrain states (e.g. loaded from a
+    /// [`TerrainMapAsset`](crate::file::TerrainMapAsset)) into the holder entity.
+    pub fn spawn_terrain(
+        &mut self,
+        map_entity: Entity,
+        states: TerrainStates,
+        config: &TerrainConfigAsset,
+        layers: CollisionLayers,
+        world_id: WorldIdx,
+    ) {
+        self.clear_terrain(map_entity);
+        self.commands.entity(map_entity).remove::<TerrainSeed>();
+        let mut chunks = TerrainChunks::default();
+
+        let chunk_size = config.chunk_size.max(1);
+        let chunk_count = (config.size + chunk_size - 1) / chunk_size;
+        // Chunks are not children of the holder.
+        let translation = self.holder_translation(map_entity);
+
+        // Spawn the chunks.
+        for chunk_y in 0..chunk_count.y {
+            for chunk_x in 0..chunk_count.x {
+                let min = UVec2::new(chunk_x, chunk_y) * chunk_size;
+                let max = (min + chunk_size).min(config.size);
+                let rects = greedy_rects(&states, min, max);
+
+                // Nothing to spawn in an empty chunk.
+                if rects.is_empty() {
+                    continue;
+                }
+
+                // Use pool if exists.
+                let entity = self.pools[TerrainType::Chunk as usize]
+                    .get_unused_or_spawn(|| self.commands.spawn_empty().id());
+
+                let origin = translation + min.as_vec2() * config.tile_size;
+                let mesh = self.meshes.add(chunk_mesh(&rects, min, config.tile_size));
+
+                self.commands.entity(entity).insert((
+                    ChunkBundle::new(&self.tile_ref, mesh, origin),
+                    ChunkColliderBundle::new(
+                        chunk_collider(&rects, min, config.tile_size),
+                        layers,
+                        world_id,
+                    ),
+                    TerrainChunkOf(map_entity),
+                ));
+
+                chunks.push(TerrainChunk {
+                    entity,
+                    min,
+                    max,
+                    rect_count: rects.len(),
+                });
+            }
+        }
+
+        // Tiles are only destructible if they have health.
+        match config.tile_health {
+            Some(health) => {
+                let healths = Vec2d::new(states.width(), states.height(), health);
+                self.commands
+                    .entity(map_entity)
+                    .insert(TerrainTileHealths(healths));
+            }
+            None => {
+                self.commands
+                    .entity(map_entity)
+                    .remove::<TerrainTileHealths>();
+            }
+        }
+
+        // Inserts the terrain map into the holder entity.
+        self.commands.entity(map_entity).insert(TerrainMapBundle {
+            states,
+            chunks,
+            config: TerrainMapConfig(config.clone()),
+        });
+    }
+
+    /// Damage the filled tile closest to `position` (in world space).
+    /// Returns the tile if it has been destroyed.
+    pub fn damage_tile(
+        &mut self,
+        map_entity: Entity,
+        position: Vec2,
+        damage: f32,
+    ) -> Option<UVec2> {
+        let translation = self.holder_translation(map_entity);
+        let tile_size = self.q_configs.get(map_entity).ok()?.tile_size;
+        let (_, states) = self.q_maps.get(map_entity).ok()?;
+        let mut healths = self.q_healths.get_mut(map_entity).ok()?;
+
+        // The hit position might lie slightly outside of the tile that was hit.
+        let tile_position = (position - translation) / tile_size;
+        let center = tile_position.round().as_ivec2();
+        let tile = (-1..=1)
+            .flat_map(|y| (-1..=1).map(move |x| center + IVec2::new(x, y)))
+            .filter(|tile| {
+                tile.cmpge(IVec2::ZERO).all()
+                    && tile.x < states.width() as i32
+                    && tile.y < states.height() as i32
+                    && *states.get(tile.x as usize, tile.y as usize)
+            })
+            .min_by(|a, b| {
+                let a = a.as_vec2().distance_squared(tile_position);
+                let b = b.as_vec2().distance_squared(tile_position);
+                a.total_cmp(&b)
+            })?
+            .as_uvec2();
+
+        let (x, y) = (tile.x as usize, tile.y as usize);
+        let health = *healths.get(x, y) - damage;
+        healths.set(x, y, health);
+
+        match health <= 0.0 {
+            true => self.destroy_tiles(map_entity, &[tile]).first().copied(),
+            false => None,
+        }
+    }
+
+    /// Remove filled tiles and rebuild the chunks they belong to.
+    /// Chunks without any filled tiles left are returned to the pool.
+    ///
+    /// Returns the tiles that were actually destroyed.
+    pub fn destroy_tiles(&mut self, map_entity: Entity, tiles: &[UVec2]) -> Vec<UVec2> {
+        let Ok(tile_size) = self
+            .q_configs
+            .get(map_entity)
+            .map(|config| config.tile_size)
+        else {
+            return Vec::new();
+        };
+        let Ok((mut chunks, mut states)) = self.q_maps.get_mut(map_entity) else {
+            return Vec::new();
+        };
+
+        let mut destroyed = Vec::with_capacity(tiles.len());
+        for &tile in tiles {
+            let (x, y) = (tile.x as usize, tile.y as usize);
+            if x >= states.width() || y >= states.height() || *states.get(x, y) == false {
                 continue;
             }
 
-            // Use pool if exists.
-            let entity = self.pools[TerrainType::Tile as usize]
-                .get_unused_or_spawn(|| self.commands.spawn_empty().id());
+            states.set(x, y, false);
+            destroyed.push(tile);
+        }
+
+        chunks.retain_mut(|chunk| {
+            if destroyed.iter().any(|&tile| chunk.contains(tile)) == false {
+                return true;
+            }
+
+            let rects = greedy_rects(&states, chunk.min, chunk.max);
+            if rects.is_empty() {
+                self.pools[TerrainType::Chunk as usize].set_unused(chunk.entity);
+                self.commands
+                    .entity(chunk.entity)
+                    .remove::<(RigidBody, Collider)>()
+                    .insert(Visibility::Hidden);
+                return false;
+            }
 
-            self.commands.entity(entity).insert(TileBundle::new(
-                &self.tile_ref,
-                Vec2::new(config.tile_size * x as f32, config.tile_size * y as f32),
-                config.tile_size,
+            let mesh = self.meshes.add(chunk_mesh(&rects, chunk.min, tile_size));
+            self.commands.entity(chunk.entity).insert((
+                Mesh2dHandle(mesh),
+                chunk_collider(&rects, chunk.min, tile_size),
             ));
+            chunk.rect_count = rects.len();
 
-            let filled_neighbor_count = states
-                .get_neighbors(x, y)
-                .iter()
-                // Is filled or it's nothing beyond (at the border).
-                .filter(|&&s| s.is_some_and(|&s| s) || s.is_none())
-                .count();
+            true
+        });
 
-            if filled_neighbor_count < 4 {
-                self.commands
-                    .entity(entity)
-                    .insert(TileColliderBundle::new(&self.tile_ref, gen));
+        destroyed
+    }
+}
+
+/// Merge filled tiles within `min..max` into as few rectangles as possible (greedy meshing).
+/// Rectangles are in tile coordinates with an inclusive `min` and exclusive `max`.
+pub fn greedy_rects(states: &Vec2d<bool>, min: UVec2, max: UVec2) -> Vec<URect> {
+    let size = max - min;
+    let mut merged = Vec2d::new_from_default(size.x as usize, size.y as usize);
+    let mut rects = Vec::new();
+
+    let is_free = |merged: &Vec2d<bool>, x: u32, y: u32| {
+        *states.get((min.x + x) as usize, (min.y + y) as usize)
+            && *merged.get(x as usize, y as usize) == false
+    };
+
+    for y in 0..size.y {
+        for x in 0..size.x {
+            if is_free(&merged, x, y) == false {
+                continue;
+            }
+
+            // Extend to the right as far as possible.
+            let mut width = 1;
+            while x + width < size.x && is_free(&merged, x + width, y) {
+                width += 1;
+            }
+
+            // Extend upwards while the whole row is available.
+            let mut height = 1;
+            while y + height < size.y && (x..x + width).all(|rx| is_free(&merged, rx, y + height)) {
+                height += 1;
+            }
+
+            for my in y..y + height {
+                for mx in x..x + width {
+                    merged.set(mx as usize, my as usize, true);
+                }
             }
 
-            tiles.push(TerrainTile { entity, x, y });
+            rects.push(URect::new(
+                min.x + x,
+                min.y + y,
+                min.x + x + width,
+                min.y + y + height,
+            ));
         }
+    }
 
-        // Inserts the terrain map into the holder entity.
-        self.commands
-            .entity(map_entity)
-            .insert(TerrainMapBundle { states, tiles });
+    rects
+}
+
+/// Convert a tile rectangle into a local rectangle relative to the chunk origin.
+/// Tiles are centered on their coordinates, hence the half tile offset.
+fn local_rect(rect: &URect, chunk_min: UVec2, tile_size: f32) -> Rect {
+    let min = (rect.min.as_vec2() - chunk_min.as_vec2() - 0.5) * tile_size;
+    let max = (rect.max.as_vec2() - chunk_min.as_vec2() - 0.5) * tile_size;
+
+    Rect::from_corners(min, max)
+}
+
+/// Batch all rectangles of a chunk into a single mesh.
+fn chunk_mesh(rects: &[URect], chunk_min: UVec2, tile_size: f32) -> Mesh {
+    let mut positions = Vec::with_capacity(rects.len() * 4);
+    let mut uvs = Vec::with_capacity(rects.len() * 4);
+    let mut indices = Vec::with_capacity(rects.len() * 6);
+
+    for rect in rects {
+        let rect = local_rect(rect, chunk_min, tile_size);
+        let offset = positions.len() as u32;
+
+        positions.extend([
+            [rect.min.x, rect.min.y, 0.0],
+            [rect.max.x, rect.min.y, 0.0],
+            [rect.max.x, rect.max.y, 0.0],
+            [rect.min.x, rect.max.y, 0.0],
+        ]);
+        uvs.extend([[0.0, 1.0], [1.0, 1.0], [1.0, 0.0], [0.0, 0.0]]);
+        indices.extend([
+            offset,
+            offset + 1,
+            offset + 2,
+            offset,
+            offset + 2,
+            offset + 3,
+        ]);
     }
+
+    let normals = vec![[0.0, 0.0, 1.0]; positions.len()];
+
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+    .with_inserted_indices(Indices::U32(indices))
+}
+
+/// Compound collider made out of all rectangles of a chunk.
+fn chunk_collider(rects: &[URect], chunk_min: UVec2, tile_size: f32) -> Collider {
+    Collider::compound(
+        rects
+            .iter()
+            .map(|rect| {
+                let rect = local_rect(rect, chunk_min, tile_size);
+                (
+                    Position(rect.center()),
+                    Rotation::default(),
+                    Collider::rectangle(rect.width(), rect.height()),
+                )
+            })
+            .collect(),
+    )
 }
 
 #[derive(Bundle)]
 pub struct TerrainMapBundle {
     pub states: TerrainStates,
-    pub tiles: TerrainTiles,
+    pub chunks: TerrainChunks,
+    pub config: TerrainMapConfig,
 }
 
+/// The config that the terrain was generated or loaded with.
+/// Its size always matches the [`TerrainStates`].
+#[derive(Component, Debug, Deref, Clone)]
+pub struct TerrainMapConfig(pub TerrainConfigAsset);
+
 #[derive(Component, Default, Debug, Deref, DerefMut)]
-pub struct TerrainTiles(Vec<TerrainTile>);
+pub struct TerrainChunks(Vec<TerrainChunk>);
 
+/// A batch of tiles sharing a single mesh and collider.
 #[derive(Debug)]
-pub struct TerrainTile {
+pub struct TerrainChunk {
     pub entity: Entity,
-    pub x: usize,
-    pub y: usize,
+    /// Inclusive minimum tile coordinate.
+    pub min: UVec2,
+    /// Exclusive maximum tile coordinate.
+    pub max: UVec2,
+    /// Number of merged rectangles in the chunk.
+    pub rect_count: usize,
 }
 
+impl TerrainChunk {
+    pub fn contains(&self, tile: UVec2) -> bool {
+        tile.cmpge(self.min).all() && tile.cmplt(self.max).all()
+    }
+}
+
+/// The map holder entity that a chunk belongs to.
+#[derive(Component, Debug, Deref, Clone, Copy)]
+pub struct TerrainChunkOf(pub Entity);
+
+/// Remaining health of every tile, only exists if
+/// [`TerrainConfigAsset::tile_health`] is set.
+///
+/// Tiles are merged into chunk meshes and colliders instead of being
+/// spawned as entities, so they can't carry the `Health` component from
+/// `lumina_shared` (which depends on this crate, not the other way around).
+/// Tiles are damaged through [`crate::DamageTile`] and gameplay or effects
+/// should react to [`crate::TilesDestroyed`] instead of health changes.
+#[derive(Component, Debug, Deref, DerefMut)]
+pub struct TerrainTileHealths(Vec2d<f32>);
+
 #[derive(Component, Debug, Deref, DerefMut)]
 pub struct TerrainStates(Vec2d<bool>);
 
+impl From<Vec2d<bool>> for TerrainStates {
+    fn from(states: Vec2d<bool>) -> Self {
+        Self(states)
+    }
+}
+
+/// The seed that the terrain was generated from, only exists if
+/// the terrain was generated using [`GenerateTerrain`].
+#[derive(Component, Debug, Deref, Clone, Copy)]
+pub struct TerrainSeed(pub u32);
+
 impl TerrainStates {
+    /// Generate a map with reserved team bases that are guaranteed to be connected.
+    ///
+    /// The map is regenerated with a derived seed (up to [`TerrainConfigAsset::max_attempts`])
+    /// if the bases are not reachable from each other. If every attempt fails,
+    /// a corridor is carved between the bases of the last attempt.
     pub fn new_map(config: &TerrainConfigAsset, gen: &GenerateTerrain) -> Self {
-        let mut states = Vec2d::new_from_default(config.size.x as usize, config.size.y as usize);
         let mut hash = XorShift32::new(gen.seed);
+        let mut seed = gen.seed;
+        let [base_a, base_b] = Self::base_tiles(config);
+
+        for attempt in 0..config.max_attempts.max(1) {
+            let mut states = Self::new_noise_map(config, seed);
+
+            let mut pass_hash = XorShift32::new(seed);
+            for pass in config.passes.iter() {
+                pass.apply(&mut states, pass_hash.next_u32());
+            }
+
+            Self::reserve_bases(&mut states, config);
+            config.symmetry.apply(&mut states);
+
+            let mut map = Self(states);
+            if *map
+                .reachable_from(base_a)
+                .get(base_b.x as usize, base_b.y as usize)
+            {
+                map.fill_unreachable(base_a);
+                return map;
+            }
+
+            if attempt + 1 == config.max_attempts.max(1) {
+                warn!(
+                    "Unable to connect bases after {} attempts (seed: {}), carving a corridor.",
+                    config.max_attempts, gen.seed
+                );
+                Self::carve_corridor(&mut map, config);
+                config.symmetry.apply(&mut map);
+                map.fill_unreachable(base_a);
+                return map;
+            }
+
+            seed = hash.next_u32();
+        }
+
+        unreachable!("At least one attempt is always made.")
+    }
+
+    /// Center tile of the base of each team, mirrored according to [`TerrainConfigAsset::symmetry`].
+    pub fn base_tiles(config: &TerrainConfigAsset) -> [UVec2; 2] {
+        let rect = Self::base_rect(config);
+        let center = rect.min + rect.size() / 2;
+
+        [center, config.symmetry.mirror(center, config.size)]
+    }
+
+    /// Tile rectangle of the first base (inclusive `min`, exclusive `max`).
+    fn base_rect(config: &TerrainConfigAsset) -> URect {
+        let base_size = config.base_size.min(config.size);
+        let min = UVec2::new(
+            config.noise_surr_width.min(config.size.x - base_size.x),
+            (config.size.y - base_size.y) / 2,
+        );
+
+        URect::from_corners(min, min + base_size)
+    }
+
+    /// Clear the base areas at both ends of the map.
+    fn reserve_bases(states: &mut Vec2d<bool>, config: &TerrainConfigAsset) {
+        let rect = Self::base_rect(config);
+
+        for y in rect.min.y..rect.max.y {
+            for x in rect.min.x..rect.max.x {
+                let tile = UVec2::new(x, y);
+                let mirrored = config.symmetry.mirror(tile, config.size);
+
+                states.set(x as usize, y as usize, false);
+                states.set(mirrored.x as usize, mirrored.y as usize, false);
+            }
+        }
+    }
+
+    /// Carve a straight corridor as tall as the base through the middle of the map.
+    fn carve_corridor(states: &mut Vec2d<bool>, config: &TerrainConfigAsset) {
+        let rect = Self::base_rect(config);
+
+        for y in rect.min.y..rect.max.y {
+            for x in rect.min.x..config.size.x - rect.min.x {
+                states.set(x as usize, y as usize, false);
+            }
+        }
+    }
+
+    /// Flood fill all empty tiles reachable from `start` (4-connected).
+    pub fn reachable_from(&self, start: UVec2) -> Vec2d<bool> {
+        let mut reachable = Vec2d::new_from_default(self.width(), self.height());
+        let (x, y) = (start.x as usize, start.y as usize);
+        if x >= self.width() || y >= self.height() || *self.get(x, y) {
+            return reachable;
+        }
+
+        let mut stack = vec![(x, y)];
+        reachable.set(x, y, true);
+
+        while let Some((x, y)) = stack.pop() {
+            let neighbors = [
+                (x.wrapping_sub(1), y),
+                (x + 1, y),
+                (x, y.wrapping_sub(1)),
+                (x, y + 1),
+            ];
+
+            for (nx, ny) in neighbors {
+                if nx >= self.width()
+                    || ny >= self.height()
+                    || *self.get(nx, ny)
+                    || *reachable.get(nx, ny)
+                {
+                    continue;
+                }
+
+                reachable.set(nx, ny, true);
+                stack.push((nx, ny));
+            }
+        }
+
+        reachable
+    }
+
+    /// Fill every empty pocket that can not be reached from `start`.
+    fn fill_unreachable(&mut self, start: UVec2) {
+        let reachable = self.reachable_from(start);
+
+        for (x, y, state) in self.iter_mut() {
+            if *reachable.get(x, y) == false {
+                *state = true;
+            }
+        }
+    }
+
+    fn new_noise_map(config: &TerrainConfigAsset, seed: u32) -> Vec2d<bool> {
+        let mut states = Vec2d::new_from_default(config.size.x as usize, config.size.y as usize);
+        let mut hash = XorShift32::new(seed);
 
         // Seeds will go from 0 -> 1000.0
         let left_seed = ((hash.next_u32() % 1000000) as f32) * 0.01;
@@ -183,7 +632,7 @@ impl TerrainStates {
         //     }
         // }
 
-        Self(states)
+        states
     }
 
     pub fn get_map_corners_without_noise_surr(config: &TerrainConfigAsset) -> (Vec2, Vec2) {
@@ -205,27 +654,24 @@ impl TerrainStates {
 
 #[derive(Resource)]
 pub struct TileRef {
-    pub mesh: Handle<Mesh>,
     pub material: Handle<ColorMaterial>,
-    pub collider: Collider,
 }
 
 #[derive(Bundle)]
-pub struct TileBundle {
+pub struct ChunkBundle {
     pub color_mesh: ColorMesh2dBundle,
     pub position: Position,
     pub no_translation_interp: NoTranslationInterpolation,
 }
 
-impl TileBundle {
-    pub fn new(tile_ref: &TileRef, position: Vec2, size: f32) -> Self {
+impl ChunkBundle {
+    pub fn new(tile_ref: &TileRef, mesh: Handle<Mesh>, position: Vec2) -> Self {
         Self {
             color_mesh: ColorMesh2dBundle {
-                mesh: Mesh2dHandle(tile_ref.mesh.clone()),
+                mesh: Mesh2dHandle(mesh),
                 material: tile_ref.material.clone(),
                 visibility: Visibility::Inherited,
-                transform: Transform::from_xyz(position.x, position.y, 0.0)
-                    .with_scale(Vec3::splat(size)),
+                transform: Transform::from_xyz(position.x, position.y, 0.0),
                 ..default()
             },
             position: Position(position),
@@ -235,20 +681,264 @@ impl TileBundle {
 }
 
 #[derive(Bundle)]
-pub struct TileColliderBundle {
+pub struct ChunkColliderBundle {
     pub collider: Collider,
     pub rigidbody: RigidBody,
     pub layers: CollisionLayers,
     pub world_id: WorldIdx,
 }
 
-impl TileColliderBundle {
-    pub fn new(tile_ref: &TileRef, gen: &GenerateTerrain) -> Self {
+impl ChunkColliderBundle {
+    pub fn new(collider: Collider, layers: CollisionLayers, world_id: WorldIdx) -> Self {
         Self {
-            collider: tile_ref.collider.clone(),
+            collider,
             rigidbody: RigidBody::Static,
-            layers: gen.layers,
-            world_id: gen.world_id,
+            layers,
+            world_id,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use crate::config::TerrainSymmetry;
+    use crate::pass::TerrainPass;
+    use crate::states_from_rows;
+
+    use super::*;
+
+    fn config(symmetry: TerrainSymmetry, passes: Vec<TerrainPass>) -> TerrainConfigAsset {
+        TerrainConfigAsset {
+            size: UVec2::new(41, 25),
+            tile_size: 1.0,
+            chunk_size: 16,
+            noise_surr_width: 3,
+            base_size: UVec2::new(5, 5),
+            noise_scale: 0.03,
+            noise_threshold: 0.4,
+            gradient_pow: 1.3,
+            symmetry,
+            max_attempts: 4,
+            passes,
+            tile_health: None,
+        }
+    }
+
+    fn generate(config: &TerrainConfigAsset, seed: u32) -> TerrainStates {
+        TerrainStates::new_map(
+            config,
+            &GenerateTerrain {
+                seed,
+                entity: Entity::PLACEHOLDER,
+                layers: CollisionLayers::default(),
+                world_id: WorldIdx(None),
+            },
+        )
+    }
+
+    /// Every empty tile must be reachable from both bases.
+    fn assert_connected(states: &TerrainStates, config: &TerrainConfigAsset) {
+        let [base_a, base_b] = TerrainStates::base_tiles(config);
+        let reachable = states.reachable_from(base_a);
+
+        assert!(*reachable.get(base_b.x as usize, base_b.y as usize));
+        for (x, y, state) in states.iter() {
+            assert_eq!(*state, *reachable.get(x, y) == false, "({x}, {y})");
+        }
+    }
+
+    #[test]
+    fn reachable_from_stops_at_walls() {
+        let mut states = TerrainStates::from(Vec2d::new_from_default(5, 3));
+        for y in 0..3 {
+            states.set(2, y, true);
         }
+
+        let reachable = states.reachable_from(UVec2::new(0, 1));
+        assert!(*reachable.get(1, 2));
+        assert!(*reachable.get(2, 1) == false);
+        assert!(*reachable.get(4, 1) == false);
+
+        // Diagonal gaps are not connected.
+        states.set(2, 0, false);
+        states.set(3, 0, true);
+        states.set(3, 1, true);
+        let reachable = states.reachable_from(UVec2::new(0, 1));
+        assert!(*reachable.get(2, 0));
+        assert!(*reachable.get(4, 2) == false);
+
+        // Opening the wall connects both sides.
+        states.set(2, 1, false);
+        states.set(3, 1, false);
+        assert!(*states.reachable_from(UVec2::new(0, 1)).get(4, 2));
+
+        // Filled or out of bound starts reach nothing.
+        assert!(states
+            .reachable_from(UVec2::new(3, 0))
+            .iter()
+            .all(|(_, _, r)| *r == false));
+        assert!(states
+            .reachable_from(UVec2::new(9, 9))
+            .iter()
+            .all(|(_, _, r)| *r == false));
+    }
+
+    #[test]
+    fn generated_maps_are_connected_and_symmetric() {
+        let passes = vec![
+            TerrainPass::InteriorNoise {
+                scale: 0.15,
+                threshold: 0.72,
+            },
+            TerrainPass::CellularAutomata {
+                iterations: 2,
+                birth_limit: 5,
+                death_limit: 4,
+            },
+        ];
+
+        for symmetry in [TerrainSymmetry::Axis, TerrainSymmetry::Point] {
+            let config = config(symmetry, passes.clone());
+
+            for seed in 0..8 {
+                let states = generate(&config, seed);
+                assert_connected(&states, &config);
+
+                for (x, y, state) in states.iter() {
+                    let mirrored = symmetry.mirror(UVec2::new(x as u32, y as u32), config.size);
+                    assert_eq!(
+                        *state,
+                        *states.get(mirrored.x as usize, mirrored.y as usize)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn corridor_connects_blocked_bases() {
+        // Fill everything except the reserved bases.
+        let passes = vec![TerrainPass::InteriorNoise {
+            scale: 0.1,
+            threshold: -1.0,
+        }];
+
+        for symmetry in [
+            TerrainSymmetry::None,
+            TerrainSymmetry::Axis,
+            TerrainSymmetry::Point,
+        ] {
+            let config = config(symmetry, passes.clone());
+            let states = generate(&config, 5);
+
+            assert_connected(&states, &config);
+        }
+    }
+
+    fn terrain_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Mesh>()
+            .init_asset::<ColorMaterial>()
+            .init_resource::<EntityPools<TerrainType>>()
+            .insert_resource(TileRef {
+                material: Handle::default(),
+            });
+
+        app
+    }
+
+    #[test]
+    fn damage_tiles_relative_to_holder() {
+        let mut app = terrain_app();
+        let mut config = config(TerrainSymmetry::None, Vec::new());
+        config.size = UVec2::new(6, 4);
+        config.chunk_size = 4;
+        config.tile_size = 10.0;
+        config.tile_health = Some(30.0);
+
+        let holder = app
+            .world_mut()
+            .spawn(GlobalTransform::from_xyz(100.0, 50.0, 0.0))
+            .id();
+
+        #[rustfmt::skip]
+        let states = states_from_rows(&[
+            "##....",
+            "......",
+            "......",
+            ".....#",
+        ]);
+        app.world_mut()
+            .run_system_once(move |mut terrain: Terrain| {
+                terrain.spawn_terrain(
+                    holder,
+                    states.clone().into(),
+                    &config,
+                    CollisionLayers::default(),
+                    WorldIdx::default(),
+                );
+            });
+
+        let chunks = app.world().get::<TerrainChunks>(holder).unwrap();
+        assert_eq!(chunks.len(), 2);
+        let chunk_position = app.world().get::<Position>(chunks[1].entity).unwrap();
+        assert_eq!(chunk_position.0, Vec2::new(140.0, 50.0));
+
+        let mut damage = |position: Vec2| {
+            app.world_mut()
+                .run_system_once(move |mut terrain: Terrain| {
+                    terrain.damage_tile(holder, position, 20.0)
+                })
+        };
+
+        // Tile (5, 3) is at (150, 80) in world space, hits land slightly off the tile center.
+        assert_eq!(damage(Vec2::new(153.0, 78.0)), None);
+        assert_eq!(damage(Vec2::new(146.0, 82.0)), Some(UVec2::new(5, 3)));
+        // Nothing left to damage around the destroyed tile.
+        assert_eq!(damage(Vec2::new(150.0, 80.0)), None);
+
+        let world = app.world();
+        let states = world.get::<TerrainStates>(holder).unwrap();
+        assert!(*states.get(5, 3) == false);
+        assert!(*states.get(0, 0) && *states.get(1, 0));
+        // The emptied chunk is returned to the pool.
+        assert_eq!(world.get::<TerrainChunks>(holder).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn destroy_only_filled_tiles() {
+        let mut app = terrain_app();
+        let config = config(TerrainSymmetry::None, Vec::new());
+        let holder = app.world_mut().spawn_empty().id();
+
+        app.world_mut()
+            .run_system_once(move |mut terrain: Terrain| {
+                let mut states = Vec2d::new_from_default(41, 25);
+                states.set(3, 4, true);
+                states.set(4, 4, true);
+                terrain.spawn_terrain(
+                    holder,
+                    states.into(),
+                    &config,
+                    CollisionLayers::default(),
+                    WorldIdx::default(),
+                );
+            });
+
+        let destroyed = app
+            .world_mut()
+            .run_system_once(move |mut terrain: Terrain| {
+                terrain.destroy_tiles(
+                    holder,
+                    &[UVec2::new(3, 4), UVec2::new(5, 4), UVec2::new(99, 99)],
+                )
+            });
+
+        assert_eq!(destroyed, vec![UVec2::new(3, 4)]);
+        let states = app.world().get::<TerrainStates>(holder).unwrap();
+        assert!(*states.get(3, 4) == false && *states.get(4, 4));
     }
 }
@@ -0,0 +1,367 @@
+use bevy::prelude::*;
+use lumina_common::prelude::*;
+use noisy_bevy::*;
+use serde::{Deserialize, Serialize};
+
+/// A pass applied to the terrain states after the border noise is generated.
+/// Passes are applied in order and are deterministic from the seed.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub enum TerrainPass {
+    /// Place obstacles inside the arena using noise.
+    InteriorNoise {
+        /// Controls the frequency/detail of the noise.
+        scale: f32,
+        /// Any value above this threshold will have a tile placed.
+        threshold: f32,
+    },
+    /// Smooth the terrain using cellular automata (on the 8 surrounding tiles).
+    CellularAutomata {
+        iterations: u32,
+        /// An empty tile will be filled if it has at least this many filled neighbors.
+        birth_limit: u8,
+        /// A filled tile will be emptied if it has less than this many filled neighbors.
+        death_limit: u8,
+    },
+    /// Remove filled islands (4-connected) with less tiles than `min_size`.
+    /// Islands touching the map edges are never removed.
+    RemoveSmallIslands { min_size: usize },
+    /// Carve L-shaped corridors between random empty tiles.
+    CarveCorridors { count: u32, width: u32 },
+}
+
+impl TerrainPass {
+    pub fn apply(&self, states: &mut Vec2d<bool>, seed: u32) {
+        match *self {
+            TerrainPass::InteriorNoise { scale, threshold } => {
+                interior_noise(states, seed, scale, threshold)
+            }
+            TerrainPass::CellularAutomata {
+                iterations,
+                birth_limit,
+                death_limit,
+            } => {
+                for _ in 0..iterations {
+                    cellular_automata(states, birth_limit, death_limit);
+                }
+            }
+            TerrainPass::RemoveSmallIslands { min_size } => remove_small_islands(states, min_size),
+            TerrainPass::CarveCorridors { count, width } => {
+                carve_corridors(states, seed, count, width)
+            }
+        }
+    }
+}
+
+fn interior_noise(states: &mut Vec2d<bool>, seed: u32, scale: f32, threshold: f32) {
+    let mut hash = XorShift32::new(seed);
+    // Seeds will go from 0 -> 1000.0
+    let offset = Vec2::new(
+        ((hash.next_u32() % 1000000) as f32) * 0.01,
+        ((hash.next_u32() % 1000000) as f32) * 0.01,
+    );
+
+    for (x, y, state) in states.iter_mut() {
+        // Remap from -1.0 -> 1.0 to 0.0 -> 1.0
+        let noise = simplex_noise_2d(Vec2::new(x as f32, y as f32) * scale + offset) * 0.5 + 0.5;
+
+        if noise > threshold {
+            *state = true;
+        }
+    }
+}
+
+/// Number of filled tiles among the 8 surrounding tiles.
+/// Tiles beyond the edges are considered filled.
+fn filled_surrounding_count(states: &Vec2d<bool>, x: usize, y: usize) -> u8 {
+    let mut count = 0;
+
+    for dy in -1..=1_isize {
+        for dx in -1..=1_isize {
+            if dx == 0 && dy == 0 {
+                continue;
+            }
+
+            let nx = x.checked_add_signed(dx).filter(|&nx| nx < states.width());
+            let ny = y.checked_add_signed(dy).filter(|&ny| ny < states.height());
+
+            let filled = match (nx, ny) {
+                (Some(nx), Some(ny)) => *states.get(nx, ny),
+                _ => true,
+            };
+
+            if filled {
+                count += 1;
+            }
+        }
+    }
+
+    count
+}
+
+fn cellular_automata(states: &mut Vec2d<bool>, birth_limit: u8, death_limit: u8) {
+    let prev = states.clone();
+
+    for (x, y, state) in states.iter_mut() {
+        let count = filled_surrounding_count(&prev, x, y);
+
+        *state = match *state {
+            true => count >= death_limit,
+            false => count >= birth_limit,
+        };
+    }
+}
+
+fn remove_small_islands(states: &mut Vec2d<bool>, min_size: usize) {
+    let (width, height) = (states.width(), states.height());
+    let mut visited = Vec2d::new_from_default(width, height);
+
+    for y in 0..height {
+        for x in 0..width {
+            if *states.get(x, y) == false || *visited.get(x, y) {
+                continue;
+            }
+
+            // Flood fill the island.
+            let mut island = vec![(x, y)];
+            let mut stack = vec![(x, y)];
+            let mut touches_edge = false;
+            visited.set(x, y, true);
+
+            while let Some((x, y)) = stack.pop() {
+                touches_edge |= x == 0 || y == 0 || x == width - 1 || y == height - 1;
+
+                let neighbors = [
+                    (x.wrapping_sub(1), y),
+                    (x + 1, y),
+                    (x, y.wrapping_sub(1)),
+                    (x, y + 1),
+                ];
+
+                for (nx, ny) in neighbors {
+                    if nx >= width
+                        || ny >= height
+                        || *states.get(nx, ny) == false
+                        || *visited.get(nx, ny)
+                    {
+                        continue;
+                    }
+
+                    visited.set(nx, ny, true);
+                    island.push((nx, ny));
+                    stack.push((nx, ny));
+                }
+            }
+
+            if touches_edge == false && island.len() < min_size {
+                for (x, y) in island {
+                    states.set(x, y, false);
+                }
+            }
+        }
+    }
+}
+
+fn carve_corridors(states: &mut Vec2d<bool>, seed: u32, count: u32, width: u32) {
+    let empty_tiles = states
+        .iter()
+        .filter(|(_, _, &state)| state == false)
+        .map(|(x, y, _)| UVec2::new(x as u32, y as u32))
+        .collect::<Vec<_>>();
+
+    if empty_tiles.len() < 2 {
+        return;
+    }
+
+    let mut hash = XorShift32::new(seed);
+    let size = UVec2::new(states.width() as u32, states.height() as u32);
+    let half_width = width / 2;
+
+    let carve = |states: &mut Vec2d<bool>, tile: UVec2| {
+        let min = tile.saturating_sub(UVec2::splat(half_width));
+        let max = (min + width.max(1)).min(size);
+
+        for y in min.y..max.y {
+            for x in min.x..max.x {
+                states.set(x as usize, y as usize, false);
+            }
+        }
+    };
+
+    for _ in 0..count {
+        let start = empty_tiles[hash.next_u32() as usize % empty_tiles.len()];
+        let end = empty_tiles[hash.next_u32() as usize % empty_tiles.len()];
+
+        // Horizontal first, then vertical.
+        for x in start.x.min(end.x)..=start.x.max(end.x) {
+            carve(states, UVec2::new(x, start.y));
+        }
+        for y in start.y.min(end.y)..=start.y.max(end.y) {
+            carve(states, UVec2::new(end.x, y));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::states_from_rows;
+
+    use super::*;
+
+    fn filled_count(states: &Vec2d<bool>) -> usize {
+        states.iter().filter(|(_, _, &s)| s).count()
+    }
+
+    fn to_rows(states: &Vec2d<bool>) -> Vec<String> {
+        (0..states.height())
+            .map(|y| {
+                (0..states.width())
+                    .map(|x| match *states.get(x, y) {
+                        true => '#',
+                        false => '.',
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn seeds_change_interior_noise() {
+        let generate = |seed: u32| {
+            let mut states = Vec2d::new_from_default(32, 24);
+            TerrainPass::InteriorNoise {
+                scale: 0.1,
+                threshold: 0.6,
+            }
+            .apply(&mut states, seed);
+            to_rows(&states)
+        };
+
+        assert_eq!(generate(7), generate(7));
+        assert_ne!(generate(7), generate(8));
+    }
+
+    #[test]
+    fn carve_corridors_follows_seed() {
+        #[rustfmt::skip]
+        let rows = [
+            "########",
+            "#..#####",
+            "########",
+            "########",
+            "#####..#",
+            "########",
+        ];
+        let carve = |seed: u32| {
+            let mut states = states_from_rows(&rows);
+            TerrainPass::CarveCorridors { count: 1, width: 1 }.apply(&mut states, seed);
+            to_rows(&states)
+        };
+
+        // Horizontal first from (2, 1), then vertical down to (5, 4).
+        #[rustfmt::skip]
+        let expected = [
+            "########",
+            "#.....##",
+            "#####.##",
+            "#####.##",
+            "#####..#",
+            "########",
+        ];
+        assert_eq!(carve(2), expected);
+
+        // Horizontal first from (5, 4), then vertical up to (2, 1).
+        #[rustfmt::skip]
+        let expected = [
+            "########",
+            "#..#####",
+            "##.#####",
+            "##.#####",
+            "##.....#",
+            "########",
+        ];
+        assert_eq!(carve(3), expected);
+    }
+
+    #[test]
+    fn cellular_automata_smooths_noise() {
+        #[rustfmt::skip]
+        let mut states = states_from_rows(&[
+            "........",
+            "..#.....",
+            "........",
+            ".....##.",
+            ".....##.",
+            "........",
+        ]);
+
+        TerrainPass::CellularAutomata {
+            iterations: 1,
+            birth_limit: 5,
+            death_limit: 2,
+        }
+        .apply(&mut states, 0);
+
+        // Lonely tile is removed.
+        assert!(*states.get(2, 1) == false);
+        // The 2x2 block survives.
+        assert!(*states.get(5, 3) && *states.get(6, 4));
+    }
+
+    #[test]
+    fn cellular_automata_treats_edges_as_filled() {
+        let mut states = Vec2d::new_from_default(4, 4);
+
+        TerrainPass::CellularAutomata {
+            iterations: 1,
+            birth_limit: 5,
+            death_limit: 4,
+        }
+        .apply(&mut states, 0);
+
+        // Corners have 5 out of bound neighbors.
+        assert!(*states.get(0, 0) && *states.get(3, 3));
+        // Edges only have 3.
+        assert!(*states.get(1, 0) == false);
+    }
+
+    #[test]
+    fn remove_small_islands_keeps_edges_and_large_islands() {
+        #[rustfmt::skip]
+        let mut states = states_from_rows(&[
+            "#.......",
+            "#...###.",
+            "....###.",
+            "..#.....",
+            "........",
+        ]);
+
+        TerrainPass::RemoveSmallIslands { min_size: 3 }.apply(&mut states, 0);
+
+        // Edge island is kept even though it is small.
+        assert!(*states.get(0, 0) && *states.get(0, 1));
+        // Large island is kept.
+        assert!(*states.get(4, 1));
+        // Small interior island is removed.
+        assert!(*states.get(2, 3) == false);
+        assert_eq!(filled_count(&states), 8);
+    }
+
+    #[test]
+    fn carve_corridors_only_removes_tiles() {
+        #[rustfmt::skip]
+        let mut states = states_from_rows(&[
+            "..######",
+            "########",
+            "########",
+            "######..",
+        ]);
+        let before = filled_count(&states);
+
+        TerrainPass::CarveCorridors { count: 4, width: 1 }.apply(&mut states, 3);
+
+        assert!(filled_count(&states) <= before);
+        // Empty tiles stay empty.
+        assert!(*states.get(0, 0) == false);
+        assert!(*states.get(7, 3) == false);
+    }
+}